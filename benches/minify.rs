@@ -0,0 +1,51 @@
+//! Time to minify json, compared with parsing it and serializing the value
+//!
+//! Run with `cargo bench --bench minify`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+fn time(mut f: impl FnMut()) -> Duration {
+    let runs = 3;
+    let start = Instant::now();
+    for _ in 0..runs {
+        f();
+    }
+    start.elapsed() / runs
+}
+
+fn main() {
+    // the parser recurses per element, so records come in batches, and it
+    // copies the elements after each one, so the document is only 2 MB
+    let batches: Vec<_> = (0..200)
+        .map(|batch| {
+            let records: Vec<_> = (0..100)
+                .map(|i| {
+                    let id = batch * 100 + i;
+                    format!(
+                        "    {{\"id\": {}, \"name\": \"item {}\", \"price\": {}.50,\n     \"tags\": [\"a\", \"b\\n\"], \"stock\": null, \"active\": true}}",
+                        id,
+                        id,
+                        id % 1000
+                    )
+                })
+                .collect();
+            format!("  \"batch{}\": [\n{}\n  ]", batch, records.join(",\n"))
+        })
+        .collect();
+    let document = format!("{{\n{}\n}}", batches.join(",\n"));
+
+    let minify = time(|| {
+        black_box(wson::minify(black_box(&document)).unwrap());
+    });
+    let parse = time(|| {
+        let value = wson::parse(black_box(&document)).unwrap();
+        black_box(wson::to_string(&value).unwrap());
+    });
+    println!(
+        "{:>9} bytes: minify {:>12?}  parse and to_string {:>12?}",
+        document.len(),
+        minify,
+        parse
+    );
+}
@@ -0,0 +1,69 @@
+//! Time to parse numbers
+//!
+//! Run with `cargo bench --bench numbers`, and with `--features fast_float`
+//! to compare the float parsers.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+fn time(input: &str) -> Duration {
+    let runs = 10;
+    let start = Instant::now();
+    for _ in 0..runs {
+        black_box(wson::parse(black_box(input)).unwrap());
+    }
+    start.elapsed() / runs
+}
+
+/// Time to parse every literal with `number`, without the rest of a document
+fn time_each(literals: &[String]) -> Duration {
+    let runs = 10;
+    let start = Instant::now();
+    for _ in 0..runs {
+        for literal in literals {
+            black_box(wson::number::number(black_box(literal)).unwrap());
+        }
+    }
+    start.elapsed() / runs
+}
+
+fn main() {
+    let count = 100_000;
+    let floats: Vec<_> = (0..count)
+        .map(|i| format!("{}.{:03}e-2", i * 7919 % 100_000, i % 1000))
+        .collect();
+    let integers: Vec<_> = (0..count).map(|i| (i * 7919).to_string()).collect();
+    println!(
+        "{:>9} numbers: floats  {:>12?}  integers {:>12?}",
+        count,
+        time_each(&floats),
+        time_each(&integers)
+    );
+
+    // coordinates with all the digits an f64 has, like in geometry
+    let mut state = 1u64;
+    let coordinates: Vec<_> = (0..1_000_000)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            ((state >> 11) as f64 / (1u64 << 53) as f64 * 360.0 - 180.0).to_string()
+        })
+        .collect();
+    println!(
+        "{:>9} coordinates:     {:>12?}",
+        coordinates.len(),
+        time_each(&coordinates)
+    );
+
+    for digits in [1_000, 10_000, 100_000, 1_000_000] {
+        let integer = "7".repeat(digits);
+        let float = format!("0.{}e-5", integer);
+        println!(
+            "{:>9} digits: integer {:>12?}  float {:>12?}",
+            digits,
+            time(&integer),
+            time(&float)
+        );
+    }
+}
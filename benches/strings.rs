@@ -0,0 +1,52 @@
+//! Time to parse strings
+//!
+//! Run with `cargo bench --bench strings`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+fn time(input: &str) -> Duration {
+    let runs = 10;
+    let start = Instant::now();
+    for _ in 0..runs {
+        black_box(wson::parse(black_box(input)).unwrap());
+    }
+    start.elapsed() / runs
+}
+
+/// Time to parse every literal with `string`, without the rest of a document
+fn time_each(literals: &[String]) -> Duration {
+    let runs = 10;
+    let start = Instant::now();
+    for _ in 0..runs {
+        for literal in literals {
+            black_box(wson::string::string(black_box(literal)).unwrap());
+        }
+    }
+    start.elapsed() / runs
+}
+
+fn main() {
+    let count = 100_000;
+    // like the messages of log lines, where most strings have no escapes
+    let plain: Vec<_> = (0..count)
+        .map(|i| format!("\"GET /api/items/{} 200 in {}ms\"", i, i % 997))
+        .collect();
+    let escaped: Vec<_> = (0..count)
+        .map(|i| format!("\"GET /api/items/{}\\t200\\nin {}ms\"", i, i % 997))
+        .collect();
+    println!(
+        "{:>9} strings: plain    {:>12?}  escaped {:>12?}",
+        count,
+        time_each(&plain),
+        time_each(&escaped)
+    );
+
+    let line = "2024-01-01T00:00:00Z GET /api/items 200 in 12ms, ünïcödé ✓\\n";
+    let document = format!("\"{}\"", line.repeat(10_000_000 / line.len()));
+    println!(
+        "{:>9} bytes in one string:  {:>12?}",
+        document.len(),
+        time(&document)
+    );
+}
@@ -0,0 +1,1072 @@
+use crate::convert::kind;
+use crate::error::GetManyError;
+use crate::number::Number;
+use crate::Value;
+use std::collections::HashMap;
+use std::ops::{Index, IndexMut};
+
+impl Value {
+    /// Members of an object, or `None` for other values
+    ///
+    /// ```rust
+    /// use wson::parse;
+    /// # use std::error;
+    /// # fn main() -> Result<(), Box<dyn error::Error>> {
+    ///
+    ///
+    /// let value = parse("{\"a\": {\"b\": 1}, \"c\": []}")?;
+    /// assert_eq!(value.as_object().map(|members| members.len()), Some(2));
+    /// assert_eq!(parse("[]")?.as_object(), None);
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn as_object(&self) -> Option<&HashMap<String, Value>> {
+        match self {
+            Value::Object(members) => Some(members),
+            _ => None,
+        }
+    }
+
+    /// Mutable members of an object, or `None` for other values
+    ///
+    /// ```rust
+    /// use wson::{parse, Value};
+    /// # use std::error;
+    /// # fn main() -> Result<(), Box<dyn error::Error>> {
+    ///
+    ///
+    /// let mut value = parse("{\"a\": 1}")?;
+    /// if let Some(members) = value.as_object_mut() {
+    ///     members.insert("b".to_string(), Value::Null);
+    /// }
+    /// assert_eq!(value, parse("{\"a\": 1, \"b\": null}")?);
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn as_object_mut(&mut self) -> Option<&mut HashMap<String, Value>> {
+        match self {
+            Value::Object(members) => Some(members),
+            _ => None,
+        }
+    }
+
+    /// Elements of an array, or `None` for other values
+    ///
+    /// ```rust
+    /// use wson::parse;
+    /// # use std::error;
+    /// # fn main() -> Result<(), Box<dyn error::Error>> {
+    ///
+    ///
+    /// let value = parse("[1, \"a\", null]")?;
+    /// assert_eq!(value.as_array().map(|elements| elements.len()), Some(3));
+    /// assert_eq!(parse("{}")?.as_array(), None);
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn as_array(&self) -> Option<&Vec<Value>> {
+        match self {
+            Value::Array(elements) => Some(elements),
+            _ => None,
+        }
+    }
+
+    /// Mutable elements of an array, or `None` for other values
+    ///
+    /// ```rust
+    /// use wson::{parse, Value};
+    /// # use std::error;
+    /// # fn main() -> Result<(), Box<dyn error::Error>> {
+    ///
+    ///
+    /// let mut value = parse("[1]")?;
+    /// if let Some(elements) = value.as_array_mut() {
+    ///     elements.push(Value::Bool(true));
+    /// }
+    /// assert_eq!(value, parse("[1, true]")?);
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn as_array_mut(&mut self) -> Option<&mut Vec<Value>> {
+        match self {
+            Value::Array(elements) => Some(elements),
+            _ => None,
+        }
+    }
+
+    /// Content of a string, or `None` for other values
+    ///
+    /// ```rust
+    /// use wson::parse;
+    /// # use std::error;
+    /// # fn main() -> Result<(), Box<dyn error::Error>> {
+    ///
+    ///
+    /// assert_eq!(parse("\"hello\"")?.as_str(), Some("hello"));
+    /// assert_eq!(parse("1")?.as_str(), None);
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(str) => Some(str),
+            _ => None,
+        }
+    }
+
+    /// `true` or `false` as a `bool`, or `None` for other values
+    ///
+    /// ```rust
+    /// use wson::parse;
+    /// # use std::error;
+    /// # fn main() -> Result<(), Box<dyn error::Error>> {
+    ///
+    ///
+    /// assert_eq!(parse("true")?.as_bool(), Some(true));
+    /// assert_eq!(parse("false")?.as_bool(), Some(false));
+    /// assert_eq!(parse("null")?.as_bool(), None);
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Bool(bool) => Some(*bool),
+            _ => None,
+        }
+    }
+
+    /// `Some(())` for `null`, or `None` for other values
+    ///
+    /// ```rust
+    /// use wson::parse;
+    /// # use std::error;
+    /// # fn main() -> Result<(), Box<dyn error::Error>> {
+    ///
+    ///
+    /// assert_eq!(parse("null")?.as_null(), Some(()));
+    /// assert_eq!(parse("false")?.as_null(), None);
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn as_null(&self) -> Option<()> {
+        match self {
+            Value::Null => Some(()),
+            _ => None,
+        }
+    }
+
+    /// `None` for `null`, or the value itself
+    ///
+    /// Combined with `get`, a missing member and a `null` member both become
+    /// `None`; use `get` alone to tell them apart.
+    ///
+    /// ```rust
+    /// use wson::{parse, Value};
+    /// # use std::error;
+    /// # fn main() -> Result<(), Box<dyn error::Error>> {
+    ///
+    ///
+    /// let value = parse("{\"a\": null, \"b\": 1}")?;
+    /// assert_eq!(value.get("a"), Some(&Value::Null));
+    /// assert_eq!(value.get("a").and_then(Value::as_option), None);
+    /// assert_eq!(value.get("b").and_then(Value::as_option), value.get("b"));
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn as_option(&self) -> Option<&Value> {
+        match self {
+            Value::Null => None,
+            value => Some(value),
+        }
+    }
+
+    /// `default` for `null`, or the value itself
+    ///
+    /// ```rust
+    /// use wson::Value;
+    /// # fn main() {
+    ///
+    ///
+    /// assert_eq!(Value::Null.null_to(Value::from(0u8)), Value::from(0u8));
+    /// assert_eq!(Value::from("a").null_to(Value::from(0u8)), Value::from("a"));
+    /// # }
+    /// ```
+    pub fn null_to(self, default: Value) -> Value {
+        match self {
+            Value::Null => default,
+            value => value,
+        }
+    }
+
+    /// Move the members out of an object, or give value back as `Err`
+    ///
+    /// ```rust
+    /// use wson::{json, Value};
+    /// # fn main() {
+    ///
+    ///
+    /// let members = json!({"a": 1}).into_object();
+    /// assert_eq!(members.map(|members| members.len()), Ok(1));
+    /// assert_eq!(json!([1]).into_object(), Err(json!([1])));
+    /// # }
+    /// ```
+    pub fn into_object(self) -> Result<HashMap<String, Value>, Value> {
+        match self {
+            Value::Object(members) => Ok(members),
+            value => Err(value),
+        }
+    }
+
+    /// Move the elements out of an array, or give value back as `Err`
+    pub fn into_array(self) -> Result<Vec<Value>, Value> {
+        match self {
+            Value::Array(elements) => Ok(elements),
+            value => Err(value),
+        }
+    }
+
+    /// Move the content out of a string, or give value back as `Err`
+    pub fn into_string(self) -> Result<String, Value> {
+        match self {
+            Value::String(string) => Ok(string),
+            value => Err(value),
+        }
+    }
+
+    /// Move the number out, or give value back as `Err`
+    pub fn into_number(self) -> Result<Number, Value> {
+        match self {
+            Value::Number(number) => Ok(number),
+            value => Err(value),
+        }
+    }
+
+    /// `true` or `false` as a `bool`, or value back as `Err`
+    pub fn into_bool(self) -> Result<bool, Value> {
+        match self {
+            Value::Bool(bool) => Ok(bool),
+            value => Err(value),
+        }
+    }
+
+    /// Number as `i64` if it fits without loss, or `None` for other values
+    ///
+    /// Integral floats like `3.0` convert, `2.5` doesn't.
+    ///
+    /// ```rust
+    /// use wson::parse;
+    /// # use std::error;
+    /// # fn main() -> Result<(), Box<dyn error::Error>> {
+    ///
+    ///
+    /// assert_eq!(parse("-12")?.as_i64(), Some(-12));
+    /// assert_eq!(parse("9223372036854775808")?.as_i64(), None);
+    /// assert_eq!(parse("\"12\"")?.as_i64(), None);
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Value::Number(number) => number.as_i64(),
+            _ => None,
+        }
+    }
+
+    /// Number as `u64` if it fits without loss, or `None` for other values
+    ///
+    /// ```rust
+    /// use wson::parse;
+    /// # use std::error;
+    /// # fn main() -> Result<(), Box<dyn error::Error>> {
+    ///
+    ///
+    /// assert_eq!(parse("18446744073709551615")?.as_u64(), Some(u64::MAX));
+    /// assert_eq!(parse("-1")?.as_u64(), None);
+    /// assert_eq!(parse("1.5")?.as_u64(), None);
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            Value::Number(number) => number.as_u64(),
+            _ => None,
+        }
+    }
+
+    /// Number as `i128` if it fits without loss, or `None` for other values
+    pub fn as_i128(&self) -> Option<i128> {
+        match self {
+            Value::Number(number) => number.as_i128(),
+            _ => None,
+        }
+    }
+
+    /// Number as `u128` if it fits without loss, or `None` for other values
+    pub fn as_u128(&self) -> Option<u128> {
+        match self {
+            Value::Number(number) => number.as_u128(),
+            _ => None,
+        }
+    }
+
+    /// Number as `usize` if it fits without loss, or `None` for other values
+    pub fn as_usize(&self) -> Option<usize> {
+        match self {
+            Value::Number(number) => number.as_usize(),
+            _ => None,
+        }
+    }
+
+    /// Number as `f64`, or `None` for other values
+    ///
+    /// Integers above 2^53 in magnitude are rounded to the nearest `f64`.
+    ///
+    /// ```rust
+    /// use wson::parse;
+    /// # use std::error;
+    /// # fn main() -> Result<(), Box<dyn error::Error>> {
+    ///
+    ///
+    /// assert_eq!(parse("1.5")?.as_f64(), Some(1.5));
+    /// assert_eq!(parse("-2")?.as_f64(), Some(-2.0));
+    /// assert_eq!(parse("null")?.as_f64(), None);
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Number(number) => Some(number.as_f64()),
+            _ => None,
+        }
+    }
+
+    /// Member of an object by key, or `None` for a missing key or other values
+    ///
+    /// ```rust
+    /// use wson::parse;
+    /// # use std::error;
+    /// # fn main() -> Result<(), Box<dyn error::Error>> {
+    ///
+    ///
+    /// let value = parse("{\"menu\": {\"items\": [\"New\", \"Open\"]}}")?;
+    /// let item = value.get("menu").and_then(|menu| menu.get("items")).and_then(|items| items.get_index(1));
+    /// assert_eq!(item.and_then(|item| item.as_str()), Some("Open"));
+    /// assert_eq!(value.get("missing"), None);
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.as_object().and_then(|members| members.get(key))
+    }
+
+    /// Mutable member of an object by key, or `None` for a missing key or other values
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Value> {
+        self.as_object_mut()
+            .and_then(|members| members.get_mut(key))
+    }
+
+    /// Members of an object for each of `keys`, or `None` if any of them is
+    /// missing or for other values
+    ///
+    /// ```rust
+    /// use wson::json;
+    /// # fn main() {
+    ///
+    ///
+    /// let value = json!({"host": "localhost", "port": 8080, "tls": false});
+    /// let [host, port] = value.get_many(["host", "port"]).unwrap();
+    /// assert_eq!((host.as_str(), port.as_u64()), (Some("localhost"), Some(8080)));
+    /// assert_eq!(value.get_many(["host", "user"]), None);
+    /// # }
+    /// ```
+    pub fn get_many<const N: usize>(&self, keys: [&str; N]) -> Option<[&Value; N]> {
+        self.try_get_many(keys).ok()
+    }
+
+    /// Members of an object for each of `keys`, or an error telling which
+    /// keys are missing
+    ///
+    /// ```rust
+    /// use wson::error::GetManyError;
+    /// use wson::json;
+    /// # fn main() {
+    ///
+    ///
+    /// let value = json!({"host": "localhost"});
+    /// assert_eq!(
+    ///     value.try_get_many(["host", "port", "user"]),
+    ///     Err(GetManyError::MissingKeys {
+    ///         keys: vec!["port".to_string(), "user".to_string()]
+    ///     })
+    /// );
+    /// # }
+    /// ```
+    pub fn try_get_many<const N: usize>(
+        &self,
+        keys: [&str; N],
+    ) -> Result<[&Value; N], GetManyError> {
+        let members = self.as_object().ok_or_else(|| GetManyError::NotAnObject {
+            found: kind(self).to_string(),
+        })?;
+        let values = keys.map(|key| members.get(key));
+        if values.iter().any(Option::is_none) {
+            let keys = keys.iter().zip(values);
+            return Err(GetManyError::MissingKeys {
+                keys: keys
+                    .filter(|(_, value)| value.is_none())
+                    .map(|(key, _)| key.to_string())
+                    .collect(),
+            });
+        }
+        Ok(values.map(|value| value.unwrap_or(&Value::Null)))
+    }
+
+    /// Element of an array by index, or `None` out of range or for other values
+    ///
+    /// ```rust
+    /// use wson::parse;
+    /// # use std::error;
+    /// # fn main() -> Result<(), Box<dyn error::Error>> {
+    ///
+    ///
+    /// let value = parse("[true, null]")?;
+    /// assert_eq!(value.get_index(0).and_then(|v| v.as_bool()), Some(true));
+    /// assert_eq!(value.get_index(2), None);
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_index(&self, index: usize) -> Option<&Value> {
+        self.as_array().and_then(|elements| elements.get(index))
+    }
+
+    /// Mutable element of an array by index, or `None` out of range or for other values
+    pub fn get_index_mut(&mut self, index: usize) -> Option<&mut Value> {
+        self.as_array_mut()
+            .and_then(|elements| elements.get_mut(index))
+    }
+
+    /// Number of members, elements, or characters of a string
+    ///
+    /// Strings are counted in `char`s, not bytes. Other values return `None`.
+    ///
+    /// ```rust
+    /// use wson::parse;
+    /// # use std::error;
+    /// # fn main() -> Result<(), Box<dyn error::Error>> {
+    ///
+    ///
+    /// assert_eq!(parse("{\"a\": 1, \"b\": [1, 2, 3]}")?.len(), Some(2));
+    /// assert_eq!(parse("[1, 2, 3]")?.len(), Some(3));
+    /// assert_eq!(parse("\"こんにちは\"")?.len(), Some(5));
+    /// assert_eq!(parse("12")?.len(), None);
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn len(&self) -> Option<usize> {
+        match self {
+            Value::Object(members) => Some(members.len()),
+            Value::Array(elements) => Some(elements.len()),
+            Value::String(str) => Some(str.chars().count()),
+            _ => None,
+        }
+    }
+
+    /// Whether value is an empty object, array or string
+    ///
+    /// Other values are never empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == Some(0)
+    }
+
+    /// Whether value is an object
+    #[inline]
+    pub fn is_object(&self) -> bool {
+        matches!(self, Value::Object(_))
+    }
+
+    /// Whether value is an array
+    #[inline]
+    pub fn is_array(&self) -> bool {
+        matches!(self, Value::Array(_))
+    }
+
+    /// Whether value is a string
+    #[inline]
+    pub fn is_string(&self) -> bool {
+        matches!(self, Value::String(_))
+    }
+
+    /// Whether value is a number
+    #[inline]
+    pub fn is_number(&self) -> bool {
+        matches!(self, Value::Number(_))
+    }
+
+    /// Whether value is a number which `as_i64` converts
+    #[inline]
+    pub fn is_i64(&self) -> bool {
+        self.as_i64().is_some()
+    }
+
+    /// Whether value is a number which `as_u64` converts
+    #[inline]
+    pub fn is_u64(&self) -> bool {
+        self.as_u64().is_some()
+    }
+
+    /// Whether value is a float
+    ///
+    /// Integers are not, although `as_f64` converts them too.
+    #[inline]
+    pub fn is_f64(&self) -> bool {
+        match self {
+            Value::Number(Number::Float(_)) => true,
+            Value::Number(Number::Raw(raw)) => matches!(raw.number(), Number::Float(_)),
+            _ => false,
+        }
+    }
+
+    /// Whether value is `true` or `false`
+    #[inline]
+    pub fn is_bool(&self) -> bool {
+        matches!(self, Value::Bool(_))
+    }
+
+    /// Whether value is `null`
+    #[inline]
+    pub fn is_null(&self) -> bool {
+        matches!(self, Value::Null)
+    }
+}
+
+static NULL: Value = Value::Null;
+
+/// Member by key, or `null` when the key is missing or value isn't an object
+///
+/// ```rust
+/// use wson::{parse, Value};
+/// # use std::error;
+/// # fn main() -> Result<(), Box<dyn error::Error>> {
+///
+///
+/// let value = parse("{\"menu\": {\"items\": [\"New\", \"Open\"]}}")?;
+/// assert_eq!(value["menu"]["items"][1], Value::String("Open".to_string()));
+/// assert_eq!(value["menu"]["missing"][0]["deeper"], Value::Null);
+///
+/// # Ok(())
+/// # }
+/// ```
+impl Index<&str> for Value {
+    type Output = Value;
+
+    fn index(&self, key: &str) -> &Value {
+        self.get(key).unwrap_or(&NULL)
+    }
+}
+
+/// Element by index, or `null` when out of range or value isn't an array
+impl Index<usize> for Value {
+    type Output = Value;
+
+    fn index(&self, index: usize) -> &Value {
+        self.get_index(index).unwrap_or(&NULL)
+    }
+}
+
+/// Member by key, inserted as `null` when missing
+///
+/// A `null` value is turned into an empty object first, so nested objects
+/// can be built with `value["a"]["b"] = ...`.
+///
+/// # Panics
+///
+/// Panics if value is neither an object nor `null`.
+///
+/// ```rust
+/// use wson::{parse, Value};
+/// # use std::error;
+/// # fn main() -> Result<(), Box<dyn error::Error>> {
+///
+///
+/// let mut value = Value::Null;
+/// value["settings"]["retries"] = Value::Bool(true);
+/// assert_eq!(value, parse("{\"settings\": {\"retries\": true}}")?);
+///
+/// # Ok(())
+/// # }
+/// ```
+impl IndexMut<&str> for Value {
+    fn index_mut(&mut self, key: &str) -> &mut Value {
+        if self.is_null() {
+            *self = Value::Object(HashMap::new());
+        }
+        match self {
+            Value::Object(members) => members.entry(key.to_string()).or_insert(Value::Null),
+            _ => panic!("cannot index into a non-object json value with {:?}", key),
+        }
+    }
+}
+
+/// Element by index
+///
+/// # Panics
+///
+/// Panics if value isn't an array or `index` is out of range. Arrays are
+/// never padded.
+impl IndexMut<usize> for Value {
+    fn index_mut(&mut self, index: usize) -> &mut Value {
+        match self {
+            Value::Array(elements) => {
+                let len = elements.len();
+                elements.get_mut(index).unwrap_or_else(|| {
+                    panic!(
+                        "index {} out of range for json array of length {}",
+                        index, len
+                    )
+                })
+            }
+            _ => panic!("cannot index into a non-array json value with {}", index),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn every_variant() -> Vec<Value> {
+        vec![
+            Value::Object(HashMap::from([("a".to_string(), Value::Null)])),
+            Value::Array(vec![Value::Null]),
+            Value::Number(Number::PositiveInteger(1)),
+            Value::String("a".to_string()),
+            Value::Null,
+            Value::Bool(true),
+            Value::Bool(false),
+        ]
+    }
+
+    #[test]
+    fn accessors_match_only_their_variant() {
+        for mut value in every_variant() {
+            let is_object = matches!(value, Value::Object(_));
+            let is_array = matches!(value, Value::Array(_));
+            assert_eq!(value.as_object().is_some(), is_object, "{:?}", value);
+            assert_eq!(value.as_object_mut().is_some(), is_object, "{:?}", value);
+            assert_eq!(value.as_array().is_some(), is_array, "{:?}", value);
+            assert_eq!(value.as_array_mut().is_some(), is_array, "{:?}", value);
+            assert_eq!(
+                value.as_str().is_some(),
+                matches!(value, Value::String(_)),
+                "{:?}",
+                value
+            );
+            assert_eq!(
+                value.as_bool().is_some(),
+                matches!(value, Value::Bool(_)),
+                "{:?}",
+                value
+            );
+            let is_number = matches!(value, Value::Number(_));
+            assert_eq!(value.as_i64().is_some(), is_number, "{:?}", value);
+            assert_eq!(value.as_u64().is_some(), is_number, "{:?}", value);
+            assert_eq!(value.as_usize().is_some(), is_number, "{:?}", value);
+            assert_eq!(value.as_u128().is_some(), is_number, "{:?}", value);
+            assert_eq!(value.as_i128().is_some(), is_number, "{:?}", value);
+            assert_eq!(value.as_f64().is_some(), is_number, "{:?}", value);
+            assert_eq!(
+                value.as_null().is_some(),
+                value == Value::Null,
+                "{:?}",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn predicates() {
+        let float = |f| Value::Number(Number::Float(f));
+        // object, array, string, number, i64, u64, f64, bool, null
+        let table = [
+            (
+                Value::Object(HashMap::new()),
+                [true, false, false, false, false, false, false, false, false],
+            ),
+            (
+                Value::Array(vec![]),
+                [false, true, false, false, false, false, false, false, false],
+            ),
+            (
+                Value::String("1".to_string()),
+                [false, false, true, false, false, false, false, false, false],
+            ),
+            (
+                Value::Number(Number::PositiveInteger(1)),
+                [false, false, false, true, true, true, false, false, false],
+            ),
+            (
+                Value::Number(Number::PositiveInteger(u64::MAX)),
+                [false, false, false, true, false, true, false, false, false],
+            ),
+            (
+                Value::Number(Number::NegativeInteger(-1)),
+                [false, false, false, true, true, false, false, false, false],
+            ),
+            (
+                float(1.0),
+                [false, false, false, true, true, true, true, false, false],
+            ),
+            (
+                float(-1.5),
+                [false, false, false, true, false, false, true, false, false],
+            ),
+            (
+                Value::Bool(true),
+                [false, false, false, false, false, false, false, true, false],
+            ),
+            (
+                Value::Bool(false),
+                [false, false, false, false, false, false, false, true, false],
+            ),
+            (
+                Value::Null,
+                [false, false, false, false, false, false, false, false, true],
+            ),
+        ];
+        for (value, expected) in table {
+            let actual = [
+                value.is_object(),
+                value.is_array(),
+                value.is_string(),
+                value.is_number(),
+                value.is_i64(),
+                value.is_u64(),
+                value.is_f64(),
+                value.is_bool(),
+                value.is_null(),
+            ];
+            assert_eq!(actual, expected, "{:?}", value);
+        }
+    }
+
+    fn menu() -> Value {
+        crate::parse(
+            "{\"menu\": {
+               \"id\": \"file\",
+               \"popup\": {
+                 \"menuitem\": [
+                   {\"value\": \"New\", \"onclick\": \"CreateNewDoc()\"},
+                   {\"value\": \"Open\", \"onclick\": \"OpenDoc()\"}
+                 ]
+               }
+            }}",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn chained_get() {
+        fn onclick(value: &Value, index: usize) -> Option<&str> {
+            value
+                .get("menu")?
+                .get("popup")?
+                .get("menuitem")?
+                .get_index(index)?
+                .get("onclick")?
+                .as_str()
+        }
+        let value = menu();
+        assert_eq!(onclick(&value, 0), Some("CreateNewDoc()"));
+        assert_eq!(onclick(&value, 1), Some("OpenDoc()"));
+        assert_eq!(onclick(&value, 2), None);
+    }
+
+    #[test]
+    fn get_on_other_variants() {
+        let value = menu();
+        assert_eq!(value.get_index(0), None);
+        assert_eq!(
+            value
+                .get("menu")
+                .and_then(|menu| menu.get("id"))
+                .and_then(|id| id.get("x")),
+            None
+        );
+        for mut value in every_variant() {
+            assert_eq!(value.get("a").is_some(), value.is_object());
+            assert_eq!(value.get_mut("a").is_some(), value.is_object());
+            assert_eq!(value.get_index(0).is_some(), value.is_array());
+            assert_eq!(value.get_index_mut(0).is_some(), value.is_array());
+        }
+    }
+
+    #[test]
+    fn get_mut_modifies_in_place() {
+        let mut value = menu();
+        if let Some(items) = value
+            .get_mut("menu")
+            .and_then(|menu| menu.get_mut("popup"))
+            .and_then(|popup| popup.get_mut("menuitem"))
+        {
+            if let Some(item) = items.get_index_mut(0) {
+                *item = Value::Null;
+            }
+        }
+        let items = value
+            .get("menu")
+            .and_then(|menu| menu.get("popup"))
+            .and_then(|popup| popup.get("menuitem"));
+        assert_eq!(
+            items.and_then(|items| items.get_index(0)),
+            Some(&Value::Null)
+        );
+    }
+
+    #[test]
+    fn index_chains_never_panic() {
+        let value = menu();
+        assert_eq!(
+            value["menu"]["popup"]["menuitem"][1]["value"],
+            Value::String("Open".to_string())
+        );
+        assert_eq!(value["menu"]["popup"]["menuitem"][5]["value"], Value::Null);
+        assert_eq!(value["menu"]["id"]["x"][0], Value::Null);
+        assert_eq!(value[0], Value::Null);
+        assert_eq!(Value::Bool(true)["a"], Value::Null);
+    }
+
+    #[test]
+    fn index_mut_inserts_and_vivifies() {
+        let mut value = menu();
+        value["menu"]["id"] = Value::Null;
+        value["menu"]["new"]["nested"] = Value::Bool(true);
+        value["menu"]["popup"]["menuitem"][0] = Value::Bool(false);
+        assert_eq!(value["menu"]["id"], Value::Null);
+        assert_eq!(value["menu"]["new"]["nested"], Value::Bool(true));
+        assert_eq!(value["menu"]["popup"]["menuitem"][0], Value::Bool(false));
+        assert!(value["menu"]["popup"]["menuitem"][1].is_object());
+
+        let mut value = Value::Null;
+        let _ = &mut value["a"];
+        assert_eq!(
+            value,
+            Value::Object(HashMap::from([("a".to_string(), Value::Null)]))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "index 2 out of range for json array of length 2")]
+    fn index_mut_out_of_range_panics() {
+        let mut value = menu();
+        value["menu"]["popup"]["menuitem"][2] = Value::Null;
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot index into a non-object json value")]
+    fn index_mut_key_on_array_panics() {
+        let mut value = Value::Array(vec![]);
+        value["a"] = Value::Null;
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot index into a non-array json value")]
+    fn index_mut_index_on_null_panics() {
+        let mut value = Value::Null;
+        value[0] = Value::Null;
+    }
+
+    #[test]
+    fn len_of_every_variant() {
+        let lens: Vec<_> = every_variant().iter().map(Value::len).collect();
+        assert_eq!(lens, [Some(1), Some(1), None, Some(1), None, None, None]);
+    }
+
+    #[test]
+    fn len_counts_characters() {
+        let value = Value::String("こんにちは".to_string());
+        assert_eq!(value.len(), Some(5));
+        assert_eq!(value.as_str().map(str::len), Some(15));
+        assert_eq!(Value::String("aé😀".to_string()).len(), Some(3));
+    }
+
+    #[test]
+    fn is_empty() {
+        assert!(Value::Object(HashMap::new()).is_empty());
+        assert!(Value::Array(vec![]).is_empty());
+        assert!(Value::String(String::new()).is_empty());
+        for value in every_variant() {
+            assert!(!value.is_empty(), "{:?}", value);
+        }
+    }
+
+    #[test]
+    fn absent_and_null_members() {
+        let value = crate::parse("{\"null\": null, \"one\": 1}").unwrap();
+        assert_eq!(value.get("absent"), None);
+        assert_eq!(value.get("null"), Some(&Value::Null));
+        assert_eq!(value.get("null").and_then(Value::as_option), None);
+        assert_eq!(
+            value.get("one").and_then(Value::as_option),
+            Some(&value["one"])
+        );
+        assert_eq!(
+            value
+                .get("absent")
+                .cloned()
+                .unwrap_or_default()
+                .null_to(Value::Bool(true)),
+            Value::Bool(true)
+        );
+        assert_eq!(
+            value["null"].clone().null_to(Value::Bool(true)),
+            Value::Bool(true)
+        );
+        assert_eq!(
+            value["one"].clone().null_to(Value::Bool(true)),
+            value["one"]
+        );
+        assert_eq!(Option::<u8>::try_from(value["null"].clone()), Ok(None));
+        assert_eq!(Option::<u8>::try_from(value["one"].clone()), Ok(Some(1)));
+    }
+
+    #[test]
+    fn into_converters_move_contents() {
+        let elements: Vec<Value> = (0..1000u64).map(Value::from).collect();
+        let ptr = elements.as_ptr();
+        let moved = Value::Array(elements).into_array();
+        assert_eq!(moved.as_ref().map(|elements| elements.as_ptr()), Ok(ptr));
+
+        let string = "a".repeat(100);
+        let ptr = string.as_ptr();
+        assert_eq!(
+            Value::String(string).into_string().map(|s| s.as_ptr()),
+            Ok(ptr)
+        );
+    }
+
+    #[test]
+    fn into_converters_give_value_back() {
+        for value in every_variant() {
+            let original = value.clone();
+            let results = [
+                value.clone().into_object().map(Value::Object),
+                value.clone().into_array().map(Value::Array),
+                value.clone().into_string().map(Value::String),
+                value.clone().into_number().map(Value::Number),
+                value.clone().into_bool().map(Value::Bool),
+            ];
+            let oks = results.iter().filter(|result| result.is_ok()).count();
+            let expected = usize::from(!matches!(value, Value::Null));
+            assert_eq!(oks, expected, "{:?}", value);
+            for result in results {
+                assert_eq!(result.unwrap_or_else(|value| value), original);
+            }
+        }
+    }
+
+    #[test]
+    fn accessors_return_contents() {
+        let values = every_variant();
+        assert_eq!(
+            values[0].as_object(),
+            Some(&HashMap::from([("a".to_string(), Value::Null)]))
+        );
+        assert_eq!(values[1].as_array(), Some(&vec![Value::Null]));
+        assert_eq!(values[2].as_u64(), Some(1));
+        assert_eq!(values[2].as_i64(), Some(1));
+        assert_eq!(values[2].as_usize(), Some(1));
+        assert_eq!(values[2].as_f64(), Some(1.0));
+        assert_eq!(values[3].as_str(), Some("a"));
+        assert_eq!(values[4].as_null(), Some(()));
+        assert_eq!(values[5].as_bool(), Some(true));
+        assert_eq!(values[6].as_bool(), Some(false));
+    }
+
+    #[test]
+    fn mutable_accessors_modify_in_place() {
+        let mut values = every_variant();
+        if let Some(members) = values[0].as_object_mut() {
+            members.clear();
+        }
+        if let Some(elements) = values[1].as_array_mut() {
+            elements.push(Value::Bool(true));
+        }
+        assert_eq!(values[0], Value::Object(HashMap::new()));
+        assert_eq!(
+            values[1],
+            Value::Array(vec![Value::Null, Value::Bool(true)])
+        );
+    }
+
+    #[test]
+    fn get_many() {
+        let value = Value::Object(HashMap::from([
+            ("a".to_string(), Value::Bool(true)),
+            ("b".to_string(), Value::Null),
+            ("c".to_string(), Value::Number(Number::PositiveInteger(1))),
+        ]));
+        assert_eq!(
+            value.get_many(["c", "a"]),
+            Some([
+                &Value::Number(Number::PositiveInteger(1)),
+                &Value::Bool(true)
+            ])
+        );
+        assert_eq!(value.get_many(["b"]), Some([&Value::Null]));
+        assert_eq!(value.get_many([]), Some([]));
+        assert_eq!(value.get_many(["a", "x"]), None);
+    }
+
+    #[test]
+    fn try_get_many_reports_missing_keys() {
+        let value = Value::Object(HashMap::from([("a".to_string(), Value::Null)]));
+        let error = value.try_get_many(["a", "x"]).unwrap_err();
+        assert_eq!(
+            error,
+            GetManyError::MissingKeys {
+                keys: vec!["x".to_string()]
+            }
+        );
+        assert_eq!(error.to_string(), "missing keys \"x\"");
+        assert_eq!(
+            value.try_get_many(["y", "a", "x"]).unwrap_err().to_string(),
+            "missing keys \"y\", \"x\""
+        );
+    }
+
+    #[test]
+    fn get_many_on_other_values() {
+        for value in every_variant()
+            .into_iter()
+            .filter(|value| !value.is_object())
+        {
+            assert_eq!(value.get_many(["a"]), None);
+            assert_eq!(value.get_many([]), None);
+            assert!(matches!(
+                value.try_get_many(["a"]),
+                Err(GetManyError::NotAnObject { .. })
+            ));
+        }
+        assert_eq!(
+            Value::Array(vec![])
+                .try_get_many(["a"])
+                .unwrap_err()
+                .to_string(),
+            "expected Object, found Array"
+        );
+    }
+}
@@ -0,0 +1,270 @@
+//! Approximate equality of values
+
+use crate::number::Number;
+use crate::ord::numeric_cmp;
+use crate::pointer;
+use crate::Value;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+/// Options to control how `Value::approx_eq` compares
+///
+/// The default compares like `==`, except that an integer never equals a
+/// float.
+///
+/// ```rust
+/// use wson::approx::EqOptions;
+/// use wson::json;
+/// # fn main() {
+///
+///
+/// let options = EqOptions::new()
+///     .absolute_tolerance(1e-9)
+///     .integers_equal_floats(true)
+///     .null_as_absent(true);
+/// let actual = json!({"x": 0.1 + 0.2, "n": 3});
+/// assert!(actual.approx_eq(&json!({"x": 0.3, "n": 3.0, "y": null}), &options));
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct EqOptions {
+    absolute_tolerance: f64,
+    relative_tolerance: f64,
+    integers_equal_floats: bool,
+    null_as_absent: bool,
+}
+
+impl EqOptions {
+    /// Options to compare exactly
+    pub fn new() -> Self {
+        EqOptions {
+            absolute_tolerance: 0.0,
+            relative_tolerance: 0.0,
+            integers_equal_floats: false,
+            null_as_absent: false,
+        }
+    }
+
+    /// Numbers are equal when they differ by at most `tolerance`
+    pub fn absolute_tolerance(mut self, tolerance: f64) -> Self {
+        self.absolute_tolerance = tolerance;
+        self
+    }
+
+    /// Numbers are equal when they differ by at most `tolerance` times the
+    /// larger magnitude of the two
+    pub fn relative_tolerance(mut self, tolerance: f64) -> Self {
+        self.relative_tolerance = tolerance;
+        self
+    }
+
+    /// Compare an integer and a float by value, like `==`, so that `3`
+    /// equals `3.0`. Otherwise they are never equal.
+    pub fn integers_equal_floats(mut self, equal: bool) -> Self {
+        self.integers_equal_floats = equal;
+        self
+    }
+
+    /// Treat an object member which is `null` as if it were absent
+    pub fn null_as_absent(mut self, absent: bool) -> Self {
+        self.null_as_absent = absent;
+        self
+    }
+}
+
+impl Default for EqOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Value {
+    /// Whether `self` equals `other` within `options`
+    ///
+    /// Tolerances apply to numbers at any depth. NaN equals NaN, like in
+    /// `==`.
+    pub fn approx_eq(&self, other: &Value, options: &EqOptions) -> bool {
+        self.approx_diff(other, options).is_none()
+    }
+
+    /// JSON Pointer of the first place where `self` and `other` differ
+    /// within `options`, or `None` if they are equal
+    ///
+    /// Object members are compared in key order. When arrays have different
+    /// lengths and their common elements are equal, the pointer is the
+    /// index just after the shorter one.
+    ///
+    /// ```rust
+    /// use wson::approx::EqOptions;
+    /// use wson::json;
+    /// # fn main() {
+    ///
+    ///
+    /// let options = EqOptions::new().relative_tolerance(0.01);
+    /// let expected = json!({"points": [{"x": 1.0}, {"x": 2.0}]});
+    /// let actual = json!({"points": [{"x": 1.001}, {"x": 2.1}]});
+    /// assert_eq!(
+    ///     actual.approx_diff(&expected, &options),
+    ///     Some("/points/1/x".to_string())
+    /// );
+    /// # }
+    /// ```
+    pub fn approx_diff(&self, other: &Value, options: &EqOptions) -> Option<String> {
+        diff(self, other, options, String::new())
+    }
+}
+
+fn diff(a: &Value, b: &Value, options: &EqOptions, path: String) -> Option<String> {
+    let equal = match (a, b) {
+        (Value::Object(a), Value::Object(b)) => return diff_members(a, b, options, path),
+        (Value::Array(a), Value::Array(b)) => {
+            for (index, (a, b)) in a.iter().zip(b).enumerate() {
+                if let Some(path) = diff(a, b, options, format!("{}/{}", path, index)) {
+                    return Some(path);
+                }
+            }
+            if a.len() != b.len() {
+                return Some(format!("{}/{}", path, a.len().min(b.len())));
+            }
+            true
+        }
+        (Value::Number(a), Value::Number(b)) => numbers_equal(a, b, options),
+        _ => a == b,
+    };
+    if equal {
+        None
+    } else {
+        Some(path)
+    }
+}
+
+fn diff_members(
+    a: &HashMap<String, Value>,
+    b: &HashMap<String, Value>,
+    options: &EqOptions,
+    path: String,
+) -> Option<String> {
+    let mut keys: Vec<_> = a
+        .keys()
+        .chain(b.keys().filter(|key| !a.contains_key(*key)))
+        .collect();
+    keys.sort();
+    keys.into_iter().find_map(|key| {
+        let path = format!("{}/{}", path, pointer::escape(key));
+        match (a.get(key), b.get(key)) {
+            (Some(a), Some(b)) => diff(a, b, options, path),
+            (Some(Value::Null), None) | (None, Some(Value::Null)) if options.null_as_absent => None,
+            _ => Some(path),
+        }
+    })
+}
+
+fn numbers_equal(a: &Number, b: &Number, options: &EqOptions) -> bool {
+    match (a, b) {
+        (Number::Raw(a), b) => return numbers_equal(&a.number(), b, options),
+        (a, Number::Raw(b)) => return numbers_equal(a, &b.number(), options),
+        _ => {}
+    }
+    let is_float = |n: &Number| matches!(n, Number::Float(_));
+    if is_float(a) != is_float(b) && !options.integers_equal_floats {
+        return false;
+    }
+    if numeric_cmp(a, b) == Ordering::Equal {
+        return true;
+    }
+    let (a, b) = (a.as_f64(), b.as_f64());
+    if !a.is_finite() || !b.is_finite() {
+        return false;
+    }
+    // large integers can round to the same f64, but they aren't equal
+    let difference = (a - b).abs().max(f64::MIN_POSITIVE);
+    difference <= options.absolute_tolerance
+        || difference <= options.relative_tolerance * a.abs().max(b.abs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json;
+
+    #[test]
+    fn exact_by_default() {
+        let options = EqOptions::new();
+        let value = json!({"a": [1, 2.5, "x", null, true], "b": {}});
+        assert!(value.approx_eq(&value.clone(), &options));
+        assert!(!json!(0.1 + 0.2).approx_eq(&json!(0.3), &options));
+        assert!(!json!(3).approx_eq(&json!(3.0), &options));
+        assert!(json!(f64::NAN).approx_eq(&json!(f64::NAN), &options));
+        assert!(json!(-0.0).approx_eq(&json!(0.0), &options));
+        assert!(!json!("1").approx_eq(&json!(1), &options));
+    }
+
+    #[test]
+    fn float_tolerance() {
+        let absolute = EqOptions::new().absolute_tolerance(1e-9);
+        assert!(json!({"x": 0.1 + 0.2}).approx_eq(&json!({"x": 0.3}), &absolute));
+        assert!(!json!({"x": 0.31}).approx_eq(&json!({"x": 0.3}), &absolute));
+
+        let relative = EqOptions::new().relative_tolerance(1e-3);
+        assert!(json!(1000.0).approx_eq(&json!(1000.9), &relative));
+        assert!(!json!(1.0).approx_eq(&json!(1.01), &relative));
+        assert!(!json!(1.0).approx_eq(&json!(f64::NAN), &relative));
+        assert!(json!(f64::INFINITY).approx_eq(&json!(f64::INFINITY), &relative));
+        assert!(!json!(f64::INFINITY).approx_eq(&json!(f64::MAX), &relative));
+    }
+
+    #[test]
+    fn integers_and_floats() {
+        let options = EqOptions::new().integers_equal_floats(true);
+        assert!(json!(3).approx_eq(&json!(3.0), &options));
+        assert!(json!(-3.0).approx_eq(&json!(-3), &options));
+        assert!(!json!(3).approx_eq(&json!(3.5), &options));
+        assert!(json!(u64::MAX).approx_eq(&json!(u64::MAX), &options));
+        assert!(!json!(u64::MAX).approx_eq(&json!(u64::MAX - 1), &options));
+        assert!(json!([1, {"a": 2}]).approx_eq(&json!([1.0, {"a": 2.0}]), &options));
+
+        let tolerant = options.absolute_tolerance(0.5);
+        assert!(json!(3).approx_eq(&json!(3.25), &tolerant));
+        assert!(json!(3).approx_eq(&json!(2), &tolerant.absolute_tolerance(1.0)));
+    }
+
+    #[test]
+    fn null_members() {
+        let options = EqOptions::new().null_as_absent(true);
+        assert!(json!({"a": 1, "b": null}).approx_eq(&json!({"a": 1}), &options));
+        assert!(json!({"a": 1}).approx_eq(&json!({"a": 1, "b": null}), &options));
+        assert!(!json!({"a": 1}).approx_eq(&json!({"a": 1, "b": 2}), &options));
+        assert!(!json!([null]).approx_eq(&json!([]), &options));
+        assert!(!json!({"a": 1, "b": null}).approx_eq(&json!({"a": 1}), &EqOptions::new()));
+    }
+
+    #[test]
+    fn first_difference() {
+        let options = EqOptions::new();
+        assert_eq!(
+            json!(1).approx_diff(&json!(2), &options),
+            Some(String::new())
+        );
+        assert_eq!(
+            json!({"b": 1, "a": [1, 2], "c": 3})
+                .approx_diff(&json!({"b": 2, "a": [1, 3], "c": 4}), &options),
+            Some("/a/1".to_string())
+        );
+        assert_eq!(
+            json!({"a/b": [1]}).approx_diff(&json!({"a/b": [1, 2]}), &options),
+            Some("/a~1b/1".to_string())
+        );
+        assert_eq!(
+            json!({"a": 1}).approx_diff(&json!({"b": 1}), &options),
+            Some("/a".to_string())
+        );
+        assert_eq!(
+            json!({"x": {}}).approx_diff(&json!({"x": []}), &options),
+            Some("/x".to_string())
+        );
+        assert_eq!(
+            json!({"x": [1]}).approx_diff(&json!({"x": [1]}), &options),
+            None
+        );
+    }
+}
@@ -0,0 +1,232 @@
+//! Checked arithmetic on numbers
+//!
+//! | operands           | result                                        |
+//! |--------------------|-----------------------------------------------|
+//! | integer, integer   | integer, or `None` beyond `u128` and `i128`   |
+//! | integer, float     | float, or `None` when infinite or NaN         |
+//! | float, float       | float, or `None` when infinite or NaN         |
+//!
+//! Integer results use the narrowest variant which holds them, so
+//! `u64::MAX + 1` is `PositiveInteger128`, and zero is always
+//! `PositiveInteger(0)`. Raw numbers count as the number they stand for,
+//! and arbitrary numbers as floats.
+
+use crate::number::Number;
+
+/// An integer as its sign and magnitude, or `None` for a float
+fn integer(number: &Number) -> Option<(bool, u128)> {
+    match *number {
+        Number::PositiveInteger(n) => Some((false, u128::from(n))),
+        Number::PositiveInteger128(n) => Some((false, n)),
+        Number::NegativeInteger(n) => Some((n < 0, n.unsigned_abs().into())),
+        Number::NegativeInteger128(n) => Some((n < 0, n.unsigned_abs())),
+        Number::Raw(ref raw) => integer(&raw.number()),
+        _ => None,
+    }
+}
+
+/// Number with the sign and magnitude, or `None` below `i128::MIN`
+fn from_integer(negative: bool, magnitude: u128) -> Option<Number> {
+    if !negative || magnitude == 0 {
+        Some(Number::from(magnitude))
+    } else if magnitude <= 1 << 127 {
+        // -2^127 is i128::MIN, whose magnitude doesn't fit in i128
+        Some(Number::from((magnitude as i128).wrapping_neg()))
+    } else {
+        None
+    }
+}
+
+fn from_float(f: f64) -> Option<Number> {
+    Number::from_f64(f)
+}
+
+fn add_integers(a: (bool, u128), b: (bool, u128)) -> Option<Number> {
+    match (a, b) {
+        ((a_negative, a), (b_negative, b)) if a_negative == b_negative => {
+            from_integer(a_negative, a.checked_add(b)?)
+        }
+        ((a_negative, a), (_, b)) if a >= b => from_integer(a_negative, a - b),
+        ((_, a), (b_negative, b)) => from_integer(b_negative, b - a),
+    }
+}
+
+impl Number {
+    /// `self + other`, or `None` when it overflows
+    ///
+    /// ```rust
+    /// use wson::number::Number;
+    /// # fn main() {
+    ///
+    ///
+    /// let sum = Number::NegativeInteger(-5).checked_add(&Number::PositiveInteger(7));
+    /// assert_eq!(sum, Some(Number::PositiveInteger(2)));
+    /// assert_eq!(
+    ///     Number::PositiveInteger(1).checked_add(&Number::Float(0.5)),
+    ///     Some(Number::Float(1.5))
+    /// );
+    /// assert_eq!(Number::Float(f64::MAX).checked_add(&Number::Float(f64::MAX)), None);
+    /// # }
+    /// ```
+    pub fn checked_add(&self, other: &Number) -> Option<Number> {
+        match (integer(self), integer(other)) {
+            (Some(a), Some(b)) => add_integers(a, b),
+            _ => from_float(self.as_f64() + other.as_f64()),
+        }
+    }
+
+    /// `self - other`, or `None` when it overflows
+    pub fn checked_sub(&self, other: &Number) -> Option<Number> {
+        match (integer(self), integer(other)) {
+            (Some(a), Some((b_negative, b))) => add_integers(a, (!b_negative && b != 0, b)),
+            _ => from_float(self.as_f64() - other.as_f64()),
+        }
+    }
+
+    /// `self * other`, or `None` when it overflows
+    pub fn checked_mul(&self, other: &Number) -> Option<Number> {
+        match (integer(self), integer(other)) {
+            (Some((a_negative, a)), Some((b_negative, b))) => {
+                from_integer(a_negative != b_negative, a.checked_mul(b)?)
+            }
+            _ => from_float(self.as_f64() * other.as_f64()),
+        }
+    }
+
+    /// `-self`, or `None` for an integer above 2^127, whose negation is
+    /// below `i128::MIN`
+    ///
+    /// A float keeps its variant, so `0.0` becomes `-0.0`.
+    pub fn checked_neg(&self) -> Option<Number> {
+        match integer(self) {
+            Some((negative, magnitude)) => from_integer(!negative, magnitude),
+            None => from_float(-self.as_f64()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn positive(n: u64) -> Number {
+        Number::PositiveInteger(n)
+    }
+
+    fn negative(n: i64) -> Number {
+        Number::NegativeInteger(n)
+    }
+
+    fn float(f: f64) -> Number {
+        Number::Float(f)
+    }
+
+    /// Compare variants too, as `==` doesn't tell `3` from `3.0`
+    fn assert_same(actual: Option<Number>, expected: Option<Number>) {
+        assert_eq!(format!("{:?}", actual), format!("{:?}", expected));
+    }
+
+    #[test]
+    fn integer_overflow() {
+        assert_same(
+            positive(u64::MAX).checked_add(&positive(1)),
+            Some(Number::PositiveInteger128(1 << 64)),
+        );
+        assert_same(
+            positive(u64::MAX).checked_mul(&positive(u64::MAX)),
+            Some(Number::PositiveInteger128(
+                u128::from(u64::MAX) * u128::from(u64::MAX),
+            )),
+        );
+        let max = Number::PositiveInteger128(u128::MAX);
+        assert_same(max.checked_add(&positive(1)), None);
+        assert_same(max.checked_mul(&positive(2)), None);
+        assert_same(max.checked_sub(&max), Some(positive(0)));
+        assert_same(
+            max.checked_add(&negative(-1)),
+            Some(Number::PositiveInteger128(u128::MAX - 1)),
+        );
+    }
+
+    #[test]
+    fn integer_underflow() {
+        assert_same(
+            negative(i64::MIN).checked_sub(&positive(1)),
+            Some(Number::NegativeInteger128(i128::from(i64::MIN) - 1)),
+        );
+        assert_same(
+            negative(i64::MIN).checked_add(&negative(i64::MIN)),
+            Some(Number::NegativeInteger128(2 * i128::from(i64::MIN))),
+        );
+        let min = Number::NegativeInteger128(i128::MIN);
+        assert_same(min.checked_sub(&positive(1)), None);
+        assert_same(min.checked_add(&negative(-1)), None);
+        assert_same(
+            min.checked_mul(&negative(-1)),
+            Some(Number::PositiveInteger128(1 << 127)),
+        );
+        assert_same(
+            positive(0).checked_sub(&min),
+            Some(Number::PositiveInteger128(1 << 127)),
+        );
+        assert_same(
+            positive(0).checked_sub(&positive(u64::MAX)),
+            Some(Number::NegativeInteger128(-i128::from(u64::MAX))),
+        );
+    }
+
+    #[test]
+    fn crossing_zero() {
+        assert_same(negative(-5).checked_add(&positive(3)), Some(negative(-2)));
+        assert_same(negative(-5).checked_add(&positive(7)), Some(positive(2)));
+        assert_same(positive(5).checked_add(&negative(-5)), Some(positive(0)));
+        assert_same(positive(3).checked_sub(&positive(5)), Some(negative(-2)));
+        assert_same(negative(-3).checked_sub(&negative(-5)), Some(positive(2)));
+        assert_same(negative(-3).checked_mul(&positive(0)), Some(positive(0)));
+        assert_same(negative(-3).checked_mul(&negative(-4)), Some(positive(12)));
+        assert_same(positive(3).checked_mul(&negative(-4)), Some(negative(-12)));
+    }
+
+    #[test]
+    fn floats_propagate() {
+        assert_same(positive(1).checked_add(&float(0.5)), Some(float(1.5)));
+        assert_same(float(0.5).checked_sub(&negative(-1)), Some(float(1.5)));
+        assert_same(positive(2).checked_mul(&float(2.0)), Some(float(4.0)));
+        assert_same(float(0.1).checked_add(&float(0.2)), Some(float(0.1 + 0.2)));
+        assert_same(float(f64::MAX).checked_mul(&positive(2)), None);
+        assert_same(float(f64::MIN).checked_sub(&float(f64::MAX)), None);
+        assert_same(float(f64::NAN).checked_add(&positive(1)), None);
+        assert_same(float(f64::INFINITY).checked_mul(&positive(0)), None);
+    }
+
+    #[test]
+    fn negation() {
+        assert_same(positive(3).checked_neg(), Some(negative(-3)));
+        assert_same(negative(-3).checked_neg(), Some(positive(3)));
+        assert_same(positive(0).checked_neg(), Some(positive(0)));
+        assert_same(negative(i64::MIN).checked_neg(), Some(positive(1 << 63)));
+        assert_same(
+            positive(u64::MAX).checked_neg(),
+            Some(Number::NegativeInteger128(-i128::from(u64::MAX))),
+        );
+        assert_same(
+            Number::PositiveInteger128(1 << 127).checked_neg(),
+            Some(Number::NegativeInteger128(i128::MIN)),
+        );
+        assert_same(
+            Number::PositiveInteger128((1 << 127) + 1).checked_neg(),
+            None,
+        );
+        assert_same(float(0.0).checked_neg(), Some(float(-0.0)));
+        assert_same(float(-1.5).checked_neg(), Some(float(1.5)));
+    }
+
+    #[test]
+    fn raw_numbers() {
+        use crate::number::RawNumber;
+
+        let raw = |literal| Number::Raw(RawNumber::new(literal).unwrap());
+        assert_same(raw("2").checked_add(&raw("3")), Some(positive(5)));
+        assert_same(raw("2.50").checked_mul(&positive(2)), Some(float(5.0)));
+    }
+}
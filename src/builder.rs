@@ -0,0 +1,203 @@
+use crate::Value;
+use std::collections::HashMap;
+
+/// Build an object member by member
+///
+/// ```rust
+/// use wson::{json, ObjectBuilder};
+/// # fn main() {
+///
+///
+/// let nickname: Option<&str> = None;
+/// let value = ObjectBuilder::new()
+///     .field("a", 1)
+///     .field_opt("b", nickname)
+///     .nested("c", |o| o.field("x", true))
+///     .nested_array("d", |a| a.push("y"))
+///     .build();
+/// assert_eq!(value, json!({"a": 1, "c": {"x": true}, "d": ["y"]}));
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ObjectBuilder {
+    members: HashMap<String, Value>,
+}
+
+impl ObjectBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the member `key`, replacing an earlier one
+    pub fn field(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.members.insert(key.into(), value.into());
+        self
+    }
+
+    /// Set the member `key` if `value` is `Some`, and leave it out if `None`
+    pub fn field_opt<V: Into<Value>>(self, key: impl Into<String>, value: Option<V>) -> Self {
+        match value {
+            Some(value) => self.field(key, value),
+            None => self,
+        }
+    }
+
+    /// Set the member `key` to an object built by `build`
+    pub fn nested<F>(self, key: impl Into<String>, build: F) -> Self
+    where
+        F: FnOnce(ObjectBuilder) -> ObjectBuilder,
+    {
+        self.field(key, build(ObjectBuilder::new()).build())
+    }
+
+    /// Set the member `key` to an array built by `build`
+    pub fn nested_array<F>(self, key: impl Into<String>, build: F) -> Self
+    where
+        F: FnOnce(ArrayBuilder) -> ArrayBuilder,
+    {
+        self.field(key, build(ArrayBuilder::new()).build())
+    }
+
+    pub fn build(self) -> Value {
+        Value::Object(self.members)
+    }
+}
+
+/// Build an array element by element
+///
+/// ```rust
+/// use wson::{json, ArrayBuilder};
+/// # fn main() {
+///
+///
+/// let value = ArrayBuilder::new()
+///     .push(1)
+///     .push_all(["a", "b"])
+///     .nested_object(|o| o.field("x", false))
+///     .build();
+/// assert_eq!(value, json!([1, "a", "b", {"x": false}]));
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ArrayBuilder {
+    elements: Vec<Value>,
+}
+
+impl ArrayBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(mut self, value: impl Into<Value>) -> Self {
+        self.elements.push(value.into());
+        self
+    }
+
+    pub fn push_all<I>(mut self, values: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<Value>,
+    {
+        self.elements.extend(values.into_iter().map(Into::into));
+        self
+    }
+
+    /// Push an object built by `build`
+    pub fn nested_object<F>(self, build: F) -> Self
+    where
+        F: FnOnce(ObjectBuilder) -> ObjectBuilder,
+    {
+        self.push(build(ObjectBuilder::new()).build())
+    }
+
+    /// Push an array built by `build`
+    pub fn nested_array<F>(self, build: F) -> Self
+    where
+        F: FnOnce(ArrayBuilder) -> ArrayBuilder,
+    {
+        self.push(build(ArrayBuilder::new()).build())
+    }
+
+    pub fn build(self) -> Value {
+        Value::Array(self.elements)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json;
+    use crate::number::Number;
+
+    #[test]
+    fn empty_builders() {
+        assert_eq!(ObjectBuilder::new().build(), Value::new_object());
+        assert_eq!(ArrayBuilder::new().build(), Value::new_array());
+    }
+
+    #[test]
+    fn object_matches_json_macro() {
+        let keys = ["x", "y", "z"];
+        let mut builder = ObjectBuilder::new()
+            .field("name", "wson")
+            .field("count", 3u64)
+            .field_opt("missing", None::<u8>)
+            .field_opt("present", Some(-1))
+            .nested("nested", |o| o.field("flag", true).field("none", ()));
+        for (i, key) in keys.iter().enumerate() {
+            builder = builder.field(*key, i as u64);
+        }
+        assert_eq!(
+            builder.build(),
+            json!({
+                "name": "wson",
+                "count": 3,
+                "present": -1,
+                "nested": {"flag": true, "none": null},
+                "x": 0,
+                "y": 1,
+                "z": 2,
+            })
+        );
+    }
+
+    #[test]
+    fn field_opt_skips_none() {
+        let value = ObjectBuilder::new().field_opt("a", None::<Value>).build();
+        assert_eq!(value.get("a"), None);
+        let value = ObjectBuilder::new()
+            .field_opt("a", Some(Value::Null))
+            .build();
+        assert_eq!(value.get("a"), Some(&Value::Null));
+    }
+
+    #[test]
+    fn field_replaces() {
+        let value = ObjectBuilder::new().field("a", 1).field("a", 2).build();
+        assert_eq!(value["a"], Value::Number(Number::PositiveInteger(2)));
+    }
+
+    #[test]
+    fn array_matches_manual_construction() {
+        let value = ArrayBuilder::new()
+            .push(1.5)
+            .push_all(vec![true, false])
+            .push_all(0..0u8)
+            .nested_object(|o| o.nested_array("list", |a| a.push(())))
+            .nested_array(|a| a.nested_array(|a| a))
+            .build();
+        assert_eq!(
+            value,
+            Value::Array(vec![
+                Value::Number(Number::Float(1.5)),
+                Value::Bool(true),
+                Value::Bool(false),
+                Value::Object(HashMap::from([(
+                    "list".to_string(),
+                    Value::Array(vec![Value::Null])
+                )])),
+                Value::Array(vec![Value::Array(vec![])]),
+            ])
+        );
+    }
+}
@@ -0,0 +1,501 @@
+use crate::convert::kind;
+use crate::error::DecodeError;
+use crate::Value;
+use std::collections::{BTreeMap, HashMap};
+
+/// Convert a Rust value into a `Value`
+///
+/// ```rust
+/// use wson::{parse, ToJson, Value};
+/// # use std::error;
+/// # fn main() -> Result<(), Box<dyn error::Error>> {
+///
+///
+/// struct Item {
+///     name: String,
+///     tags: Vec<String>,
+/// }
+///
+/// impl ToJson for Item {
+///     fn to_json(&self) -> Value {
+///         [("name", self.name.to_json()), ("tags", self.tags.to_json())]
+///             .into_iter()
+///             .collect()
+///     }
+/// }
+///
+/// let item = Item { name: "a".to_string(), tags: vec!["x".to_string()] };
+/// assert_eq!(item.to_json(), parse("{\"name\": \"a\", \"tags\": [\"x\"]}")?);
+///
+/// # Ok(())
+/// # }
+/// ```
+pub trait ToJson {
+    fn to_json(&self) -> Value;
+}
+
+/// Convert a `Value` into a Rust value
+///
+/// Errors carry a JSON Pointer to the value which failed; `from_member`
+/// and the implementations for containers add to it.
+///
+/// ```rust
+/// use wson::error::DecodeError;
+/// use wson::{from_member, parse, FromJson, Value};
+/// use std::collections::HashMap;
+/// # use std::error;
+/// # fn main() -> Result<(), Box<dyn error::Error>> {
+///
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Item {
+///     name: String,
+///     count: Option<u32>,
+/// }
+///
+/// impl FromJson for Item {
+///     fn from_json(value: &Value) -> Result<Self, DecodeError> {
+///         Ok(Item {
+///             name: from_member(value, "name")?,
+///             count: from_member(value, "count")?,
+///         })
+///     }
+/// }
+///
+/// let value = parse("{\"items\": [{\"name\": \"a\"}, {\"name\": 1}]}")?;
+/// let error = HashMap::<String, Vec<Item>>::from_json(&value).unwrap_err();
+/// assert_eq!(error.to_string(), "expected String, found Number at \"/items/1/name\"");
+///
+/// # Ok(())
+/// # }
+/// ```
+pub trait FromJson: Sized {
+    fn from_json(value: &Value) -> Result<Self, DecodeError>;
+}
+
+/// Convert the member `key` of an object, or `null` when it is missing
+///
+/// A missing member is decoded as `null`, so it works for `Option` fields.
+/// Errors are pointed at the member.
+pub fn from_member<T: FromJson>(value: &Value, key: &str) -> Result<T, DecodeError> {
+    T::from_json(&value[key]).map_err(|e| e.within(key))
+}
+
+impl ToJson for Value {
+    fn to_json(&self) -> Value {
+        self.clone()
+    }
+}
+
+impl FromJson for Value {
+    fn from_json(value: &Value) -> Result<Self, DecodeError> {
+        Ok(value.clone())
+    }
+}
+
+impl<T: ToJson + ?Sized> ToJson for &T {
+    fn to_json(&self) -> Value {
+        (**self).to_json()
+    }
+}
+
+impl ToJson for str {
+    fn to_json(&self) -> Value {
+        Value::from(self)
+    }
+}
+
+macro_rules! primitives {
+    ($($primitive:ty),*) => {
+        $(
+            impl ToJson for $primitive {
+                fn to_json(&self) -> Value {
+                    Value::from(self.clone())
+                }
+            }
+
+            impl FromJson for $primitive {
+                fn from_json(value: &Value) -> Result<Self, DecodeError> {
+                    <$primitive>::try_from(value)
+                }
+            }
+        )*
+    };
+}
+
+primitives!(bool, String, f64, u64, i64, u32, i32, u16, i16, u8, i8);
+
+impl ToJson for f32 {
+    fn to_json(&self) -> Value {
+        Value::from(*self)
+    }
+}
+
+impl FromJson for f32 {
+    fn from_json(value: &Value) -> Result<Self, DecodeError> {
+        f64::from_json(value).map(|f| f as f32)
+    }
+}
+
+impl ToJson for usize {
+    fn to_json(&self) -> Value {
+        Value::from(*self)
+    }
+}
+
+impl FromJson for usize {
+    fn from_json(value: &Value) -> Result<Self, DecodeError> {
+        usize::try_from(value)
+    }
+}
+
+impl<T: ToJson> ToJson for Option<T> {
+    fn to_json(&self) -> Value {
+        match self {
+            Some(value) => value.to_json(),
+            None => Value::Null,
+        }
+    }
+}
+
+impl<T: FromJson> FromJson for Option<T> {
+    fn from_json(value: &Value) -> Result<Self, DecodeError> {
+        match value {
+            Value::Null => Ok(None),
+            value => T::from_json(value).map(Some),
+        }
+    }
+}
+
+impl<T: ToJson> ToJson for [T] {
+    fn to_json(&self) -> Value {
+        self.iter().map(ToJson::to_json).collect()
+    }
+}
+
+impl<T: ToJson> ToJson for Vec<T> {
+    fn to_json(&self) -> Value {
+        self.as_slice().to_json()
+    }
+}
+
+impl<T: FromJson> FromJson for Vec<T> {
+    fn from_json(value: &Value) -> Result<Self, DecodeError> {
+        elements(value, "array")?
+            .iter()
+            .enumerate()
+            .map(|(i, element)| T::from_json(element).map_err(|e| e.within(&i.to_string())))
+            .collect()
+    }
+}
+
+impl<T: ToJson, const N: usize> ToJson for [T; N] {
+    fn to_json(&self) -> Value {
+        self.as_slice().to_json()
+    }
+}
+
+/// The array must have exactly `N` elements
+impl<T: FromJson, const N: usize> FromJson for [T; N] {
+    fn from_json(value: &Value) -> Result<Self, DecodeError> {
+        let elements = Vec::<T>::from_json(value)?;
+        let len = elements.len();
+        elements.try_into().map_err(|_| {
+            DecodeError::new(
+                format!("array of length {}", N),
+                format!("array of length {}", len),
+            )
+        })
+    }
+}
+
+impl<T: ToJson> ToJson for HashMap<String, T> {
+    fn to_json(&self) -> Value {
+        self.iter()
+            .map(|(key, value)| (key.as_str(), value.to_json()))
+            .collect()
+    }
+}
+
+impl<T: FromJson> FromJson for HashMap<String, T> {
+    fn from_json(value: &Value) -> Result<Self, DecodeError> {
+        members(value, "object")?
+            .iter()
+            .map(|(key, value)| Ok((key.clone(), T::from_json(value).map_err(|e| e.within(key))?)))
+            .collect()
+    }
+}
+
+impl<T: ToJson> ToJson for BTreeMap<String, T> {
+    fn to_json(&self) -> Value {
+        self.iter()
+            .map(|(key, value)| (key.as_str(), value.to_json()))
+            .collect()
+    }
+}
+
+impl<T: FromJson> FromJson for BTreeMap<String, T> {
+    fn from_json(value: &Value) -> Result<Self, DecodeError> {
+        members(value, "object")?
+            .iter()
+            .map(|(key, value)| Ok((key.clone(), T::from_json(value).map_err(|e| e.within(key))?)))
+            .collect()
+    }
+}
+
+/// Tuples are arrays with exactly as many elements
+macro_rules! tuples {
+    ($(($len:expr => $($t:ident $i:tt),+)),*) => {
+        $(
+            impl<$($t: ToJson),+> ToJson for ($($t,)+) {
+                fn to_json(&self) -> Value {
+                    Value::Array(vec![$(self.$i.to_json()),+])
+                }
+            }
+
+            impl<$($t: FromJson),+> FromJson for ($($t,)+) {
+                fn from_json(value: &Value) -> Result<Self, DecodeError> {
+                    let elements = elements(value, concat!("array of length ", $len))?;
+                    if elements.len() != $len {
+                        return Err(DecodeError::new(
+                            concat!("array of length ", $len),
+                            format!("array of length {}", elements.len()),
+                        ));
+                    }
+                    Ok(($(
+                        $t::from_json(&elements[$i]).map_err(|e| e.within(stringify!($i)))?,
+                    )+))
+                }
+            }
+        )*
+    };
+}
+
+tuples!(
+    (1 => A 0),
+    (2 => A 0, B 1),
+    (3 => A 0, B 1, C 2),
+    (4 => A 0, B 1, C 2, D 3),
+    (5 => A 0, B 1, C 2, D 3, E 4),
+    (6 => A 0, B 1, C 2, D 3, E 4, F 5)
+);
+
+fn elements<'v>(value: &'v Value, expected: &str) -> Result<&'v Vec<Value>, DecodeError> {
+    value
+        .as_array()
+        .ok_or_else(|| DecodeError::new(expected, kind(value)))
+}
+
+fn members<'v>(
+    value: &'v Value,
+    expected: &str,
+) -> Result<&'v HashMap<String, Value>, DecodeError> {
+    value
+        .as_object()
+        .ok_or_else(|| DecodeError::new(expected, kind(value)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse;
+
+    #[derive(Debug, PartialEq)]
+    struct Order {
+        id: u64,
+        customer: Customer,
+        items: Vec<Item>,
+        note: Option<String>,
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Customer {
+        name: String,
+        location: (f64, f64),
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Item {
+        name: String,
+        quantity: u32,
+        tags: BTreeMap<String, bool>,
+    }
+
+    impl ToJson for Order {
+        fn to_json(&self) -> Value {
+            [
+                ("id", self.id.to_json()),
+                ("customer", self.customer.to_json()),
+                ("items", self.items.to_json()),
+                ("note", self.note.to_json()),
+            ]
+            .into_iter()
+            .collect()
+        }
+    }
+
+    impl FromJson for Order {
+        fn from_json(value: &Value) -> Result<Self, DecodeError> {
+            Ok(Order {
+                id: from_member(value, "id")?,
+                customer: from_member(value, "customer")?,
+                items: from_member(value, "items")?,
+                note: from_member(value, "note")?,
+            })
+        }
+    }
+
+    impl ToJson for Customer {
+        fn to_json(&self) -> Value {
+            [
+                ("name", self.name.to_json()),
+                ("location", self.location.to_json()),
+            ]
+            .into_iter()
+            .collect()
+        }
+    }
+
+    impl FromJson for Customer {
+        fn from_json(value: &Value) -> Result<Self, DecodeError> {
+            Ok(Customer {
+                name: from_member(value, "name")?,
+                location: from_member(value, "location")?,
+            })
+        }
+    }
+
+    impl ToJson for Item {
+        fn to_json(&self) -> Value {
+            [
+                ("name", self.name.to_json()),
+                ("quantity", self.quantity.to_json()),
+                ("tags", self.tags.to_json()),
+            ]
+            .into_iter()
+            .collect()
+        }
+    }
+
+    impl FromJson for Item {
+        fn from_json(value: &Value) -> Result<Self, DecodeError> {
+            Ok(Item {
+                name: from_member(value, "name")?,
+                quantity: from_member(value, "quantity")?,
+                tags: from_member(value, "tags")?,
+            })
+        }
+    }
+
+    fn order() -> Order {
+        Order {
+            id: 7,
+            customer: Customer {
+                name: "a".to_string(),
+                location: (35.5, -139.25),
+            },
+            items: vec![
+                Item {
+                    name: "pen".to_string(),
+                    quantity: 2,
+                    tags: BTreeMap::from([("gift".to_string(), true)]),
+                },
+                Item {
+                    name: "ink".to_string(),
+                    quantity: 1,
+                    tags: BTreeMap::new(),
+                },
+            ],
+            note: None,
+        }
+    }
+
+    #[test]
+    fn round_trip_nested_struct() -> Result<(), DecodeError> {
+        let order = order();
+        let value = order.to_json();
+        assert_eq!(Order::from_json(&value)?, order);
+
+        let json = crate::to_string(&value).unwrap();
+        let parsed = parse(&json).unwrap();
+        assert_eq!(Order::from_json(&parsed)?, order);
+        Ok(())
+    }
+
+    #[test]
+    fn errors_point_at_the_failing_value() {
+        let mut value = order().to_json();
+        value["items"][1]["name"] = Value::from(3u8);
+        let error = Order::from_json(&value).unwrap_err();
+        assert_eq!(error.pointer(), "/items/1/name");
+        assert_eq!(
+            error.to_string(),
+            "expected String, found Number at \"/items/1/name\""
+        );
+
+        let mut value = order().to_json();
+        value["customer"]["location"] = Value::from(vec![1.0]);
+        let error = Order::from_json(&value).unwrap_err();
+        assert_eq!(error.pointer(), "/customer/location");
+        assert_eq!(error.expected(), "array of length 2");
+
+        let mut value = order().to_json();
+        value.remove("id");
+        let error = Order::from_json(&value).unwrap_err();
+        assert_eq!(error.to_string(), "expected u64, found Null at \"/id\"");
+    }
+
+    #[test]
+    fn std_types_round_trip() -> Result<(), DecodeError> {
+        fn round_trip<T: ToJson + FromJson + PartialEq + std::fmt::Debug>(
+            value: T,
+        ) -> Result<(), DecodeError> {
+            assert_eq!(T::from_json(&value.to_json())?, value);
+            Ok(())
+        }
+        round_trip(true)?;
+        round_trip("a".to_string())?;
+        round_trip(u64::MAX)?;
+        round_trip(i64::MIN)?;
+        round_trip(-3i8)?;
+        round_trip(300u16)?;
+        round_trip(1.5f32)?;
+        round_trip(2.25f64)?;
+        round_trip(42usize)?;
+        round_trip(Some(1u8))?;
+        round_trip(Option::<u8>::None)?;
+        round_trip(vec![vec![1u32], vec![]])?;
+        round_trip([1i32, 2, 3])?;
+        round_trip(HashMap::from([("a".to_string(), vec![true])]))?;
+        round_trip(BTreeMap::from([("a".to_string(), 1u8)]))?;
+        round_trip((1u8,))?;
+        round_trip((1u8, "a".to_string(), (true, None::<u8>)))?;
+        round_trip((1u8, 2u8, 3u8, 4u8, 5u8, 6u8))?;
+        round_trip(parse("{\"a\": [null]}").unwrap())?;
+        Ok(())
+    }
+
+    #[test]
+    fn fixed_size_arrays_check_length() {
+        let error = <[u8; 3]>::from_json(&Value::from(vec![1u8, 2])).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "expected array of length 3, found array of length 2"
+        );
+        let error = <(u8, u8)>::from_json(&Value::from(vec![1u8, 2, 3])).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "expected array of length 2, found array of length 3"
+        );
+        let error = <(u8, String)>::from_json(&Value::from(vec![1u8, 2])).unwrap_err();
+        assert_eq!(error.pointer(), "/1");
+    }
+
+    #[test]
+    fn to_json_references() {
+        let name = "a";
+        assert_eq!(name.to_json(), Value::from("a"));
+        assert_eq!((&&name).to_json(), Value::from("a"));
+        assert_eq!([name].as_slice().to_json(), Value::from(vec!["a"]));
+    }
+}
@@ -0,0 +1,222 @@
+use crate::error::SerializeError;
+use crate::serialize::{write_number, write_string, SerializeOptions};
+use crate::Value;
+use std::fmt::Write;
+
+/// ANSI SGR parameters, like `"1;34"` for bold blue, used for each kind of token
+///
+/// The default colors follow jq.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColorScheme {
+    pub key: String,
+    pub string: String,
+    pub number: String,
+    pub null: String,
+    pub bool: String,
+    /// Brackets, braces, ':' and ','
+    pub punctuation: String,
+}
+
+impl Default for ColorScheme {
+    fn default() -> Self {
+        ColorScheme {
+            key: "34;1".to_string(),
+            string: "0;32".to_string(),
+            number: "0;39".to_string(),
+            null: "1;30".to_string(),
+            bool: "0;39".to_string(),
+            punctuation: "1;39".to_string(),
+        }
+    }
+}
+
+/// Serialize value into json indented with 2 spaces and colored with ANSI
+/// escape codes for terminals
+///
+/// Without the escape codes the output is the same as `to_string_pretty(value, 2)`.
+/// Whether stdout is a terminal is left to the caller.
+///
+/// ```rust
+/// use wson::color::{to_string_pretty_colored, ColorScheme};
+/// use wson::Value;
+/// # use std::error;
+/// # fn main() -> Result<(), Box<dyn error::Error>> {
+///
+///
+/// let json = to_string_pretty_colored(&Value::Array(vec![Value::Null]), &ColorScheme::default())?;
+/// assert_eq!(
+///     json,
+///     "\x1b[1;39m[\x1b[0m\n  \x1b[1;30mnull\x1b[0m\n\x1b[1;39m]\x1b[0m"
+/// );
+///
+/// # Ok(())
+/// # }
+/// ```
+pub fn to_string_pretty_colored(
+    value: &Value,
+    scheme: &ColorScheme,
+) -> Result<String, SerializeError> {
+    let mut printer = Printer {
+        output: String::new(),
+        scheme,
+        options: SerializeOptions::pretty(2),
+        depth: 0,
+    };
+    printer.write_value(value)?;
+    Ok(printer.output)
+}
+
+struct Printer<'a> {
+    output: String,
+    scheme: &'a ColorScheme,
+    options: SerializeOptions,
+    depth: usize,
+}
+
+impl Printer<'_> {
+    fn write_value(&mut self, value: &Value) -> Result<(), SerializeError> {
+        match value {
+            Value::Object(members) => {
+                self.punctuation("{");
+                self.depth += 1;
+                for (i, (key, value)) in members.iter().enumerate() {
+                    self.separator(i);
+                    self.begin(&self.scheme.key);
+                    write_string(&mut self.output, key, &self.options)?;
+                    self.end();
+                    self.punctuation(":");
+                    self.output.push(' ');
+                    self.write_value(value).map_err(|error| error.within(key))?;
+                }
+                self.close("}", members.is_empty());
+            }
+            Value::Array(elements) => {
+                self.punctuation("[");
+                self.depth += 1;
+                for (i, element) in elements.iter().enumerate() {
+                    self.separator(i);
+                    self.write_value(element)
+                        .map_err(|error| error.within(&i.to_string()))?;
+                }
+                self.close("]", elements.is_empty());
+            }
+            Value::Number(number) => {
+                self.begin(&self.scheme.number);
+                write_number(&mut self.output, number, &self.options)?;
+                self.end();
+            }
+            Value::String(str) => {
+                self.begin(&self.scheme.string);
+                write_string(&mut self.output, str, &self.options)?;
+                self.end();
+            }
+            Value::Null => self.token(&self.scheme.null, "null"),
+            Value::Bool(true) => self.token(&self.scheme.bool, "true"),
+            Value::Bool(false) => self.token(&self.scheme.bool, "false"),
+        }
+        Ok(())
+    }
+
+    fn separator(&mut self, i: usize) {
+        if i > 0 {
+            self.punctuation(",");
+        }
+        self.newline();
+    }
+
+    fn close(&mut self, bracket: &str, empty: bool) {
+        self.depth -= 1;
+        if !empty {
+            self.newline();
+        }
+        self.punctuation(bracket);
+    }
+
+    fn newline(&mut self) {
+        self.output.push('\n');
+        for _ in 0..self.depth {
+            self.output.push_str("  ");
+        }
+    }
+
+    fn punctuation(&mut self, punctuation: &str) {
+        self.token(&self.scheme.punctuation, punctuation);
+    }
+
+    fn token(&mut self, color: &str, token: &str) {
+        self.begin(color);
+        self.output.push_str(token);
+        self.end();
+    }
+
+    fn begin(&mut self, color: &str) {
+        let _ = write!(self.output, "\x1b[{}m", color);
+    }
+
+    fn end(&mut self) {
+        self.output.push_str("\x1b[0m");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::number::Number;
+    use crate::to_string_pretty;
+    use std::collections::HashMap;
+
+    fn strip_colors(colored: &str) -> String {
+        let mut plain = String::new();
+        let mut rest = colored;
+        while let Some(start) = rest.find('\x1b') {
+            plain.push_str(&rest[..start]);
+            let end = rest[start..].find('m').expect("escape codes end with 'm'");
+            rest = &rest[start + end + 1..];
+        }
+        plain.push_str(rest);
+        plain
+    }
+
+    #[test]
+    fn colored_output_is_pretty_output() -> Result<(), SerializeError> {
+        let value = Value::Object(HashMap::from([
+            (
+                "menuitem".to_string(),
+                Value::Array(vec![
+                    Value::Object(HashMap::from([(
+                        "value".to_string(),
+                        Value::String("New".to_string()),
+                    )])),
+                    Value::Array(vec![]),
+                    Value::Object(HashMap::new()),
+                ]),
+            ),
+            ("id".to_string(), Value::Number(Number::Float(1.5))),
+            (
+                "flags".to_string(),
+                Value::Array(vec![Value::Bool(true), Value::Bool(false), Value::Null]),
+            ),
+        ]));
+        let colored = to_string_pretty_colored(&value, &ColorScheme::default())?;
+        assert_ne!(colored, to_string_pretty(&value, 2)?);
+        assert_eq!(strip_colors(&colored), to_string_pretty(&value, 2)?);
+        Ok(())
+    }
+
+    #[test]
+    fn keys_and_strings_colored_differently() -> Result<(), SerializeError> {
+        let scheme = ColorScheme {
+            key: "k".to_string(),
+            string: "s".to_string(),
+            ..ColorScheme::default()
+        };
+        let value = Value::Object(HashMap::from([(
+            "a".to_string(),
+            Value::String("b".to_string()),
+        )]));
+        let colored = to_string_pretty_colored(&value, &scheme)?;
+        assert!(colored.contains("\x1b[km\"a\"\x1b[0m"));
+        assert!(colored.contains("\x1b[sm\"b\"\x1b[0m"));
+        Ok(())
+    }
+}
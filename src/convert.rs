@@ -0,0 +1,833 @@
+use crate::error::{ConversionError, NumberConversionError};
+use crate::number::Number;
+use crate::string::JsonString;
+use crate::Value;
+use std::collections::HashMap;
+
+impl From<bool> for Value {
+    fn from(bool: bool) -> Self {
+        Value::Bool(bool)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(str: &str) -> Self {
+        Value::String(str.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(string: String) -> Self {
+        Value::String(string)
+    }
+}
+
+impl From<()> for Value {
+    fn from(_: ()) -> Self {
+        Value::Null
+    }
+}
+
+// `usize` and `isize` are at most 64 bits wide, so `as` doesn't truncate
+macro_rules! from_unsigned {
+    ($($unsigned:ty),*) => {
+        $(
+            impl From<$unsigned> for Number {
+                fn from(n: $unsigned) -> Self {
+                    Number::PositiveInteger(n as u64)
+                }
+            }
+
+            impl From<$unsigned> for Value {
+                fn from(n: $unsigned) -> Self {
+                    Value::Number(Number::from(n))
+                }
+            }
+        )*
+    };
+}
+
+from_unsigned!(u8, u16, u32, u64, usize);
+
+/// Negative integers become `NegativeInteger`, and zero or more `PositiveInteger`
+macro_rules! from_signed {
+    ($($signed:ty),*) => {
+        $(
+            impl From<$signed> for Number {
+                fn from(n: $signed) -> Self {
+                    let n = n as i64;
+                    match u64::try_from(n) {
+                        Ok(n) => Number::PositiveInteger(n),
+                        Err(_) => Number::NegativeInteger(n),
+                    }
+                }
+            }
+
+            impl From<$signed> for Value {
+                fn from(n: $signed) -> Self {
+                    Value::Number(Number::from(n))
+                }
+            }
+        )*
+    };
+}
+
+from_signed!(i8, i16, i32, i64, isize);
+
+/// Integers within the `u64` range become `PositiveInteger`
+impl From<u128> for Number {
+    fn from(n: u128) -> Self {
+        match u64::try_from(n) {
+            Ok(n) => Number::PositiveInteger(n),
+            Err(_) => Number::PositiveInteger128(n),
+        }
+    }
+}
+
+/// Zero or more are converted like `u128`, and integers within the `i64`
+/// range become `NegativeInteger`
+impl From<i128> for Number {
+    fn from(n: i128) -> Self {
+        match (u128::try_from(n), i64::try_from(n)) {
+            (Ok(n), _) => Number::from(n),
+            (_, Ok(n)) => Number::NegativeInteger(n),
+            _ => Number::NegativeInteger128(n),
+        }
+    }
+}
+
+impl From<u128> for Value {
+    fn from(n: u128) -> Self {
+        Value::Number(Number::from(n))
+    }
+}
+
+impl From<i128> for Value {
+    fn from(n: i128) -> Self {
+        Value::Number(Number::from(n))
+    }
+}
+
+impl From<f32> for Number {
+    fn from(f: f32) -> Self {
+        Number::Float(f64::from(f))
+    }
+}
+
+impl From<f64> for Number {
+    fn from(f: f64) -> Self {
+        Number::Float(f)
+    }
+}
+
+impl From<f32> for Value {
+    fn from(f: f32) -> Self {
+        Value::Number(Number::from(f))
+    }
+}
+
+impl From<f64> for Value {
+    fn from(f: f64) -> Self {
+        Value::Number(Number::from(f))
+    }
+}
+
+impl From<Number> for Value {
+    fn from(number: Number) -> Self {
+        Value::Number(number)
+    }
+}
+
+/// A borrowed string is copied
+impl From<JsonString<'_>> for Value {
+    fn from(string: JsonString<'_>) -> Self {
+        Value::String(string.into_string())
+    }
+}
+
+/// `None` becomes `null`
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(option: Option<T>) -> Self {
+        option.map_or(Value::Null, Into::into)
+    }
+}
+
+impl<T: Into<Value>> From<Vec<T>> for Value {
+    fn from(elements: Vec<T>) -> Self {
+        Value::Array(elements.into_iter().map(Into::into).collect())
+    }
+}
+
+/// Collect into an array
+///
+/// ```rust
+/// use wson::{parse, Value};
+/// # use std::error;
+/// # fn main() -> Result<(), Box<dyn error::Error>> {
+///
+///
+/// let value: Value = (0..5u32).map(|i| i * 2).collect();
+/// assert_eq!(value, parse("[0, 2, 4, 6, 8]")?);
+///
+/// # Ok(())
+/// # }
+/// ```
+impl<T: Into<Value>> FromIterator<T> for Value {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Value::Array(iter.into_iter().map(Into::into).collect())
+    }
+}
+
+/// Append to an array
+///
+/// `null` is turned into an empty array first.
+///
+/// # Panics
+///
+/// Panics if value is neither an array nor `null`.
+impl<T: Into<Value>> Extend<T> for Value {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.elements_or_vivify()
+            .extend(iter.into_iter().map(Into::into))
+    }
+}
+
+impl<V: Into<Value>> From<HashMap<String, V>> for Value {
+    fn from(members: HashMap<String, V>) -> Self {
+        Value::Object(
+            members
+                .into_iter()
+                .map(|(key, value)| (key, value.into()))
+                .collect(),
+        )
+    }
+}
+
+/// Collect key and value pairs into an object
+///
+/// When a key appears more than once, the last value is kept.
+///
+/// ```rust
+/// use wson::{parse, Value};
+/// # use std::error;
+/// # fn main() -> Result<(), Box<dyn error::Error>> {
+///
+///
+/// let value: Value = [("a", 1u8), ("b", 2)].into_iter().collect();
+/// assert_eq!(value, parse("{\"a\": 1, \"b\": 2}")?);
+///
+/// # Ok(())
+/// # }
+/// ```
+impl<K: Into<String>, V: Into<Value>> FromIterator<(K, V)> for Value {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        Value::Object(
+            iter.into_iter()
+                .map(|(key, value)| (key.into(), value.into()))
+                .collect(),
+        )
+    }
+}
+
+/// Name of the kind of `value` for errors
+pub(crate) fn kind(value: &Value) -> &'static str {
+    match value {
+        Value::Object(_) => "Object",
+        Value::Array(_) => "Array",
+        Value::Number(_) => "Number",
+        Value::String(_) => "String",
+        Value::Null => "Null",
+        Value::Bool(_) => "Bool",
+    }
+}
+
+impl TryFrom<&Value> for bool {
+    type Error = ConversionError;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        value
+            .as_bool()
+            .ok_or_else(|| ConversionError::new("bool", kind(value)))
+    }
+}
+
+impl TryFrom<&Value> for String {
+    type Error = ConversionError;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        value
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| ConversionError::new("String", kind(value)))
+    }
+}
+
+impl TryFrom<&Value> for f64 {
+    type Error = ConversionError;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        value
+            .as_f64()
+            .ok_or_else(|| ConversionError::new("f64", kind(value)))
+    }
+}
+
+/// Integer of type `T` with the value of `number`
+///
+/// Integral floats convert like integers. Infinities are too large or too
+/// small.
+fn to_integer<T>(number: &Number) -> Result<T, NumberConversionError>
+where
+    T: TryFrom<u128> + TryFrom<i128>,
+{
+    let unsigned = T::try_from(-1i128).is_err();
+    let below = if unsigned {
+        NumberConversionError::Negative
+    } else {
+        NumberConversionError::TooSmall
+    };
+    if let Some(n) = number.as_u128() {
+        T::try_from(n).map_err(|_| NumberConversionError::TooLarge)
+    } else if let Some(n) = number.as_i128() {
+        // as_u128 failed, so n is negative
+        T::try_from(n).map_err(|_| below)
+    } else {
+        // a float with a fractional part or beyond 128 bits
+        match number.as_f64() {
+            f if f.is_nan() || (f.is_finite() && f.fract() != 0.0) => {
+                Err(NumberConversionError::NotAnInteger)
+            }
+            f if f < 0.0 => Err(below),
+            _ => Err(NumberConversionError::TooLarge),
+        }
+    }
+}
+
+/// Integers must be in range, and floats integral like `3.0`
+macro_rules! try_from_integer {
+    ($($integer:ty),*) => {
+        $(
+            impl TryFrom<&Number> for $integer {
+                type Error = NumberConversionError;
+
+                fn try_from(number: &Number) -> Result<Self, Self::Error> {
+                    to_integer(number)
+                }
+            }
+
+            impl TryFrom<Number> for $integer {
+                type Error = NumberConversionError;
+
+                fn try_from(number: Number) -> Result<Self, Self::Error> {
+                    to_integer(&number)
+                }
+            }
+
+            impl TryFrom<&Value> for $integer {
+                type Error = ConversionError;
+
+                fn try_from(value: &Value) -> Result<Self, Self::Error> {
+                    match value {
+                        Value::Number(number) => <$integer>::try_from(number)
+                            .map_err(|_| ConversionError::new(stringify!($integer), number.to_string())),
+                        _ => Err(ConversionError::new(stringify!($integer), kind(value))),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+try_from_integer!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+macro_rules! try_from_owned {
+    ($($primitive:ty),*) => {
+        $(
+            impl TryFrom<Value> for $primitive {
+                type Error = ConversionError;
+
+                fn try_from(value: Value) -> Result<Self, Self::Error> {
+                    <$primitive>::try_from(&value)
+                }
+            }
+        )*
+    };
+}
+
+try_from_owned!(bool, f64, u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+impl TryFrom<Value> for String {
+    type Error = ConversionError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::String(string) => Ok(string),
+            value => Err(ConversionError::new("String", kind(&value))),
+        }
+    }
+}
+
+/// `null` becomes `None`, anything else is converted into `T`
+impl<T: TryFrom<Value, Error = ConversionError>> TryFrom<Value> for Option<T> {
+    type Error = ConversionError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Null => Ok(None),
+            value => T::try_from(value).map(Some),
+        }
+    }
+}
+
+/// Errors tell the index of the element which failed
+///
+/// ```rust
+/// use wson::parse;
+/// # use std::error;
+/// # fn main() -> Result<(), Box<dyn error::Error>> {
+///
+///
+/// let value = parse("{\"ids\": [1, 2, 3]}")?;
+/// let ids: Vec<u64> = value.pointer("/ids").cloned().unwrap_or_default().try_into()?;
+/// assert_eq!(ids, vec![1, 2, 3]);
+///
+/// let error = Vec::<u64>::try_from(parse("[1, -2]")?).unwrap_err();
+/// assert_eq!(error.to_string(), "expected u64, found -2 at \"/1\"");
+///
+/// # Ok(())
+/// # }
+/// ```
+impl<T: TryFrom<Value, Error = ConversionError>> TryFrom<Value> for Vec<T> {
+    type Error = ConversionError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Array(elements) => elements
+                .into_iter()
+                .enumerate()
+                .map(|(i, element)| T::try_from(element).map_err(|e| e.within(&i.to_string())))
+                .collect(),
+            value => Err(ConversionError::new("Vec", kind(&value))),
+        }
+    }
+}
+
+/// Errors tell the key of the member which failed
+impl<T: TryFrom<Value, Error = ConversionError>> TryFrom<Value> for HashMap<String, T> {
+    type Error = ConversionError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Object(members) => members
+                .into_iter()
+                .map(|(key, value)| match T::try_from(value) {
+                    Ok(value) => Ok((key, value)),
+                    Err(e) => Err(e.within(&key)),
+                })
+                .collect(),
+            value => Err(ConversionError::new("HashMap", kind(&value))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(value: &mut Value, key: &str, x: impl Into<Value>) {
+        value.insert(key, x.into());
+    }
+
+    #[test]
+    fn unsigned_integers() {
+        assert_eq!(Value::from(7u8), Value::Number(Number::PositiveInteger(7)));
+        assert_eq!(Value::from(7u16), Value::Number(Number::PositiveInteger(7)));
+        assert_eq!(Value::from(7u32), Value::Number(Number::PositiveInteger(7)));
+        assert_eq!(
+            Value::from(u64::MAX),
+            Value::Number(Number::PositiveInteger(u64::MAX))
+        );
+        assert_eq!(
+            Value::from(i64::MAX as u64 + 1),
+            Value::Number(Number::PositiveInteger(9223372036854775808))
+        );
+    }
+
+    #[test]
+    fn signed_integers() {
+        assert_eq!(
+            Value::from(-7i8),
+            Value::Number(Number::NegativeInteger(-7))
+        );
+        assert_eq!(
+            Value::from(-7i16),
+            Value::Number(Number::NegativeInteger(-7))
+        );
+        assert_eq!(
+            Value::from(-7i32),
+            Value::Number(Number::NegativeInteger(-7))
+        );
+        assert_eq!(
+            Value::from(i64::MIN),
+            Value::Number(Number::NegativeInteger(i64::MIN))
+        );
+        assert_eq!(Value::from(7i32), Value::Number(Number::PositiveInteger(7)));
+        assert_eq!(
+            Value::from(-0i64),
+            Value::Number(Number::PositiveInteger(0))
+        );
+    }
+
+    #[test]
+    fn floats() {
+        assert_eq!(Value::from(1.5f32), Value::Number(Number::Float(1.5)));
+        assert_eq!(Value::from(-1.5), Value::Number(Number::Float(-1.5)));
+        assert_eq!(Value::from(3.0), Value::Number(Number::Float(3.0)));
+    }
+
+    #[test]
+    fn numbers_from_primitives() {
+        assert_eq!(Number::from(7u8), Number::PositiveInteger(7));
+        assert_eq!(Number::from(7usize), Number::PositiveInteger(7));
+        assert_eq!(Number::from(u64::MAX), Number::PositiveInteger(u64::MAX));
+        assert_eq!(Number::from(-5i32), Number::NegativeInteger(-5));
+        assert_eq!(Number::from(-5isize), Number::NegativeInteger(-5));
+        assert_eq!(Number::from(i64::MIN), Number::NegativeInteger(i64::MIN));
+        assert_eq!(Number::from(0i8), Number::PositiveInteger(0));
+        assert_eq!(Number::from(-0i64), Number::PositiveInteger(0));
+        assert_eq!(Number::from(2.5f32), Number::Float(2.5));
+        assert!(matches!(Number::from(-0.0), Number::Float(f) if f.is_sign_negative()));
+        assert_eq!(
+            Value::from(42usize),
+            Value::Number(Number::PositiveInteger(42))
+        );
+        assert_eq!(
+            Value::from(-42isize),
+            Value::Number(Number::NegativeInteger(-42))
+        );
+    }
+
+    #[test]
+    fn other_primitives() {
+        assert_eq!(Value::from(true), Value::Bool(true));
+        assert_eq!(Value::from("a"), Value::String("a".to_string()));
+        assert_eq!(Value::from("a".to_string()), Value::String("a".to_string()));
+        assert_eq!(Value::from(()), Value::Null);
+    }
+
+    #[test]
+    fn generic_helper() {
+        let mut value = Value::Null;
+        set(&mut value, "name", "wson");
+        set(&mut value, "count", 3u8);
+        set(&mut value, "delta", -1);
+        set(&mut value, "ok", true);
+        set(&mut value, "none", ());
+        assert_eq!(value["count"], Value::Number(Number::PositiveInteger(3)));
+        assert_eq!(value["delta"], Value::Number(Number::NegativeInteger(-1)));
+        assert_eq!(value["name"], "wson");
+        assert_eq!(value["ok"], true);
+        assert_eq!(value.get("none"), Some(&Value::Null));
+    }
+
+    fn numbers(numbers: &[u64]) -> Value {
+        Value::Array(
+            numbers
+                .iter()
+                .map(|n| Value::Number(Number::PositiveInteger(*n)))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn arrays_from_ranges() {
+        let value: Value = (0..4u64).collect();
+        assert_eq!(value, numbers(&[0, 1, 2, 3]));
+        let value: Value = (0..0u8).collect();
+        assert_eq!(value, Value::Array(vec![]));
+    }
+
+    #[test]
+    fn arrays_from_vecs() {
+        assert_eq!(
+            Value::from(vec!["a", "b"]),
+            Value::Array(vec![Value::from("a"), Value::from("b")])
+        );
+        assert_eq!(
+            Value::from(vec![Value::Null, Value::Bool(true)]),
+            Value::Array(vec![Value::Null, Value::Bool(true)])
+        );
+        assert_eq!(
+            Value::from(vec![vec![1u32, 2], vec![], vec![3]]),
+            Value::Array(vec![numbers(&[1, 2]), numbers(&[]), numbers(&[3])])
+        );
+    }
+
+    #[test]
+    fn collect_nested() {
+        let value: Value = (1..3u64).map(|n| (0..n).collect::<Value>()).collect();
+        assert_eq!(value, Value::Array(vec![numbers(&[0]), numbers(&[0, 1])]));
+    }
+
+    #[test]
+    fn extend_arrays() {
+        let mut value = numbers(&[1]);
+        value.extend(vec![2u64, 3]);
+        assert_eq!(value, numbers(&[1, 2, 3]));
+
+        let mut value = Value::Null;
+        value.extend(["a"]);
+        assert_eq!(value, Value::Array(vec![Value::from("a")]));
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot add an element to a non-array json value")]
+    fn extend_object_panics() {
+        Value::new_object().extend([1u8]);
+    }
+
+    #[test]
+    fn options() {
+        assert_eq!(
+            Value::from(Some(1u8)),
+            Value::Number(Number::PositiveInteger(1))
+        );
+        assert_eq!(Value::from(None::<u8>), Value::Null);
+        assert_eq!(Value::from(Some(None::<&str>)), Value::Null);
+        let value: Value = vec![Some("a"), None].into_iter().collect();
+        assert_eq!(value, Value::Array(vec![Value::from("a"), Value::Null]));
+    }
+
+    #[test]
+    fn number_and_json_string() {
+        assert_eq!(
+            Value::from(Number::Float(1.5)),
+            Value::Number(Number::Float(1.5))
+        );
+        assert_eq!(
+            Value::from(JsonString::from("a")),
+            Value::String("a".to_string())
+        );
+    }
+
+    #[test]
+    fn objects_from_hash_maps() {
+        let value = Value::from(HashMap::from([("a".to_string(), 1u8)]));
+        assert_eq!(
+            value,
+            Value::Object(HashMap::from([(
+                "a".to_string(),
+                Value::Number(Number::PositiveInteger(1))
+            )]))
+        );
+    }
+
+    #[test]
+    fn collect_objects() {
+        let value: Value = [("a", "x"), ("b", "y")].into_iter().collect();
+        assert_eq!(value["a"], "x");
+        assert_eq!(value["b"], "y");
+        assert_eq!(value.len(), Some(2));
+
+        let value: Value = vec![("a".to_string(), true)].into_iter().collect();
+        assert_eq!(
+            value,
+            Value::Object(HashMap::from([("a".to_string(), Value::Bool(true))]))
+        );
+    }
+
+    #[test]
+    fn collect_duplicate_keys_keeps_last() {
+        let value: Value = [("a", 1u8), ("b", 2), ("a", 3)].into_iter().collect();
+        assert_eq!(value.len(), Some(2));
+        assert_eq!(value["a"], Value::Number(Number::PositiveInteger(3)));
+    }
+
+    #[test]
+    fn collect_objects_in_array() {
+        let value: Value = (0..2u64)
+            .map(|i| [("id", i)].into_iter().collect::<Value>())
+            .collect();
+        assert_eq!(value[1]["id"], 1u64);
+        assert_eq!(Some(value), crate::parse("[{\"id\": 0}, {\"id\": 1}]").ok());
+    }
+
+    #[test]
+    fn try_from_success() -> Result<(), ConversionError> {
+        assert!(bool::try_from(Value::Bool(true))?);
+        assert_eq!(String::try_from(Value::from("a"))?, "a");
+        assert_eq!(String::try_from(&Value::from("a"))?, "a");
+        assert_eq!(f64::try_from(Value::from(1.5))?, 1.5);
+        assert_eq!(f64::try_from(Value::from(-2))?, -2.0);
+        assert_eq!(u64::try_from(Value::from(u64::MAX))?, u64::MAX);
+        assert_eq!(i64::try_from(Value::from(i64::MIN))?, i64::MIN);
+        assert_eq!(u32::try_from(&Value::from(7u8))?, 7);
+        assert_eq!(i32::try_from(Value::from(-7))?, -7);
+        assert_eq!(usize::try_from(Value::from(3.0))?, 3);
+        Ok(())
+    }
+
+    #[test]
+    fn try_from_mismatched_kind() {
+        let error = u32::try_from(Value::from("1")).unwrap_err();
+        assert_eq!((error.expected(), error.found()), ("u32", "String"));
+        assert_eq!(error.to_string(), "expected u32, found String");
+        assert_eq!(
+            bool::try_from(Value::Null).unwrap_err().to_string(),
+            "expected bool, found Null"
+        );
+        assert_eq!(
+            String::try_from(Value::from(1u8)).unwrap_err().to_string(),
+            "expected String, found Number"
+        );
+        assert_eq!(
+            f64::try_from(&Value::new_array()).unwrap_err().to_string(),
+            "expected f64, found Array"
+        );
+        assert_eq!(
+            i64::try_from(Value::new_object()).unwrap_err().to_string(),
+            "expected i64, found Object"
+        );
+    }
+
+    #[test]
+    fn try_from_out_of_range() {
+        assert_eq!(
+            u32::try_from(Value::from(5_000_000_000u64))
+                .unwrap_err()
+                .to_string(),
+            "expected u32, found 5000000000"
+        );
+        assert_eq!(
+            u64::try_from(Value::from(-1)).unwrap_err().to_string(),
+            "expected u64, found -1"
+        );
+        assert_eq!(
+            i64::try_from(Value::from(u64::MAX))
+                .unwrap_err()
+                .to_string(),
+            "expected i64, found 18446744073709551615"
+        );
+        assert_eq!(
+            i32::try_from(Value::from(i64::from(i32::MIN) - 1))
+                .unwrap_err()
+                .to_string(),
+            "expected i32, found -2147483649"
+        );
+    }
+
+    #[test]
+    fn number_try_from_boundaries() {
+        use NumberConversionError::*;
+
+        macro_rules! assert_boundaries {
+            ($($integer:ty),*) => {
+                $(
+                    let (min, max) = (<$integer>::MIN, <$integer>::MAX);
+                    assert_eq!(<$integer>::try_from(Number::from(max)), Ok(max));
+                    assert_eq!(<$integer>::try_from(&Number::from(min)), Ok(min));
+                    let above = Number::from(u128::try_from(max).unwrap() + 1);
+                    assert_eq!(<$integer>::try_from(above), Err(TooLarge));
+                    let below = Number::from(i128::try_from(min).unwrap() - 1);
+                    let expected = if min == 0 { Negative } else { TooSmall };
+                    assert_eq!(<$integer>::try_from(below), Err(expected));
+                    assert_eq!(<$integer>::try_from(Number::Float(3.5)), Err(NotAnInteger));
+                    assert_eq!(<$integer>::try_from(Number::Float(-0.0)), Ok(0));
+                )*
+            };
+        }
+        assert_boundaries!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+        assert_eq!(u16::try_from(Number::Float(65535.0)), Ok(65535));
+        assert_eq!(u16::try_from(Number::Float(65536.0)), Err(TooLarge));
+        assert_eq!(u8::try_from(Number::Float(-1.0)), Err(Negative));
+        assert_eq!(u8::try_from(Number::Float(-1.5)), Err(NotAnInteger));
+        assert_eq!(i8::try_from(Number::Float(-129.0)), Err(TooSmall));
+        assert_eq!(u64::try_from(Number::Float(1e300)), Err(TooLarge));
+        assert_eq!(i64::try_from(Number::Float(-1e300)), Err(TooSmall));
+        assert_eq!(u64::try_from(Number::Float(f64::INFINITY)), Err(TooLarge));
+        assert_eq!(
+            u64::try_from(Number::Float(f64::NEG_INFINITY)),
+            Err(Negative)
+        );
+        assert_eq!(u64::try_from(Number::Float(f64::NAN)), Err(NotAnInteger));
+        assert_eq!(u128::try_from(Number::from(u128::MAX)), Ok(u128::MAX));
+        assert_eq!(i128::try_from(Number::from(u128::MAX)), Err(TooLarge));
+        assert_eq!(
+            i8::try_from(Number::NegativeInteger128(i128::MIN)),
+            Err(TooSmall)
+        );
+        assert_eq!(TooLarge.to_string(), "number is too large");
+    }
+
+    #[test]
+    fn try_from_isize() -> Result<(), ConversionError> {
+        assert_eq!(isize::try_from(Value::from(-3))?, -3);
+        assert_eq!(
+            isize::try_from(&Value::from(u64::MAX))
+                .unwrap_err()
+                .to_string(),
+            "expected isize, found 18446744073709551615"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn try_from_non_integral_float() {
+        assert_eq!(
+            u32::try_from(Value::from(3.5)).unwrap_err().to_string(),
+            "expected u32, found 3.5"
+        );
+        assert_eq!(
+            i64::try_from(Value::from(-0.5)).unwrap_err().to_string(),
+            "expected i64, found -0.5"
+        );
+    }
+
+    #[test]
+    fn try_from_option() -> Result<(), ConversionError> {
+        assert_eq!(Option::<i32>::try_from(Value::Null)?, None);
+        assert_eq!(Option::<i32>::try_from(Value::from(1))?, Some(1));
+        assert!(Option::<i32>::try_from(Value::from("1")).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn try_from_containers() -> Result<(), ConversionError> {
+        let value = crate::parse("{\"a\": [\"x\", null], \"b\": []}").unwrap();
+        let map = HashMap::<String, Vec<Option<String>>>::try_from(value)?;
+        assert_eq!(
+            map,
+            HashMap::from([
+                ("a".to_string(), vec![Some("x".to_string()), None]),
+                ("b".to_string(), vec![]),
+            ])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn try_from_container_errors_have_pointer() {
+        let value: Value = (0..500u64)
+            .map(|i| {
+                if i == 417 {
+                    Value::Null
+                } else {
+                    Value::from(i)
+                }
+            })
+            .collect();
+        let error = Vec::<u64>::try_from(value).unwrap_err();
+        assert_eq!(error.pointer(), "/417");
+        assert_eq!(error.to_string(), "expected u64, found Null at \"/417\"");
+
+        let value = crate::parse("{\"a/b\": [[\"x\", 1]]}").unwrap();
+        let error = HashMap::<String, Vec<Vec<String>>>::try_from(value).unwrap_err();
+        assert_eq!(error.pointer(), "/a~1b/0/1");
+
+        let error = Vec::<u64>::try_from(Value::new_object()).unwrap_err();
+        assert_eq!(error.to_string(), "expected Vec, found Object");
+        let error = HashMap::<String, u64>::try_from(Value::new_array()).unwrap_err();
+        assert_eq!(error.to_string(), "expected HashMap, found Array");
+    }
+}
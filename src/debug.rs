@@ -0,0 +1,210 @@
+use crate::serialize::{write_number, write_string, NonFiniteFloats, SerializeOptions};
+use crate::Value;
+
+impl Value {
+    /// Format value as json-like text shortened for logging
+    ///
+    /// - objects and arrays nested deeper than `max_depth` become `{…}` and `[…]`
+    /// - members and elements after `max_items` become `… (+N more)`
+    /// - strings and keys longer than `max_string_len` characters are cut with
+    ///   `…` and followed by their length, like `"abc…" (120 chars)`
+    ///
+    /// The output is not valid json. Object members are sorted by key so the
+    /// same value is always formatted the same.
+    ///
+    /// ```rust
+    /// use wson::number::Number;
+    /// use wson::Value;
+    /// use std::collections::HashMap;
+    /// # fn main() {
+    ///
+    ///
+    /// let value = Value::Object(HashMap::from([
+    ///   ("body".to_string(), Value::String("a".repeat(100))),
+    ///   ("ids".to_string(), Value::Array((0..10).map(|i| Value::Number(Number::PositiveInteger(i))).collect())),
+    ///   ("deep".to_string(), Value::Array(vec![Value::Array(vec![Value::Null])])),
+    /// ]));
+    /// assert_eq!(
+    ///   value.to_debug_string(1, 3, 5),
+    ///   "{\"body\": \"aaaaa…\" (100 chars), \"deep\": [[…]], \"ids\": [0, 1, 2, … (+7 more)]}"
+    /// );
+    /// # }
+    /// ```
+    pub fn to_debug_string(
+        &self,
+        max_depth: usize,
+        max_items: usize,
+        max_string_len: usize,
+    ) -> String {
+        let mut printer = DebugPrinter {
+            output: String::new(),
+            options: SerializeOptions::new().non_finite_floats(NonFiniteFloats::Literal),
+            max_depth,
+            max_items,
+            max_string_len,
+        };
+        printer.write_value(self, 0);
+        printer.output
+    }
+}
+
+struct DebugPrinter {
+    output: String,
+    options: SerializeOptions,
+    max_depth: usize,
+    max_items: usize,
+    max_string_len: usize,
+}
+
+impl DebugPrinter {
+    fn write_value(&mut self, value: &Value, depth: usize) {
+        match value {
+            Value::Object(members) if depth > self.max_depth && !members.is_empty() => {
+                self.output.push_str("{…}")
+            }
+            Value::Object(members) => {
+                let mut members: Vec<_> = members.iter().collect();
+                members.sort_unstable_by_key(|(key, _)| *key);
+                self.output.push('{');
+                for (i, (key, value)) in members.iter().take(self.max_items).enumerate() {
+                    if i > 0 {
+                        self.output.push_str(", ");
+                    }
+                    self.write_string(key);
+                    self.output.push_str(": ");
+                    self.write_value(value, depth + 1);
+                }
+                self.write_rest(members.len());
+                self.output.push('}');
+            }
+            Value::Array(elements) if depth > self.max_depth && !elements.is_empty() => {
+                self.output.push_str("[…]")
+            }
+            Value::Array(elements) => {
+                self.output.push('[');
+                for (i, element) in elements.iter().take(self.max_items).enumerate() {
+                    if i > 0 {
+                        self.output.push_str(", ");
+                    }
+                    self.write_value(element, depth + 1);
+                }
+                self.write_rest(elements.len());
+                self.output.push(']');
+            }
+            Value::Number(number) => {
+                let _ = write_number(&mut self.output, number, &self.options);
+            }
+            Value::String(str) => self.write_string(str),
+            Value::Null => self.output.push_str("null"),
+            Value::Bool(true) => self.output.push_str("true"),
+            Value::Bool(false) => self.output.push_str("false"),
+        }
+    }
+
+    /// Write how many members or elements out of `len` were left out
+    fn write_rest(&mut self, len: usize) {
+        if len > self.max_items {
+            if self.max_items > 0 {
+                self.output.push_str(", ");
+            }
+            self.output
+                .push_str(&format!("… (+{} more)", len - self.max_items));
+        }
+    }
+
+    fn write_string(&mut self, str: &str) {
+        match str.char_indices().nth(self.max_string_len) {
+            Some((end, _)) => {
+                let _ = write_string(&mut self.output, &str[..end], &self.options);
+                self.output.insert(self.output.len() - 1, '…');
+                self.output
+                    .push_str(&format!(" ({} chars)", str.chars().count()));
+            }
+            None => {
+                let _ = write_string(&mut self.output, str, &self.options);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::number::Number;
+    use std::collections::HashMap;
+
+    fn numbers(n: u64) -> Value {
+        Value::Array(
+            (0..n)
+                .map(|i| Value::Number(Number::PositiveInteger(i)))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn within_limits() {
+        let value = Value::Object(HashMap::from([
+            ("b".to_string(), numbers(2)),
+            ("a".to_string(), Value::String("str".to_string())),
+        ]));
+        assert_eq!(
+            value.to_debug_string(5, 5, 5),
+            "{\"a\": \"str\", \"b\": [0, 1]}"
+        );
+    }
+
+    #[test]
+    fn depth_limit() {
+        let value = Value::Array(vec![
+            Value::Array(vec![numbers(1)]),
+            Value::Object(HashMap::new()),
+        ]);
+        assert_eq!(value.to_debug_string(0, 10, 10), "[[…], {}]");
+        assert_eq!(value.to_debug_string(1, 10, 10), "[[[…]], {}]");
+        assert_eq!(value.to_debug_string(2, 10, 10), "[[[0]], {}]");
+    }
+
+    #[test]
+    fn items_limit() {
+        assert_eq!(
+            numbers(140).to_debug_string(1, 3, 10),
+            "[0, 1, 2, … (+137 more)]"
+        );
+        assert_eq!(numbers(3).to_debug_string(1, 0, 10), "[… (+3 more)]");
+        let value = Value::Object(HashMap::from([
+            ("a".to_string(), Value::Null),
+            ("b".to_string(), Value::Null),
+        ]));
+        assert_eq!(
+            value.to_debug_string(1, 1, 10),
+            "{\"a\": null, … (+1 more)}"
+        );
+    }
+
+    #[test]
+    fn string_limit() {
+        let value = Value::String("こんにちは世界".to_string());
+        assert_eq!(value.to_debug_string(1, 1, 5), "\"こんにちは…\" (7 chars)");
+        assert_eq!(value.to_debug_string(1, 1, 7), "\"こんにちは世界\"");
+        let value = Value::Object(HashMap::from([("long key".to_string(), Value::Null)]));
+        assert_eq!(
+            value.to_debug_string(1, 1, 4),
+            "{\"long…\" (8 chars): null}"
+        );
+    }
+
+    #[test]
+    fn combined_limits() {
+        let value = Value::Object(HashMap::from([
+            ("text".to_string(), Value::String("x".repeat(1000))),
+            ("rows".to_string(), Value::Array(vec![numbers(50); 4])),
+            ("nan".to_string(), Value::Number(Number::Float(f64::NAN))),
+        ]));
+        let formatted = value.to_debug_string(1, 3, 4);
+        assert_eq!(
+            formatted,
+            "{\"nan\": NaN, \"rows\": [[…], […], […], … (+1 more)], \"text\": \"xxxx…\" (1000 chars)}"
+        );
+        assert_eq!(formatted, value.to_debug_string(1, 3, 4));
+    }
+}
@@ -0,0 +1,537 @@
+use crate::Value;
+use std::collections::{hash_map, HashMap, HashSet};
+use std::mem;
+
+impl Value {
+    /// Move value out and leave `null` in its place
+    ///
+    /// ```rust
+    /// use wson::{parse, Value};
+    /// # use std::error;
+    /// # fn main() -> Result<(), Box<dyn error::Error>> {
+    ///
+    ///
+    /// let mut value = parse("{\"items\": [{\"id\": 1}]}")?;
+    /// let item = value.pointer_mut("/items/0").map(Value::take);
+    /// assert_eq!(item, Some(parse("{\"id\": 1}")?));
+    /// assert_eq!(value, parse("{\"items\": [null]}")?);
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn take(&mut self) -> Value {
+        mem::replace(self, Value::Null)
+    }
+
+    /// Insert a member into an object and return the value it replaced
+    ///
+    /// `null` is turned into an empty object first, like `value[key] = ...`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if value is neither an object nor `null`.
+    ///
+    /// ```rust
+    /// use wson::{parse, Value};
+    /// # use std::error;
+    /// # fn main() -> Result<(), Box<dyn error::Error>> {
+    ///
+    ///
+    /// let mut value = Value::Null;
+    /// assert_eq!(value.insert("a", Value::Bool(true)), None);
+    /// assert_eq!(value.insert("a", Value::Bool(false)), Some(Value::Bool(true)));
+    /// assert_eq!(value, parse("{\"a\": false}")?);
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn insert(&mut self, key: impl Into<String>, value: Value) -> Option<Value> {
+        if self.is_null() {
+            *self = Value::Object(HashMap::new());
+        }
+        match self {
+            Value::Object(members) => members.insert(key.into(), value),
+            _ => panic!("cannot insert a member into a non-object json value"),
+        }
+    }
+
+    /// Remove a member from an object and return it
+    ///
+    /// Returns `None` when the key is missing or value isn't an object.
+    ///
+    /// ```rust
+    /// use wson::{parse, Value};
+    /// # use std::error;
+    /// # fn main() -> Result<(), Box<dyn error::Error>> {
+    ///
+    ///
+    /// let mut value = parse("{\"a\": true, \"b\": null}")?;
+    /// assert_eq!(value.remove("a"), Some(Value::Bool(true)));
+    /// assert_eq!(value.remove("a"), None);
+    /// assert_eq!(value, parse("{\"b\": null}")?);
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn remove(&mut self, key: &str) -> Option<Value> {
+        self.as_object_mut()?.remove(key)
+    }
+
+    /// Append an element to an array
+    ///
+    /// `null` is turned into an empty array first.
+    ///
+    /// # Panics
+    ///
+    /// Panics if value is neither an array nor `null`.
+    ///
+    /// ```rust
+    /// use wson::{parse, Value};
+    /// # use std::error;
+    /// # fn main() -> Result<(), Box<dyn error::Error>> {
+    ///
+    ///
+    /// let mut value = Value::Null;
+    /// value.push(Value::Bool(true));
+    /// value.push(Value::Null);
+    /// assert_eq!(value, parse("[true, null]")?);
+    /// assert_eq!(value.pop(), Some(Value::Null));
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn push(&mut self, value: Value) {
+        self.elements_or_vivify().push(value)
+    }
+
+    /// Remove the last element of an array and return it
+    ///
+    /// Returns `None` when the array is empty or value isn't an array.
+    pub fn pop(&mut self) -> Option<Value> {
+        self.as_array_mut()?.pop()
+    }
+
+    /// Insert an element into an array at `index`, shifting later elements
+    ///
+    /// `null` is turned into an empty array first.
+    ///
+    /// # Panics
+    ///
+    /// Panics if value is neither an array nor `null`, or `index` is greater
+    /// than the length of the array.
+    ///
+    /// ```rust
+    /// use wson::{parse, Value};
+    /// # use std::error;
+    /// # fn main() -> Result<(), Box<dyn error::Error>> {
+    ///
+    ///
+    /// let mut value = parse("[1, 3]")?;
+    /// value.insert_at(1, parse("2")?);
+    /// assert_eq!(value, parse("[1, 2, 3]")?);
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn insert_at(&mut self, index: usize, value: Value) {
+        self.elements_or_vivify().insert(index, value)
+    }
+
+    /// Shorten an array to `len` elements
+    ///
+    /// Does nothing when the array is already shorter or value isn't an array.
+    pub fn truncate(&mut self, len: usize) {
+        if let Some(elements) = self.as_array_mut() {
+            elements.truncate(len)
+        }
+    }
+
+    /// Remove elements of an array equal to the element before them
+    ///
+    /// Does nothing when value isn't an array.
+    ///
+    /// ```rust
+    /// use wson::json;
+    /// # fn main() {
+    ///
+    ///
+    /// let mut value = json!([1, 1, 2, 1, [3], [3]]);
+    /// value.dedup();
+    /// assert_eq!(value, json!([1, 2, 1, [3]]));
+    /// # }
+    /// ```
+    pub fn dedup(&mut self) {
+        if let Some(elements) = self.as_array_mut() {
+            elements.dedup()
+        }
+    }
+
+    /// Keep only the first element of an array for each distinct value at
+    /// `pointer` in the elements, keeping their order
+    ///
+    /// Elements where nothing is at `pointer` are all kept. Does nothing when
+    /// value isn't an array.
+    ///
+    /// ```rust
+    /// use wson::json;
+    /// # fn main() {
+    ///
+    ///
+    /// let mut events = json!([{"id": 1, "n": "a"}, {"id": 2}, {"id": 1, "n": "b"}]);
+    /// events.unique_by("/id");
+    /// assert_eq!(events, json!([{"id": 1, "n": "a"}, {"id": 2}]));
+    /// # }
+    /// ```
+    pub fn unique_by(&mut self, pointer: &str) {
+        let elements = match self.as_array_mut() {
+            Some(elements) => elements,
+            None => return,
+        };
+        let mut seen = HashSet::new();
+        let keep: Vec<bool> = elements
+            .iter()
+            .map(|element| element.pointer(pointer).is_none_or(|key| seen.insert(key)))
+            .collect();
+        let mut keep = keep.into_iter();
+        elements.retain(|_| keep.next().unwrap_or(true));
+    }
+
+    /// Entry of a member in an object for in-place lookup and insertion
+    ///
+    /// `null` is turned into an empty object first.
+    ///
+    /// # Panics
+    ///
+    /// Panics if value is neither an object nor `null`.
+    ///
+    /// ```rust
+    /// use wson::number::Number;
+    /// use wson::{parse, Value};
+    /// # use std::error;
+    /// # fn main() -> Result<(), Box<dyn error::Error>> {
+    ///
+    ///
+    /// let mut counts = Value::Null;
+    /// for word in ["a", "b", "a"] {
+    ///     counts
+    ///         .entry(word)
+    ///         .and_modify(|count| {
+    ///             if let Some(n) = count.as_u64() {
+    ///                 *count = Value::Number(Number::PositiveInteger(n + 1));
+    ///             }
+    ///         })
+    ///         .or_insert(Value::Number(Number::PositiveInteger(1)));
+    /// }
+    /// assert_eq!(counts, parse("{\"a\": 2, \"b\": 1}")?);
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn entry(&mut self, key: impl Into<String>) -> Entry<'_> {
+        if self.is_null() {
+            *self = Value::Object(HashMap::new());
+        }
+        match self {
+            Value::Object(members) => Entry(members.entry(key.into())),
+            _ => panic!("cannot get an entry of a non-object json value"),
+        }
+    }
+
+    pub(crate) fn elements_or_vivify(&mut self) -> &mut Vec<Value> {
+        if self.is_null() {
+            *self = Value::Array(vec![]);
+        }
+        match self {
+            Value::Array(elements) => elements,
+            _ => panic!("cannot add an element to a non-array json value"),
+        }
+    }
+}
+
+/// Member of an object which may or may not exist, from `Value::entry`
+pub struct Entry<'a>(hash_map::Entry<'a, String, Value>);
+
+impl<'a> Entry<'a> {
+    pub fn key(&self) -> &str {
+        self.0.key()
+    }
+
+    /// Insert `default` if the member is missing, and return the member
+    pub fn or_insert(self, default: Value) -> &'a mut Value {
+        self.0.or_insert(default)
+    }
+
+    /// Insert the result of `default` if the member is missing, and return the member
+    pub fn or_insert_with<F: FnOnce() -> Value>(self, default: F) -> &'a mut Value {
+        self.0.or_insert_with(default)
+    }
+
+    /// Call `f` with the member if it exists
+    pub fn and_modify<F: FnOnce(&mut Value)>(self, f: F) -> Self {
+        Entry(self.0.and_modify(f))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json;
+    use crate::number::Number;
+
+    #[test]
+    fn take_every_variant() {
+        let values = [
+            Value::Object(HashMap::from([("a".to_string(), Value::Bool(true))])),
+            Value::Array(vec![Value::Bool(false), Value::Null]),
+            Value::Number(Number::Float(1.5)),
+            Value::String("a".to_string()),
+            Value::Null,
+            Value::Bool(true),
+            Value::Bool(false),
+        ];
+        for value in values {
+            let mut slot = value.clone();
+            assert_eq!(slot.take(), value);
+            assert_eq!(slot, Value::Null);
+        }
+    }
+
+    #[test]
+    fn take_from_parent() {
+        let mut value = Value::Array(vec![
+            Value::Array(vec![Value::Bool(true)]),
+            Value::Bool(false),
+        ]);
+        let moved = value.get_index_mut(0).map(Value::take);
+        assert_eq!(moved, Some(Value::Array(vec![Value::Bool(true)])));
+        assert_eq!(value, Value::Array(vec![Value::Null, Value::Bool(false)]));
+    }
+
+    #[test]
+    fn insert_overwrites() {
+        let mut value = Value::Object(HashMap::from([("a".to_string(), Value::Bool(true))]));
+        assert_eq!(
+            value.insert("a".to_string(), Value::Null),
+            Some(Value::Bool(true))
+        );
+        assert_eq!(value.insert("b", Value::Bool(false)), None);
+        assert_eq!(
+            value,
+            Value::Object(HashMap::from([
+                ("a".to_string(), Value::Null),
+                ("b".to_string(), Value::Bool(false))
+            ]))
+        );
+    }
+
+    #[test]
+    fn insert_into_null() {
+        let mut value = Value::Null;
+        assert_eq!(value.insert("a", Value::Bool(true)), None);
+        assert_eq!(
+            value,
+            Value::Object(HashMap::from([("a".to_string(), Value::Bool(true))]))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot insert a member into a non-object json value")]
+    fn insert_into_array_panics() {
+        Value::Array(vec![]).insert("a", Value::Null);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot insert a member into a non-object json value")]
+    fn insert_into_scalar_panics() {
+        Value::Bool(true).insert("a", Value::Null);
+    }
+
+    #[test]
+    fn remove_members() {
+        let mut value = Value::Object(HashMap::from([("a".to_string(), Value::Bool(true))]));
+        assert_eq!(value.remove("b"), None);
+        assert_eq!(value.remove("a"), Some(Value::Bool(true)));
+        assert_eq!(value, Value::Object(HashMap::new()));
+
+        for mut value in [
+            Value::Null,
+            Value::Array(vec![Value::Bool(true)]),
+            Value::String("a".to_string()),
+            Value::Bool(false),
+        ] {
+            let original = value.clone();
+            assert_eq!(value.remove("a"), None);
+            assert_eq!(value, original);
+        }
+    }
+
+    #[test]
+    fn push_and_pop() {
+        let mut value = Value::Array(vec![Value::Bool(true)]);
+        value.push(Value::Bool(false));
+        assert_eq!(value.pop(), Some(Value::Bool(false)));
+        assert_eq!(value.pop(), Some(Value::Bool(true)));
+        assert_eq!(value.pop(), None);
+        assert_eq!(value, Value::Array(vec![]));
+    }
+
+    #[test]
+    fn push_onto_null() {
+        let mut value = Value::Null;
+        value.push(Value::Bool(true));
+        assert_eq!(value, Value::Array(vec![Value::Bool(true)]));
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot add an element to a non-array json value")]
+    fn push_onto_object_panics() {
+        Value::Object(HashMap::new()).push(Value::Null);
+    }
+
+    #[test]
+    fn pop_on_other_values() {
+        for mut value in [
+            Value::Null,
+            Value::Object(HashMap::new()),
+            Value::Bool(true),
+        ] {
+            let original = value.clone();
+            assert_eq!(value.pop(), None);
+            assert_eq!(value, original);
+        }
+    }
+
+    #[test]
+    fn insert_at_positions() {
+        let mut value = Value::Null;
+        value.insert_at(0, Value::Null);
+        value.insert_at(0, Value::Bool(true));
+        value.insert_at(2, Value::Bool(false));
+        assert_eq!(
+            value,
+            Value::Array(vec![Value::Bool(true), Value::Null, Value::Bool(false)])
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn insert_at_out_of_bounds_panics() {
+        Value::Array(vec![Value::Bool(true)]).insert_at(2, Value::Null);
+    }
+
+    #[test]
+    fn truncate_arrays() {
+        let mut value = Value::Array(vec![Value::Bool(true), Value::Bool(false), Value::Null]);
+        value.truncate(5);
+        assert_eq!(value.as_array().map(Vec::len), Some(3));
+        value.truncate(1);
+        assert_eq!(value, Value::Array(vec![Value::Bool(true)]));
+
+        let mut value = Value::String("abc".to_string());
+        value.truncate(1);
+        assert_eq!(value, Value::String("abc".to_string()));
+    }
+
+    #[test]
+    fn entry_vacant_and_occupied() {
+        let mut value = Value::Object(HashMap::from([(
+            "list".to_string(),
+            Value::Array(vec![Value::Bool(true)]),
+        )]));
+        value
+            .entry("list")
+            .or_insert_with(|| Value::Array(vec![]))
+            .push(Value::Bool(false));
+        value
+            .entry("new")
+            .or_insert_with(|| Value::Array(vec![]))
+            .push(Value::Null);
+        assert_eq!(
+            value["list"],
+            Value::Array(vec![Value::Bool(true), Value::Bool(false)])
+        );
+        assert_eq!(value["new"], Value::Array(vec![Value::Null]));
+
+        let mut called = false;
+        value.entry("missing").and_modify(|_| called = true);
+        assert!(!called);
+        assert_eq!(value.get("missing"), None);
+
+        let entry = value
+            .entry("list".to_string())
+            .and_modify(|list| list.truncate(0));
+        assert_eq!(entry.key(), "list");
+        assert_eq!(entry.or_insert(Value::Null), &Value::Array(vec![]));
+    }
+
+    #[test]
+    fn entry_on_null() {
+        let mut value = Value::Null;
+        *value.entry("a").or_insert(Value::Null) = Value::Bool(true);
+        assert_eq!(
+            value,
+            Value::Object(HashMap::from([("a".to_string(), Value::Bool(true))]))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot get an entry of a non-object json value")]
+    fn entry_on_array_panics() {
+        Value::Array(vec![]).entry("a");
+    }
+
+    #[test]
+    fn dedup_adjacent() {
+        let mut value = json!([1, 1, 1.5, "a", "a", null, null, {"b": [1]}, {"b": [1]}, 1]);
+        value.dedup();
+        assert_eq!(value, json!([1, 1.5, "a", null, {"b": [1]}, 1]));
+
+        let mut value = json!([1, 1.0, 2.0, 2]);
+        value.dedup();
+        assert_eq!(value, json!([1, 2]));
+
+        let mut value = json!([2, 2, 2, 2]);
+        value.dedup();
+        assert_eq!(value, json!([2]));
+
+        let mut value = json!([1, 2, 3]);
+        value.dedup();
+        assert_eq!(value, json!([1, 2, 3]));
+
+        let mut value = json!({"a": 1});
+        value.dedup();
+        assert_eq!(value, json!({"a": 1}));
+    }
+
+    #[test]
+    fn unique_by_nested_field() {
+        let mut value = json!([
+            {"event": {"id": "x"}, "n": 1},
+            {"event": {"id": "y"}, "n": 2},
+            {"event": {"id": "x"}, "n": 3},
+            {"event": {}, "n": 4},
+            {"event": {"id": "y"}, "n": 5},
+            {"event": {}, "n": 6},
+            {"event": {"id": {"nested": true}}, "n": 7},
+            {"event": {"id": {"nested": true}}, "n": 8},
+        ]);
+        value.unique_by("/event/id");
+        let n: Vec<_> = value.iter().filter_map(|(_, e)| e["n"].as_u64()).collect();
+        assert_eq!(n, [1, 2, 4, 6, 7]);
+    }
+
+    #[test]
+    fn unique_by_all_and_no_duplicates() {
+        let mut value = json!([{"k": 1}, {"k": 1}, {"k": 1}]);
+        value.unique_by("/k");
+        assert_eq!(value, json!([{"k": 1}]));
+
+        let mut value = json!([{"k": 1}, {"k": 2}, {"k": "1"}, {"k": 1.5}]);
+        value.unique_by("/k");
+        assert_eq!(value, json!([{"k": 1}, {"k": 2}, {"k": "1"}, {"k": 1.5}]));
+
+        let mut value = json!(["a", "b", "a"]);
+        value.unique_by("");
+        assert_eq!(value, json!(["a", "b"]));
+    }
+}
@@ -0,0 +1,139 @@
+use crate::number::Number;
+use crate::ord::numeric_cmp;
+use crate::Value;
+use std::cmp::Ordering;
+
+// Comparisons with primitives, so that `value == "ok"` or `value == 3`
+// work. Numbers compare by numeric value whatever their variant, and a value
+// of another kind is never equal.
+
+impl PartialEq<str> for Value {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == Some(other)
+    }
+}
+
+impl PartialEq<&str> for Value {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == Some(*other)
+    }
+}
+
+impl PartialEq<String> for Value {
+    fn eq(&self, other: &String) -> bool {
+        self.as_str() == Some(other.as_str())
+    }
+}
+
+impl PartialEq<bool> for Value {
+    fn eq(&self, other: &bool) -> bool {
+        self.as_bool() == Some(*other)
+    }
+}
+
+impl PartialEq<i64> for Value {
+    fn eq(&self, other: &i64) -> bool {
+        equals_number(self, &Number::NegativeInteger(*other))
+    }
+}
+
+impl PartialEq<u64> for Value {
+    fn eq(&self, other: &u64) -> bool {
+        equals_number(self, &Number::PositiveInteger(*other))
+    }
+}
+
+/// NaN is never equal, like between `f64`s
+impl PartialEq<f64> for Value {
+    fn eq(&self, other: &f64) -> bool {
+        !other.is_nan() && equals_number(self, &Number::Float(*other))
+    }
+}
+
+fn equals_number(value: &Value, number: &Number) -> bool {
+    match value {
+        Value::Number(n) => numeric_cmp(n, number) == Ordering::Equal,
+        _ => false,
+    }
+}
+
+macro_rules! reversed {
+    ($($primitive:ty),*) => {
+        $(
+            impl PartialEq<Value> for $primitive {
+                fn eq(&self, other: &Value) -> bool {
+                    other == self
+                }
+            }
+        )*
+    };
+}
+
+reversed!(str, &str, String, bool, i64, u64, f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn strings() {
+        let value = Value::String("ok".to_string());
+        assert_eq!(value, "ok");
+        assert_eq!(value, *"ok");
+        assert_eq!(value, "ok".to_string());
+        assert_eq!("ok", value);
+        assert_eq!("ok".to_string(), value);
+        assert_ne!(value, "ng");
+        assert_ne!(Value::Null, "null");
+    }
+
+    #[test]
+    fn booleans() {
+        assert_eq!(Value::Bool(true), true);
+        assert_eq!(false, Value::Bool(false));
+        assert_ne!(Value::Bool(true), false);
+        assert_ne!(Value::String("true".to_string()), true);
+        assert_ne!(Value::Null, false);
+    }
+
+    #[test]
+    fn numbers_across_variants() {
+        assert_eq!(Value::Number(Number::PositiveInteger(3)), 3i64);
+        assert_eq!(Value::Number(Number::PositiveInteger(3)), 3u64);
+        assert_eq!(Value::Number(Number::PositiveInteger(3)), 3.0);
+        assert_eq!(Value::Number(Number::Float(3.0)), 3u64);
+        assert_eq!(Value::Number(Number::NegativeInteger(-3)), -3.0);
+        assert_eq!(Value::Number(Number::NegativeInteger(0)), 0u64);
+        assert_eq!(Value::Number(Number::Float(-0.0)), 0i64);
+        assert_eq!(-3i64, Value::Number(Number::NegativeInteger(-3)));
+        assert_eq!(u64::MAX, Value::Number(Number::PositiveInteger(u64::MAX)));
+        assert_ne!(
+            Value::Number(Number::PositiveInteger(u64::MAX)),
+            18446744073709551616.0
+        );
+        assert_ne!(Value::Number(Number::Float(2.5)), 2u64);
+        assert_ne!(Value::Number(Number::Float(f64::NAN)), f64::NAN);
+    }
+
+    #[test]
+    fn mismatched_kinds() {
+        assert_ne!(Value::String("1".to_string()), 1i64);
+        assert_ne!(Value::String("1".to_string()), 1u64);
+        assert_ne!(Value::String("1".to_string()), 1.0);
+        assert_ne!(Value::Bool(true), 1u64);
+        assert_ne!(Value::Number(Number::PositiveInteger(1)), "1");
+        assert_ne!(Value::Number(Number::PositiveInteger(1)), true);
+        assert_ne!(Value::Array(vec![Value::Bool(true)]), true);
+        assert_ne!(Value::Object(HashMap::new()), "");
+    }
+
+    #[test]
+    fn indexed_values() {
+        let value = crate::parse("{\"status\": \"ok\", \"count\": 2, \"done\": true}").unwrap();
+        assert_eq!(value["status"], "ok");
+        assert_eq!(value["count"], 2u64);
+        assert_eq!(value["done"], true);
+        assert_ne!(value["missing"], "ok");
+    }
+}
@@ -0,0 +1,473 @@
+use crate::pointer;
+use std::{error::Error, fmt, io};
+
+/// Error with the position in the input where parsing failed
+///
+/// ```rust
+/// use wson::error::ParseErrorKind;
+/// use wson::minify;
+/// # fn main() {
+///
+///
+/// let error = minify("{\"a\": 1,\n \"b\" 2}").unwrap_err();
+/// assert_eq!(error.kind(), &ParseErrorKind::Expected("':'"));
+/// assert_eq!((error.line(), error.column()), (2, 6));
+/// assert_eq!(error.to_string(), "expected ':' at line 2 column 6");
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    kind: ParseErrorKind,
+    offset: usize,
+    line: usize,
+    column: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseErrorKind {
+    /// Something else was found where the described token was expected
+    Expected(&'static str),
+    /// Characters are left after the json value
+    TrailingCharacters,
+    /// A number is too large: a float beyond `f64`, or an integer beyond
+    /// `u128` or `i128` with `ParseOptions::strict_integers`
+    NumberOutOfRange,
+    /// A `\u` escape of a surrogate which isn't part of a pair, which
+    /// can't be in a `String`
+    LoneSurrogate,
+    /// A control character, U+0000 to U+001F, which isn't escaped in a
+    /// string
+    ControlCharacter,
+}
+
+impl ParseError {
+    /// Create an error for `kind` found at `rest`, which is a suffix of `input`
+    pub(crate) fn new(kind: ParseErrorKind, input: &str, rest: &str) -> Self {
+        let offset = input.len() - rest.len();
+        let consumed = &input[..offset];
+        let line = consumed.matches('\n').count() + 1;
+        let line_start = consumed.rfind('\n').map_or(0, |i| i + 1);
+        let column = consumed[line_start..].chars().count() + 1;
+
+        ParseError {
+            kind,
+            offset,
+            line,
+            column,
+        }
+    }
+
+    pub fn kind(&self) -> &ParseErrorKind {
+        &self.kind
+    }
+
+    /// Byte offset in the input
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// 1-based line number
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// 1-based column number, counted in characters
+    pub fn column(&self) -> usize {
+        self.column
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at line {} column {}",
+            self.kind, self.line, self.column
+        )
+    }
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Expected(expected) => write!(f, "expected {}", expected),
+            Self::TrailingCharacters => write!(f, "trailing characters"),
+            Self::NumberOutOfRange => write!(f, "number out of range"),
+            Self::LoneSurrogate => write!(f, "unpaired surrogate escape"),
+            Self::ControlCharacter => write!(f, "control character in string"),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// Error from serializing a `Value`
+#[derive(Debug)]
+pub enum SerializeError {
+    /// NaN or Infinity was found at the JSON Pointer `pointer`
+    NonFiniteFloat { pointer: String },
+    /// `JsonWriter` was called in an order which doesn't make valid json
+    InvalidStructure(&'static str),
+    /// Writing into an `io::Write` failed
+    Io(io::Error),
+    /// Writing into a `fmt::Write` failed
+    Fmt(fmt::Error),
+}
+
+impl SerializeError {
+    /// Add the reference token of the member or element the error is in
+    pub(crate) fn within(self, token: &str) -> Self {
+        match self {
+            Self::NonFiniteFloat { pointer } => Self::NonFiniteFloat {
+                pointer: format!("/{}{}", pointer::escape(token), pointer),
+            },
+            error => error,
+        }
+    }
+}
+
+impl fmt::Display for SerializeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NonFiniteFloat { pointer } => {
+                write!(f, "NaN or Infinity can't be serialized at \"{}\"", pointer)
+            }
+            Self::InvalidStructure(message) => write!(f, "{}", message),
+            Self::Io(error) => write!(f, "{}", error),
+            Self::Fmt(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl Error for SerializeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::NonFiniteFloat { .. } | Self::InvalidStructure(_) => None,
+            Self::Io(error) => Some(error),
+            Self::Fmt(error) => Some(error),
+        }
+    }
+}
+
+impl From<io::Error> for SerializeError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<fmt::Error> for SerializeError {
+    fn from(error: fmt::Error) -> Self {
+        Self::Fmt(error)
+    }
+}
+
+/// Error from changing a `Value` at a JSON Pointer
+#[derive(Debug, Clone, PartialEq)]
+pub enum PointerError {
+    /// The pointer doesn't start with '/' or has a '~' not followed by '0' or '1'
+    InvalidPointer,
+    /// The parent of `pointer` is neither an object, an array nor null
+    NotAContainer { pointer: String },
+    /// The last token of `pointer` isn't a valid index in its array
+    InvalidIndex { pointer: String },
+}
+
+impl fmt::Display for PointerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidPointer => write!(f, "invalid json pointer"),
+            Self::NotAContainer { pointer } => {
+                write!(f, "the parent of \"{}\" is not an object or array", pointer)
+            }
+            Self::InvalidIndex { pointer } => {
+                write!(f, "\"{}\" is not a valid array index", pointer)
+            }
+        }
+    }
+}
+
+impl Error for PointerError {}
+
+/// Error from applying a JSON Patch
+#[derive(Debug, Clone, PartialEq)]
+pub enum PatchError {
+    /// `pointer` doesn't start with '/' or has a '~' not followed by '0' or '1'
+    InvalidPointer { pointer: String },
+    /// Nothing is at `pointer`, or at its parent when adding
+    NotFound { pointer: String },
+    /// The last token of `pointer` isn't a valid index in its array
+    InvalidIndex { pointer: String },
+    /// A move from `from` into `path`, which is inside it
+    MoveIntoChild { from: String, path: String },
+    /// The value at `pointer` isn't the one a test expected
+    TestFailed { pointer: String },
+}
+
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidPointer { pointer } => write!(f, "invalid json pointer \"{}\"", pointer),
+            Self::NotFound { pointer } => write!(f, "nothing at \"{}\"", pointer),
+            Self::InvalidIndex { pointer } => {
+                write!(f, "\"{}\" is not a valid array index", pointer)
+            }
+            Self::MoveIntoChild { from, path } => {
+                write!(f, "cannot move \"{}\" into its child \"{}\"", from, path)
+            }
+            Self::TestFailed { pointer } => write!(f, "test failed at \"{}\"", pointer),
+        }
+    }
+}
+
+impl Error for PatchError {}
+
+/// Error from `Value::group_by`
+#[derive(Debug, Clone, PartialEq)]
+pub enum GroupError {
+    /// Only arrays can be grouped, and the value was a `found`
+    NotAnArray { found: String },
+    /// The key pointer doesn't start with '/' or has a '~' not followed by
+    /// '0' or '1'
+    InvalidPointer { pointer: String },
+}
+
+impl fmt::Display for GroupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotAnArray { found } => write!(f, "expected Array, found {}", found),
+            Self::InvalidPointer { pointer } => write!(f, "invalid json pointer \"{}\"", pointer),
+        }
+    }
+}
+
+impl Error for GroupError {}
+
+/// Error from `Value::try_get_many`
+#[derive(Debug, Clone, PartialEq)]
+pub enum GetManyError {
+    /// Only objects have members, and the value was a `found`
+    NotAnObject { found: String },
+    /// The keys which are not in the object, in the order they were asked for
+    MissingKeys { keys: Vec<String> },
+}
+
+impl fmt::Display for GetManyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotAnObject { found } => write!(f, "expected Object, found {}", found),
+            Self::MissingKeys { keys } => {
+                let keys: Vec<_> = keys.iter().map(|key| format!("\"{}\"", key)).collect();
+                write!(f, "missing keys {}", keys.join(", "))
+            }
+        }
+    }
+}
+
+impl Error for GetManyError {}
+
+/// Error from converting a `Value` into a Rust type
+///
+/// ```rust
+/// use wson::Value;
+/// # fn main() {
+///
+///
+/// let error = u32::try_from(Value::String("1".to_string())).unwrap_err();
+/// assert_eq!(error.to_string(), "expected u32, found String");
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConversionError {
+    expected: String,
+    found: String,
+    pointer: String,
+}
+
+impl ConversionError {
+    /// Error for finding `found` where `expected` was needed
+    pub fn new(expected: impl Into<String>, found: impl Into<String>) -> Self {
+        ConversionError {
+            expected: expected.into(),
+            found: found.into(),
+            pointer: String::new(),
+        }
+    }
+
+    /// Add the key or index of the member or element the error is in
+    ///
+    /// Call it on the way out, innermost first, to build the pointer.
+    pub fn within(mut self, token: &str) -> Self {
+        self.pointer = format!("/{}{}", pointer::escape(token), self.pointer);
+        self
+    }
+
+    /// Name of the type which was converted into
+    pub fn expected(&self) -> &str {
+        &self.expected
+    }
+
+    /// Kind of the value found, or the number if it didn't fit
+    pub fn found(&self) -> &str {
+        &self.found
+    }
+
+    /// JSON Pointer to the value which failed, empty for the whole value
+    pub fn pointer(&self) -> &str {
+        &self.pointer
+    }
+}
+
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "expected {}, found {}", self.expected, self.found)?;
+        if !self.pointer.is_empty() {
+            write!(f, " at \"{}\"", self.pointer)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for ConversionError {}
+
+/// Error from converting a `Number` into an integer type
+///
+/// ```rust
+/// use wson::error::NumberConversionError;
+/// use wson::number::Number;
+/// # fn main() {
+///
+///
+/// assert_eq!(u16::try_from(Number::Float(65535.0)), Ok(65535));
+/// assert_eq!(u16::try_from(Number::PositiveInteger(65536)), Err(NumberConversionError::TooLarge));
+/// assert_eq!(u16::try_from(Number::NegativeInteger(-1)), Err(NumberConversionError::Negative));
+/// assert_eq!(u16::try_from(Number::Float(3.5)), Err(NumberConversionError::NotAnInteger));
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum NumberConversionError {
+    /// The number is below zero, and the type is unsigned
+    Negative,
+    /// The number is above the maximum of the type
+    TooLarge,
+    /// The number is below the minimum of the signed type
+    TooSmall,
+    /// The number is a float with a fractional part, or NaN
+    NotAnInteger,
+}
+
+impl fmt::Display for NumberConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Negative => write!(f, "number is negative"),
+            Self::TooLarge => write!(f, "number is too large"),
+            Self::TooSmall => write!(f, "number is too small"),
+            Self::NotAnInteger => write!(f, "number is not an integer"),
+        }
+    }
+}
+
+impl Error for NumberConversionError {}
+
+/// Error from `FromJson`, which is the same as from `TryFrom<Value>`
+pub type DecodeError = ConversionError;
+
+/// Error from `Value::unflatten` for a key which goes through another key's
+/// value, like `a.b` when `a` is `1`
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnflattenError {
+    key: String,
+}
+
+impl UnflattenError {
+    pub(crate) fn new(key: impl Into<String>) -> Self {
+        UnflattenError { key: key.into() }
+    }
+
+    /// The flattened key which conflicts
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+}
+
+impl fmt::Display for UnflattenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "\"{}\" conflicts with another key", self.key)
+    }
+}
+
+impl Error for UnflattenError {}
+
+/// Error from renaming keys, when two keys of an object get the same name
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyCollisionError {
+    pointer: String,
+    key: String,
+}
+
+impl KeyCollisionError {
+    pub(crate) fn new(pointer: impl Into<String>, key: impl Into<String>) -> Self {
+        KeyCollisionError {
+            pointer: pointer.into(),
+            key: key.into(),
+        }
+    }
+
+    /// JSON Pointer of the object
+    pub fn pointer(&self) -> &str {
+        &self.pointer
+    }
+
+    /// The name more than one key was renamed to
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+}
+
+impl fmt::Display for KeyCollisionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "more than one key renamed to \"{}\" in \"{}\"",
+            self.key, self.pointer
+        )
+    }
+}
+
+impl Error for KeyCollisionError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn position_at_start() {
+        let error = ParseError::new(ParseErrorKind::Expected("value"), "x", "x");
+        assert_eq!((error.offset(), error.line(), error.column()), (0, 1, 1));
+    }
+
+    #[test]
+    fn position_after_newlines() {
+        let input = "[\n  1,\n  x]";
+        let error = ParseError::new(ParseErrorKind::Expected("value"), input, "x]");
+        assert_eq!((error.offset(), error.line(), error.column()), (9, 3, 3));
+    }
+
+    #[test]
+    fn column_counts_characters() {
+        let input = "\"こんにちは\" x";
+        let error = ParseError::new(ParseErrorKind::TrailingCharacters, input, "x");
+        assert_eq!(error.column(), 9);
+    }
+
+    #[test]
+    fn serialize_error_pointer() {
+        let error = SerializeError::NonFiniteFloat {
+            pointer: String::new(),
+        };
+        let error = error.within("0").within("a/b~c");
+        assert_eq!(
+            error.to_string(),
+            "NaN or Infinity can't be serialized at \"/a~1b~0c/0\""
+        );
+    }
+}
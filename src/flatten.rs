@@ -0,0 +1,320 @@
+use crate::error::UnflattenError;
+use crate::pointer::array_index;
+use crate::Value;
+use std::collections::HashMap;
+
+impl Value {
+    /// Flatten nested objects and arrays into one map of joined keys
+    ///
+    /// Keys are the keys and indices on the way to each leaf joined with
+    /// `sep`. Leaves are scalars and empty objects and arrays. A `sep` in a
+    /// key is escaped as `\` followed by `sep`, and `\` as `\\`. A scalar is
+    /// flattened into the key `""`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `sep` is empty.
+    ///
+    /// ```rust
+    /// use wson::json;
+    /// use std::collections::HashMap;
+    /// # fn main() {
+    ///
+    ///
+    /// let value = json!({"a": {"b": [1, 2]}, "c.d": true});
+    /// assert_eq!(
+    ///     value.flatten("."),
+    ///     HashMap::from([
+    ///         ("a.b.0".to_string(), json!(1)),
+    ///         ("a.b.1".to_string(), json!(2)),
+    ///         ("c\\.d".to_string(), json!(true)),
+    ///     ])
+    /// );
+    /// # }
+    /// ```
+    pub fn flatten(&self, sep: &str) -> HashMap<String, Value> {
+        assert!(!sep.is_empty(), "separator must not be empty");
+        let mut flattened = HashMap::new();
+        match self {
+            Value::Object(members) if !members.is_empty() => {
+                flatten_into(&mut flattened, None, self, sep)
+            }
+            Value::Array(elements) if !elements.is_empty() => {
+                flatten_into(&mut flattened, None, self, sep)
+            }
+            Value::Object(_) | Value::Array(_) => {}
+            scalar => {
+                flattened.insert(String::new(), scalar.clone());
+            }
+        }
+        flattened
+    }
+
+    /// Build nested objects and arrays back from keys joined with `sep`
+    ///
+    /// Keys are split at each `sep` not escaped with `\`, as `flatten`
+    /// writes them. An object whose keys are exactly `0`, `1`, ... up to its
+    /// length, without leading zeros, becomes an array. Any other keys stay
+    /// object keys. The result is always an object or an array, so
+    /// `flatten` and `unflatten` round trip for objects and arrays that
+    /// have no such keys.
+    ///
+    /// Fails when a key goes through another key's value, like `a.b` when
+    /// `a` is `1`, unless that value is an empty object.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `sep` is empty.
+    ///
+    /// ```rust
+    /// use wson::{json, Value};
+    /// # use std::error;
+    /// # fn main() -> Result<(), Box<dyn error::Error>> {
+    ///
+    ///
+    /// let flattened = [
+    ///     ("a.b.0".to_string(), json!(1)),
+    ///     ("a.b.1".to_string(), json!(2)),
+    ///     ("a.c.1".to_string(), json!(3)),
+    /// ];
+    /// assert_eq!(
+    ///     Value::unflatten(flattened, ".")?,
+    ///     json!({"a": {"b": [1, 2], "c": {"1": 3}}})
+    /// );
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn unflatten<I>(flattened: I, sep: &str) -> Result<Value, UnflattenError>
+    where
+        I: IntoIterator<Item = (String, Value)>,
+    {
+        assert!(!sep.is_empty(), "separator must not be empty");
+        let mut root = Value::Object(HashMap::new());
+        for (key, value) in flattened {
+            let tokens = split(&key, sep);
+            let (last, parents) = tokens.split_last().unwrap_or((&key, &[]));
+            let mut node = &mut root;
+            for token in parents {
+                node = match node {
+                    Value::Object(members) => members
+                        .entry(token.clone())
+                        .or_insert_with(|| Value::Object(HashMap::new())),
+                    _ => return Err(UnflattenError::new(key)),
+                };
+            }
+            let members = match node {
+                Value::Object(members) => members,
+                _ => return Err(UnflattenError::new(key)),
+            };
+            match members.get(last) {
+                None => {
+                    members.insert(last.clone(), value);
+                }
+                // an empty object and the members other keys put in it
+                Some(Value::Object(_)) if value == Value::Object(HashMap::new()) => {}
+                Some(_) => return Err(UnflattenError::new(key)),
+            }
+        }
+        into_arrays(&mut root);
+        Ok(root)
+    }
+}
+
+fn flatten_into(
+    flattened: &mut HashMap<String, Value>,
+    key: Option<String>,
+    value: &Value,
+    sep: &str,
+) {
+    let join = |token: &str| match &key {
+        Some(key) => format!("{}{}{}", key, sep, escape(token, sep)),
+        None => escape(token, sep),
+    };
+    match value {
+        Value::Object(members) if !members.is_empty() => {
+            for (token, member) in members {
+                flatten_into(flattened, Some(join(token)), member, sep);
+            }
+        }
+        Value::Array(elements) if !elements.is_empty() => {
+            for (index, element) in elements.iter().enumerate() {
+                flatten_into(flattened, Some(join(&index.to_string())), element, sep);
+            }
+        }
+        leaf => {
+            flattened.insert(key.unwrap_or_default(), leaf.clone());
+        }
+    }
+}
+
+/// Write `\` as `\\` and `sep` as `\` followed by `sep`
+fn escape(token: &str, sep: &str) -> String {
+    token
+        .replace('\\', "\\\\")
+        .replace(sep, &format!("\\{}", sep))
+}
+
+/// Split `key` at each `sep` which isn't escaped, and unescape the parts
+fn split(key: &str, sep: &str) -> Vec<String> {
+    let mut tokens = vec![String::new()];
+    let mut rest = key;
+    while let Some(c) = rest.chars().next() {
+        let token = tokens.last_mut().unwrap();
+        if let Some(escaped) = rest.strip_prefix('\\') {
+            if let Some(after) = escaped.strip_prefix(sep) {
+                token.push_str(sep);
+                rest = after;
+            } else if let Some(after) = escaped.strip_prefix('\\') {
+                token.push('\\');
+                rest = after;
+            } else {
+                token.push('\\');
+                rest = escaped;
+            }
+        } else if let Some(after) = rest.strip_prefix(sep) {
+            tokens.push(String::new());
+            rest = after;
+        } else {
+            token.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    tokens
+}
+
+/// Turn objects whose keys are `0` up to their length into arrays
+fn into_arrays(value: &mut Value) {
+    let members = match value {
+        Value::Object(members) => members,
+        Value::Array(elements) => {
+            elements.iter_mut().for_each(into_arrays);
+            return;
+        }
+        _ => return,
+    };
+    members.values_mut().for_each(into_arrays);
+    let is_array = !members.is_empty()
+        && members
+            .keys()
+            .all(|key| array_index(key).is_some_and(|index| index < members.len()));
+    if is_array {
+        let mut elements: Vec<_> = members
+            .drain()
+            .map(|(key, value)| (array_index(&key).unwrap_or_default(), value))
+            .collect();
+        elements.sort_by_key(|(index, _)| *index);
+        *value = elements.into_iter().map(|(_, value)| value).collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::UnflattenError;
+    use crate::{json, Value};
+    use std::collections::HashMap;
+
+    fn map<const N: usize>(entries: [(&str, Value); N]) -> HashMap<String, Value> {
+        entries
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value))
+            .collect()
+    }
+
+    #[test]
+    fn flatten_nested() {
+        let value = json!({"a": {"b": [1, {"c": null}], "d": {}}, "e": [], "f": "x"});
+        assert_eq!(
+            value.flatten("."),
+            map([
+                ("a.b.0", json!(1)),
+                ("a.b.1.c", Value::Null),
+                ("a.d", json!({})),
+                ("e", json!([])),
+                ("f", json!("x")),
+            ])
+        );
+        assert_eq!(
+            json!([[1], 2]).flatten("__"),
+            map([("0__0", json!(1)), ("1", json!(2))])
+        );
+    }
+
+    #[test]
+    fn flatten_scalars_and_empty_containers() {
+        assert_eq!(json!(1).flatten("."), map([("", json!(1))]));
+        assert!(json!({}).flatten(".").is_empty());
+        assert!(json!([]).flatten(".").is_empty());
+    }
+
+    #[test]
+    fn separator_in_keys_is_escaped() -> Result<(), UnflattenError> {
+        let value = json!({"a.b": {"c\\d": {"e.\\.": 1}}, "__": [2]});
+        let flattened = value.flatten(".");
+        assert_eq!(
+            flattened,
+            map([("a\\.b.c\\\\d.e\\.\\\\\\.", json!(1)), ("__.0", json!(2))])
+        );
+        assert_eq!(Value::unflatten(flattened, ".")?, value);
+
+        let flattened = value.flatten("__");
+        assert_eq!(flattened["\\____0"], json!(2));
+        assert_eq!(Value::unflatten(flattened, "__")?, value);
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip() -> Result<(), UnflattenError> {
+        let values = [
+            json!({"a": {"b": [1, 2]}}),
+            json!({"a": [{"b": [[], {}]}, null, [true, [false]]], "c": {"d": {"e": "f"}}}),
+            json!([{"x": 1}, [2, 3], "y"]),
+            json!({"": {"": [""]}}),
+            json!({"list": [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11]}),
+        ];
+        for value in values {
+            for sep in [".", "/", "::"] {
+                assert_eq!(Value::unflatten(value.flatten(sep), sep)?, value);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn numeric_keys_rule() -> Result<(), UnflattenError> {
+        let value = Value::unflatten(
+            map([
+                ("contiguous.1", json!("b")),
+                ("contiguous.0", json!("a")),
+                ("gap.0", json!(1)),
+                ("gap.2", json!(2)),
+                ("leading_zero.00", json!(3)),
+                ("mixed.0", json!(4)),
+                ("mixed.x", json!(5)),
+            ]),
+            ".",
+        )?;
+        assert_eq!(
+            value,
+            json!({
+                "contiguous": ["a", "b"],
+                "gap": {"0": 1, "2": 2},
+                "leading_zero": {"00": 3},
+                "mixed": {"0": 4, "x": 5},
+            })
+        );
+        assert_eq!(Value::unflatten(map([("0", json!(1))]), ".")?, json!([1]));
+        Ok(())
+    }
+
+    #[test]
+    fn conflicting_keys() {
+        let error = Value::unflatten(map([("a", json!(1)), ("a.b", json!(2))]), ".").unwrap_err();
+        assert!(error.key() == "a" || error.key() == "a.b");
+        assert!(Value::unflatten(map([("a", json!([])), ("a.0", json!(1))]), ".").is_err());
+        assert_eq!(
+            Value::unflatten(map([("a", json!({})), ("a.b", json!(1))]), "."),
+            Ok(json!({"a": {"b": 1}}))
+        );
+    }
+}
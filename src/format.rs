@@ -0,0 +1,383 @@
+use crate::error::{ParseError, ParseErrorKind};
+use crate::number::{lenient_number_literal, number_literal};
+use crate::string::string_literal;
+use crate::ws;
+use crate::ParseOptions;
+use nom::{branch::alt, bytes::complete::tag, error::ErrorKind, IResult};
+
+/// Remove insignificant whitespace from json without building a `Value`
+///
+/// Numbers and strings are copied as written, so their spelling is kept.
+///
+/// ```rust
+/// use wson::minify;
+/// # use std::error;
+/// # fn main() -> Result<(), Box<dyn error::Error>> {
+///
+///
+/// let json = minify("{ \"price\": 1.50,\n  \"tags\": [ \"a\\u0062\", null ] }")?;
+/// assert_eq!(json, "{\"price\":1.50,\"tags\":[\"a\\u0062\",null]}");
+///
+/// // invalid json is rejected
+/// assert!(minify("[1, 2").is_err());
+///
+/// # Ok(())
+/// # }
+/// ```
+pub fn minify(input: &str) -> Result<String, ParseError> {
+    let options = ParseOptions::new();
+    let mut formatter = Formatter::new(input, None, &options);
+    formatter.document()?;
+    Ok(formatter.output)
+}
+
+/// Indent json with `indent` per depth without building a `Value`
+///
+/// Numbers and strings are copied as written, so a number like
+/// `0.30000000000000004` is kept byte for byte.
+///
+/// ```rust
+/// use wson::prettify;
+/// # use std::error;
+/// # fn main() -> Result<(), Box<dyn error::Error>> {
+///
+///
+/// let json = prettify("{\"price\":1.50,\"tags\":[\"a\",{}]}", "  ")?;
+/// assert_eq!(json, "{\n  \"price\": 1.50,\n  \"tags\": [\n    \"a\",\n    {}\n  ]\n}");
+///
+/// # Ok(())
+/// # }
+/// ```
+pub fn prettify(input: &str, indent: &str) -> Result<String, ParseError> {
+    let options = ParseOptions::new();
+    let mut formatter = Formatter::new(input, Some(indent), &options);
+    formatter.document()?;
+    Ok(formatter.output)
+}
+
+/// First error in `input`, with control characters in strings and
+/// lenient numbers allowed when `options` allow them
+pub(crate) fn check(input: &str, options: &ParseOptions) -> Result<(), ParseError> {
+    Formatter::new(input, None, options).document()
+}
+
+/// Rewrite json token by token into `output`, putting every member and
+/// element on its own line when `indent` is given
+struct Formatter<'a> {
+    input: &'a str,
+    rest: &'a str,
+    output: String,
+    indent: Option<&'a str>,
+    depth: usize,
+    /// Syntax beyond json which is accepted
+    options: &'a ParseOptions,
+}
+
+impl<'a> Formatter<'a> {
+    fn new(input: &'a str, indent: Option<&'a str>, options: &'a ParseOptions) -> Self {
+        Formatter {
+            input,
+            rest: input,
+            output: String::with_capacity(input.len()),
+            indent,
+            depth: 0,
+            options,
+        }
+    }
+
+    fn document(&mut self) -> Result<(), ParseError> {
+        self.element()?;
+        if self.rest.is_empty() {
+            Ok(())
+        } else {
+            Err(self.error(ParseErrorKind::TrailingCharacters))
+        }
+    }
+
+    fn element(&mut self) -> Result<(), ParseError> {
+        self.skip_ws();
+        if self.eat('{') {
+            self.object()?;
+        } else if self.eat('[') {
+            self.array()?;
+        } else {
+            let options = self.options;
+            let literal = self.token(|input| scalar(input, options), "value")?;
+            self.output.push_str(literal);
+        }
+        self.skip_ws();
+        Ok(())
+    }
+
+    /// Rest of an object after '{'
+    fn object(&mut self) -> Result<(), ParseError> {
+        self.output.push('{');
+        self.skip_ws();
+        if self.eat('}') {
+            self.output.push('}');
+            return Ok(());
+        }
+        self.depth += 1;
+        loop {
+            self.newline();
+            self.skip_ws();
+            let allow_control_chars = self.options.allow_control_chars;
+            let key = self.token(|input| string_literal(input, allow_control_chars), "string")?;
+            self.output.push_str(key);
+            self.skip_ws();
+            if !self.eat(':') {
+                return Err(self.error(ParseErrorKind::Expected("':'")));
+            }
+            self.output.push(':');
+            if self.indent.is_some() {
+                self.output.push(' ');
+            }
+            self.element()?;
+            if self.eat(',') {
+                self.output.push(',');
+            } else if self.eat('}') {
+                self.depth -= 1;
+                self.newline();
+                self.output.push('}');
+                return Ok(());
+            } else {
+                return Err(self.error(ParseErrorKind::Expected("',' or '}'")));
+            }
+        }
+    }
+
+    /// Rest of an array after '['
+    fn array(&mut self) -> Result<(), ParseError> {
+        self.output.push('[');
+        self.skip_ws();
+        if self.eat(']') {
+            self.output.push(']');
+            return Ok(());
+        }
+        self.depth += 1;
+        loop {
+            self.newline();
+            self.element()?;
+            if self.eat(',') {
+                self.output.push(',');
+            } else if self.eat(']') {
+                self.depth -= 1;
+                self.newline();
+                self.output.push(']');
+                return Ok(());
+            } else {
+                return Err(self.error(ParseErrorKind::Expected("',' or ']'")));
+            }
+        }
+    }
+
+    fn newline(&mut self) {
+        if let Some(indent) = self.indent {
+            self.output.push('\n');
+            for _ in 0..self.depth {
+                self.output.push_str(indent);
+            }
+        }
+    }
+
+    fn skip_ws(&mut self) {
+        if let Ok((rest, _)) = ws(self.rest) {
+            self.rest = rest;
+        }
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        match self.rest.strip_prefix(c) {
+            Some(rest) => {
+                self.rest = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn token<P>(&mut self, mut parser: P, expected: &'static str) -> Result<&'a str, ParseError>
+    where
+        P: FnMut(&'a str) -> IResult<&'a str, &'a str>,
+    {
+        match parser(self.rest) {
+            Ok((rest, token)) => {
+                self.rest = rest;
+                Ok(token)
+            }
+            Err(nom::Err::Failure(error)) if error.code == ErrorKind::Char => Err(ParseError::new(
+                ParseErrorKind::ControlCharacter,
+                self.input,
+                error.input,
+            )),
+            Err(_) => Err(self.error(ParseErrorKind::Expected(expected))),
+        }
+    }
+
+    fn error(&self, kind: ParseErrorKind) -> ParseError {
+        ParseError::new(kind, self.input, self.rest)
+    }
+}
+
+fn scalar<'a>(input: &'a str, options: &ParseOptions) -> IResult<&'a str, &'a str> {
+    alt((
+        |input| string_literal(input, options.allow_control_chars),
+        |input| {
+            if options.is_lenient() {
+                lenient_number_literal(input, options)
+            } else {
+                number_literal(input)
+            }
+        },
+        tag("null"),
+        tag("true"),
+        tag("false"),
+    ))(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse, to_string, to_string_pretty};
+
+    #[test]
+    fn minify_scalars() -> Result<(), ParseError> {
+        assert_eq!(minify(" null ")?, "null");
+        assert_eq!(minify("true")?, "true");
+        assert_eq!(minify("\n\"a b\"\n")?, "\"a b\"");
+        assert_eq!(minify("-3.2E-1")?, "-3.2E-1");
+        Ok(())
+    }
+
+    #[test]
+    fn minify_keeps_number_spelling() -> Result<(), ParseError> {
+        assert_eq!(
+            minify("[1.50, 1e3, 0.30000000000000004, 18446744073709551616]")?,
+            "[1.50,1e3,0.30000000000000004,18446744073709551616]"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn minify_keeps_string_escapes() -> Result<(), ParseError> {
+        assert_eq!(
+            minify("{ \"k\\\"ey\" : \"\\u3053 \\n\" }")?,
+            "{\"k\\\"ey\":\"\\u3053 \\n\"}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn minify_empty_containers() -> Result<(), ParseError> {
+        assert_eq!(minify("{ }")?, "{}");
+        assert_eq!(minify("[\n\n]")?, "[]");
+        assert_eq!(minify("[ {}, [ ] ]")?, "[{},[]]");
+        Ok(())
+    }
+
+    #[test]
+    fn minify_matches_serializer() -> Result<(), ParseError> {
+        let input = "{\"menu\": {
+               \"id\": \"file\",
+               \"popup\": {
+                 \"menuitem\": [
+                   {\"value\": \"New\", \"onclick\": \"CreateNewDoc()\"},
+                   {\"value\": \"Open\", \"onclick\": \"OpenDoc()\"}
+                 ]
+               }
+            }}";
+        let json = minify(input)?;
+        assert_eq!(parse(&json).ok(), parse(input).ok());
+        assert_eq!(
+            Some(json.len()),
+            to_string(&parse(input).unwrap()).ok().map(|s| s.len())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn minify_errors() {
+        let error = minify("[1, 2").unwrap_err();
+        assert_eq!(error.kind(), &ParseErrorKind::Expected("',' or ']'"));
+        assert_eq!(error.offset(), 5);
+
+        let error = minify("{\"a\" 1}").unwrap_err();
+        assert_eq!(error.kind(), &ParseErrorKind::Expected("':'"));
+        assert_eq!(error.offset(), 5);
+
+        let error = minify("{1: 1}").unwrap_err();
+        assert_eq!(error.kind(), &ParseErrorKind::Expected("string"));
+        assert_eq!(error.offset(), 1);
+
+        let error = minify("[1,]").unwrap_err();
+        assert_eq!(error.kind(), &ParseErrorKind::Expected("value"));
+        assert_eq!(error.offset(), 3);
+
+        let error = minify("").unwrap_err();
+        assert_eq!(error.kind(), &ParseErrorKind::Expected("value"));
+    }
+
+    #[test]
+    fn minify_rejects_trailing_characters() {
+        let error = minify("{} {}").unwrap_err();
+        assert_eq!(error.kind(), &ParseErrorKind::TrailingCharacters);
+        assert_eq!(error.offset(), 3);
+
+        let error = minify("01").unwrap_err();
+        assert_eq!(error.kind(), &ParseErrorKind::TrailingCharacters);
+    }
+
+    #[test]
+    fn prettify_scalars() -> Result<(), ParseError> {
+        assert_eq!(prettify(" null ", "  ")?, "null");
+        assert_eq!(prettify("1.50", "  ")?, "1.50");
+        Ok(())
+    }
+
+    #[test]
+    fn prettify_keeps_number_spelling() -> Result<(), ParseError> {
+        // 0.1 + 0.2 as f64 is written back as 0.30000000000000004, and 1.0e1
+        // would become 10.0
+        let input = "[0.30000000000000004,1.0e1,-0]";
+        assert_eq!(
+            prettify(input, "  ")?,
+            "[\n  0.30000000000000004,\n  1.0e1,\n  -0\n]"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn prettify_nested() -> Result<(), ParseError> {
+        let input = "{\"a\":[{\"b\":{}},[]],\"c\":\"\\u3053\"}";
+        assert_eq!(
+            prettify(input, "\t")?,
+            "{\n\t\"a\": [\n\t\t{\n\t\t\t\"b\": {}\n\t\t},\n\t\t[]\n\t],\n\t\"c\": \"\\u3053\"\n}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn prettify_matches_serializer() -> Result<(), ParseError> {
+        let input = "[ {\"id\" : \"file\"}, [ 1, null , true ] , { } ]";
+        let value = parse(input).unwrap();
+        assert_eq!(
+            Some(prettify(input, "    ")?),
+            to_string_pretty(&value, 4).ok()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn prettify_minify_round_trip() -> Result<(), ParseError> {
+        let input = "{\"a\":[1.50,{\"b\":[true,false,null]}],\"c\":{}}";
+        assert_eq!(minify(&prettify(input, "  ")?)?, input);
+        Ok(())
+    }
+
+    #[test]
+    fn prettify_errors() {
+        let error = prettify("{\"a\": [1 2]}", "  ").unwrap_err();
+        assert_eq!(error.kind(), &ParseErrorKind::Expected("',' or ']'"));
+        assert_eq!(error.offset(), 9);
+    }
+}
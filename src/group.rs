@@ -0,0 +1,179 @@
+use crate::convert::kind;
+use crate::error::GroupError;
+use crate::pointer;
+use crate::Value;
+use std::collections::HashMap;
+
+impl Value {
+    /// Group the elements of an array by the value at `key_pointer` in each
+    ///
+    /// Gives an object of arrays, with the elements of each group in their
+    /// original order. Groups are named by the value at `key_pointer`: a
+    /// string as it is, and anything else as compact json, so `1` and `"1"`
+    /// are grouped together. Elements where nothing is at `key_pointer` are
+    /// dropped; use `group_by_with` to keep them.
+    ///
+    /// ```rust
+    /// use wson::json;
+    /// # use std::error;
+    /// # fn main() -> Result<(), Box<dyn error::Error>> {
+    ///
+    ///
+    /// let rows = json!([{"country": "JP", "n": 1}, {"country": "US", "n": 2}, {"country": "JP", "n": 3}]);
+    /// assert_eq!(
+    ///     rows.group_by("/country")?,
+    ///     json!({
+    ///         "JP": [{"country": "JP", "n": 1}, {"country": "JP", "n": 3}],
+    ///         "US": [{"country": "US", "n": 2}],
+    ///     })
+    /// );
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn group_by(&self, key_pointer: &str) -> Result<Value, GroupError> {
+        self.group_by_with(key_pointer, None)
+    }
+
+    /// Group the elements of an array like `group_by`, putting elements
+    /// where nothing is at `key_pointer` into the group `missing` if given
+    ///
+    /// ```rust
+    /// use wson::json;
+    /// # use std::error;
+    /// # fn main() -> Result<(), Box<dyn error::Error>> {
+    ///
+    ///
+    /// let rows = json!([{"tag": "a"}, {}, {"tag": "a"}]);
+    /// assert_eq!(
+    ///     rows.group_by_with("/tag", Some("__missing__"))?,
+    ///     json!({"a": [{"tag": "a"}, {"tag": "a"}], "__missing__": [{}]})
+    /// );
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn group_by_with(
+        &self,
+        key_pointer: &str,
+        missing: Option<&str>,
+    ) -> Result<Value, GroupError> {
+        let elements = self.as_array().ok_or_else(|| GroupError::NotAnArray {
+            found: kind(self).to_string(),
+        })?;
+        if !pointer::is_valid(key_pointer) {
+            return Err(GroupError::InvalidPointer {
+                pointer: key_pointer.to_string(),
+            });
+        }
+
+        let mut groups: HashMap<String, Vec<Value>> = HashMap::new();
+        for element in elements {
+            let group = match element.pointer(key_pointer) {
+                Some(Value::String(key)) => key.clone(),
+                Some(key) => key.to_string(),
+                None => match missing {
+                    Some(missing) => missing.to_string(),
+                    None => continue,
+                },
+            };
+            groups.entry(group).or_default().push(element.clone());
+        }
+        Ok(groups.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::GroupError;
+    use crate::json;
+
+    #[test]
+    fn group_by_number_keys() -> Result<(), GroupError> {
+        let value = json!([
+            {"status": 200, "id": 1},
+            {"status": 404, "id": 2},
+            {"status": 200, "id": 3},
+            {"status": "200", "id": 4},
+            {"status": 1.5, "id": 5},
+        ]);
+        assert_eq!(
+            value.group_by("/status")?,
+            json!({
+                "200": [
+                    {"status": 200, "id": 1},
+                    {"status": 200, "id": 3},
+                    {"status": "200", "id": 4},
+                ],
+                "404": [{"status": 404, "id": 2}],
+                "1.5": [{"status": 1.5, "id": 5}],
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn group_by_nested_and_other_keys() -> Result<(), GroupError> {
+        let value = json!([
+            {"user": {"admin": true}},
+            {"user": {"admin": null}},
+            {"user": {"admin": [1]}},
+            {"user": {"admin": true}},
+        ]);
+        assert_eq!(
+            value.group_by("/user/admin")?,
+            json!({
+                "true": [{"user": {"admin": true}}, {"user": {"admin": true}}],
+                "null": [{"user": {"admin": null}}],
+                "[1]": [{"user": {"admin": [1]}}],
+            })
+        );
+        assert_eq!(
+            json!(["a", "b", "a"]).group_by("")?,
+            json!({"a": ["a", "a"], "b": ["b"]})
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn group_by_missing_keys() -> Result<(), GroupError> {
+        let value = json!([{"k": "x"}, {"other": 1}, 2, {"k": "y"}, {"k": "x"}]);
+        let grouped = json!({"x": [{"k": "x"}, {"k": "x"}], "y": [{"k": "y"}]});
+        assert_eq!(value.group_by("/k")?, grouped);
+
+        let mut with_missing = grouped;
+        with_missing.insert("__missing__", json!([{"other": 1}, 2]));
+        assert_eq!(
+            value.group_by_with("/k", Some("__missing__"))?,
+            with_missing
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn group_by_empty_array() -> Result<(), GroupError> {
+        assert_eq!(json!([]).group_by("/k")?, json!({}));
+        assert_eq!(json!([]).group_by_with("/k", Some("none"))?, json!({}));
+        Ok(())
+    }
+
+    #[test]
+    fn group_by_errors() {
+        assert_eq!(
+            json!({"a": []}).group_by("/a"),
+            Err(GroupError::NotAnArray {
+                found: "Object".to_string()
+            })
+        );
+        assert_eq!(
+            json!([]).group_by("k"),
+            Err(GroupError::InvalidPointer {
+                pointer: "k".to_string()
+            })
+        );
+        assert_eq!(
+            json!([]).group_by("/~2").unwrap_err().to_string(),
+            "invalid json pointer \"/~2\""
+        );
+    }
+}
@@ -0,0 +1,306 @@
+use crate::number::Number;
+use crate::Value;
+use std::hash::{Hash, Hasher};
+
+impl Value {
+    /// Feed the content of value into `hasher`
+    ///
+    /// Object members are hashed in key order, so the result doesn't depend on
+    /// how the `HashMap` was built. Values which compare equal hash equal.
+    ///
+    /// Numbers are hashed like they compare: `PositiveInteger(3)` and
+    /// `Float(3.0)` are equal and hash the same, as do `0.0` and `-0.0`, and
+    /// all NaNs.
+    ///
+    /// This is also the `Hash` implementation of `Value`.
+    ///
+    /// ```rust
+    /// use wson::parse;
+    /// use std::collections::hash_map::DefaultHasher;
+    /// use std::hash::Hasher;
+    /// # use std::error;
+    /// # fn main() -> Result<(), Box<dyn error::Error>> {
+    ///
+    ///
+    /// let digest = |json| -> Result<u64, Box<dyn error::Error>> {
+    ///     let mut hasher = DefaultHasher::new();
+    ///     parse(json)?.canonical_hash(&mut hasher);
+    ///     Ok(hasher.finish())
+    /// };
+    /// assert_eq!(digest("{\"a\": 1, \"b\": [true]}")?, digest("{\"b\": [true], \"a\": 1}")?);
+    /// assert_eq!(digest("[1]")?, digest("[1.0]")?);
+    /// assert_ne!(digest("[1]")?, digest("[1.5]")?);
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn canonical_hash<H: Hasher>(&self, hasher: &mut H) {
+        match self {
+            Value::Object(members) => {
+                hasher.write_u8(0);
+                let mut members: Vec<_> = members.iter().collect();
+                members.sort_unstable_by_key(|(key, _)| *key);
+                hasher.write_usize(members.len());
+                for (key, value) in members {
+                    key.hash(hasher);
+                    value.canonical_hash(hasher);
+                }
+            }
+            Value::Array(elements) => {
+                hasher.write_u8(1);
+                hasher.write_usize(elements.len());
+                for element in elements {
+                    element.canonical_hash(hasher);
+                }
+            }
+            Value::Number(number) => {
+                hasher.write_u8(2);
+                number.canonical_hash(hasher);
+            }
+            Value::String(str) => {
+                hasher.write_u8(3);
+                str.hash(hasher);
+            }
+            Value::Null => hasher.write_u8(4),
+            Value::Bool(bool) => {
+                hasher.write_u8(5);
+                bool.hash(hasher);
+            }
+        }
+    }
+}
+
+impl Number {
+    fn canonical_hash<H: Hasher>(&self, hasher: &mut H) {
+        match self {
+            // every integer variant is equal for the same value
+            Number::PositiveInteger(n) => hash_positive(hasher, u128::from(*n)),
+            Number::PositiveInteger128(n) => hash_positive(hasher, *n),
+            Number::NegativeInteger(n) => hash_signed(hasher, i128::from(*n)),
+            Number::NegativeInteger128(n) => hash_signed(hasher, *n),
+            // an integral float is equal to the integer
+            Number::Float(f) => match (self.as_u128(), self.as_i128()) {
+                (Some(n), _) => hash_positive(hasher, n),
+                (_, Some(n)) => hash_signed(hasher, n),
+                _ => {
+                    hasher.write_u8(2);
+                    let bits = if f.is_nan() {
+                        f64::NAN.to_bits()
+                    } else {
+                        f.to_bits()
+                    };
+                    hasher.write_u64(bits);
+                }
+            },
+            Number::Raw(raw) => raw.number().canonical_hash(hasher),
+            #[cfg(feature = "arbitrary_precision")]
+            Number::Arbitrary(literal) => {
+                hasher.write_u8(3);
+                literal.hash(hasher);
+            }
+        }
+    }
+}
+
+fn hash_positive<H: Hasher>(hasher: &mut H, n: u128) {
+    hasher.write_u8(0);
+    hasher.write_u128(n);
+}
+
+fn hash_signed<H: Hasher>(hasher: &mut H, n: i128) {
+    if n >= 0 {
+        hash_positive(hasher, n as u128);
+    } else {
+        hasher.write_u8(1);
+        hasher.write_i128(n);
+    }
+}
+
+impl Hash for Value {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.canonical_hash(state)
+    }
+}
+
+impl Hash for Number {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.canonical_hash(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::hash_map::DefaultHasher;
+    use std::collections::HashMap;
+
+    fn digest(value: &Value) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.canonical_hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn number(n: u64) -> Value {
+        Value::Number(Number::PositiveInteger(n))
+    }
+
+    #[test]
+    fn independent_of_insertion_order() {
+        let keys: Vec<String> = (0..100).map(|i| format!("key{}", i)).collect();
+        let mut forward = HashMap::new();
+        for (i, key) in keys.iter().enumerate() {
+            forward.insert(key.clone(), number(i as u64));
+        }
+        let mut backward = HashMap::with_capacity(1000);
+        for (i, key) in keys.iter().enumerate().rev() {
+            backward.insert(key.clone(), number(i as u64));
+        }
+        let forward = Value::Object(forward);
+        let backward = Value::Object(backward);
+        assert_eq!(forward, backward);
+        assert_eq!(digest(&forward), digest(&backward));
+    }
+
+    #[test]
+    fn nested_objects() {
+        let inner = |a, b| {
+            Value::Object(HashMap::from([
+                ("x".to_string(), Value::Array(vec![a, b])),
+                ("y".to_string(), Value::Null),
+            ]))
+        };
+        let left = Value::Array(vec![inner(number(1), Value::Bool(true))]);
+        let right = Value::Array(vec![inner(number(1), Value::Bool(true))]);
+        assert_eq!(digest(&left), digest(&right));
+
+        let swapped = Value::Array(vec![inner(Value::Bool(true), number(1))]);
+        assert_ne!(digest(&left), digest(&swapped));
+    }
+
+    #[test]
+    fn distinguishes_structure() {
+        let values = [
+            Value::Object(HashMap::new()),
+            Value::Array(vec![]),
+            Value::Array(vec![Value::Array(vec![])]),
+            Value::Array(vec![Value::String("ab".to_string())]),
+            Value::Array(vec![
+                Value::String("a".to_string()),
+                Value::String("b".to_string()),
+            ]),
+            Value::String(String::new()),
+            Value::Null,
+            Value::Bool(true),
+            Value::Bool(false),
+            number(0),
+        ];
+        for (i, a) in values.iter().enumerate() {
+            for b in &values[i + 1..] {
+                assert_ne!(digest(a), digest(b), "{:?} {:?}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn numbers() {
+        let float = |f| Value::Number(Number::Float(f));
+        assert_eq!(digest(&number(3)), digest(&float(3.0)));
+        assert_eq!(digest(&number(0)), digest(&float(-0.0)));
+        assert_ne!(digest(&number(3)), digest(&float(3.5)));
+        assert_eq!(
+            digest(&number(1)),
+            digest(&Value::Number(Number::NegativeInteger(1)))
+        );
+        assert_eq!(digest(&float(0.0)), digest(&float(-0.0)));
+        assert_eq!(digest(&float(f64::NAN)), digest(&float(-f64::NAN)));
+        assert_ne!(digest(&float(0.1)), digest(&float(0.2)));
+    }
+
+    /// Objects which are equal but were built in different orders and with
+    /// different capacities, so their `HashMap`s iterate differently
+    fn shuffled(seed: u64, depth: usize) -> (Value, Value) {
+        let keys: Vec<String> = (0..8)
+            .map(|i| format!("k{}", (i * 7 + seed) % 11))
+            .collect();
+        let mut left = HashMap::new();
+        let mut right = HashMap::with_capacity(64);
+        for (i, key) in keys.iter().enumerate() {
+            let (a, b) = if depth == 0 {
+                let value = match (i as u64 + seed) % 5 {
+                    0 => Value::Number(Number::Float(-0.0)),
+                    1 => Value::Number(Number::Float(f64::NAN)),
+                    2 => Value::String(key.clone()),
+                    3 => Value::Bool(i % 2 == 0),
+                    _ => number(i as u64),
+                };
+                let other = match &value {
+                    Value::Number(Number::Float(f)) if *f == 0.0 => {
+                        Value::Number(Number::Float(0.0))
+                    }
+                    value => value.clone(),
+                };
+                (value, other)
+            } else {
+                let (a, b) = shuffled(seed + i as u64, depth - 1);
+                (
+                    Value::Array(vec![a, Value::Null]),
+                    Value::Array(vec![b, Value::Null]),
+                )
+            };
+            left.insert(key.clone(), a);
+            right.insert(key.clone(), b);
+        }
+        let mut right_reversed = HashMap::with_capacity(64);
+        let mut entries: Vec<_> = right.into_iter().collect();
+        entries.reverse();
+        right_reversed.extend(entries);
+        (Value::Object(left), Value::Object(right_reversed))
+    }
+
+    fn std_hash<T: Hash>(value: &T) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn equal_values_hash_equal() {
+        for seed in 0..20 {
+            for depth in 0..3 {
+                let (left, right) = shuffled(seed, depth);
+                assert_eq!(left, right);
+                assert_eq!(std_hash(&left), std_hash(&right));
+                assert_eq!(std_hash(&left), digest(&left));
+            }
+        }
+    }
+
+    #[test]
+    fn eq_is_reflexive_for_nan() {
+        let value = Value::Array(vec![Value::Number(Number::Float(f64::NAN))]);
+        assert_eq!(value, value.clone());
+        assert_eq!(
+            Value::Number(Number::PositiveInteger(3)),
+            Value::Number(Number::Float(3.0))
+        );
+        assert_eq!(
+            Value::Number(Number::Float(0.0)),
+            Value::Number(Number::Float(-0.0))
+        );
+    }
+
+    #[test]
+    fn values_in_hash_set() {
+        use std::collections::HashSet;
+
+        let mut set = HashSet::new();
+        for seed in 0..5 {
+            let (left, right) = shuffled(seed, 1);
+            set.insert(left);
+            set.insert(right);
+        }
+        set.insert(Value::Number(Number::Float(f64::NAN)));
+        set.insert(Value::Number(Number::Float(f64::NAN)));
+        assert_eq!(set.len(), 6);
+    }
+}
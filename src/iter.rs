@@ -0,0 +1,565 @@
+//! Iterators over the members or elements of a `Value`
+
+use crate::pointer;
+use crate::Value;
+use std::collections::hash_map;
+use std::{iter, slice, vec};
+
+impl Value {
+    /// Iterate over the members of an object or the elements of an array
+    ///
+    /// Members come with `Some(key)` and elements with `None`. Other values
+    /// have nothing to iterate. The order of members is unspecified.
+    ///
+    /// ```rust
+    /// use wson::json;
+    /// # fn main() {
+    ///
+    ///
+    /// let value = json!({"a": 1, "b": 2});
+    /// let mut keys = Vec::new();
+    /// for (key, member) in &value {
+    ///     assert!(member.is_u64());
+    ///     keys.extend(key);
+    /// }
+    /// keys.sort();
+    /// assert_eq!(keys, ["a", "b"]);
+    ///
+    /// let array = json!([true]);
+    /// let elements: Vec<_> = array.iter().collect();
+    /// assert_eq!(elements, [(None, &json!(true))]);
+    /// # }
+    /// ```
+    pub fn iter(&self) -> Iter<'_> {
+        Iter(match self {
+            Value::Object(members) => Children::Object(members.iter()),
+            Value::Array(elements) => Children::Array(elements.iter()),
+            _ => Children::None,
+        })
+    }
+
+    /// Iterate over mutable members of an object or elements of an array
+    pub fn iter_mut(&mut self) -> IterMut<'_> {
+        IterMut(match self {
+            Value::Object(members) => Children::Object(members.iter_mut()),
+            Value::Array(elements) => Children::Array(elements.iter_mut()),
+            _ => Children::None,
+        })
+    }
+
+    /// Iterate over every node, starting with `self`, in depth-first pre-order
+    ///
+    /// Each node comes with its JSON Pointer. Children of an object come in
+    /// unspecified order. Nodes are visited lazily as the iterator advances.
+    ///
+    /// ```rust
+    /// use wson::json;
+    /// # fn main() {
+    ///
+    ///
+    /// let value = json!({"a/b": [true]});
+    /// let nodes: Vec<_> = value.iter_deep().collect();
+    /// assert_eq!(
+    ///     nodes,
+    ///     [
+    ///         (String::new(), &value),
+    ///         ("/a~1b".to_string(), &json!([true])),
+    ///         ("/a~1b/0".to_string(), &json!(true)),
+    ///     ]
+    /// );
+    /// # }
+    /// ```
+    pub fn iter_deep(&self) -> DeepIter<'_> {
+        DeepIter {
+            root: Some(self),
+            stack: Vec::new(),
+        }
+    }
+
+    /// Iterate over every mutable leaf in depth-first order
+    ///
+    /// Leaves are scalars and empty containers. Non-empty containers are not
+    /// yielded, since their children are borrowed while they are visited.
+    pub fn iter_deep_mut(&mut self) -> DeepIterMut<'_> {
+        DeepIterMut {
+            root: Some(self),
+            stack: Vec::new(),
+        }
+    }
+
+    /// JSON Pointers of every leaf, which is a scalar or an empty container
+    ///
+    /// Members of objects come in order of their keys and elements of arrays
+    /// in their own order, so the output is stable.
+    ///
+    /// ```rust
+    /// use wson::json;
+    /// # fn main() {
+    ///
+    ///
+    /// let value = json!({"b": [1, {}], "a": null});
+    /// let paths: Vec<_> = value.leaf_paths().collect();
+    /// assert_eq!(paths, ["/a", "/b/0", "/b/1"]);
+    /// # }
+    /// ```
+    pub fn leaf_paths(&self) -> impl Iterator<Item = String> + '_ {
+        sorted_pre_order(self)
+            .filter(|(_, _, node)| !has_children(node))
+            .map(|(pointer, _, _)| pointer)
+    }
+
+    /// First value of a member named `key`, anywhere in the tree
+    ///
+    /// The tree is searched depth-first in pre-order, going through members
+    /// of objects in order of their keys and elements of arrays in their own
+    /// order. A matching member is found before anything nested in it.
+    ///
+    /// ```rust
+    /// use wson::json;
+    /// # fn main() {
+    ///
+    ///
+    /// let value = json!({"data": [{"id": 1}, {"error_code": 42}], "meta": {"error_code": 7}});
+    /// assert_eq!(value.find("error_code"), Some(&json!(42)));
+    /// assert_eq!(value.find("missing"), None);
+    /// # }
+    /// ```
+    pub fn find(&self, key: &str) -> Option<&Value> {
+        sorted_pre_order(self)
+            .find(|(_, member, _)| *member == Some(key))
+            .map(|(_, _, value)| value)
+    }
+
+    /// Every value of a member named `key` with its JSON Pointer, in the order
+    /// `find` searches
+    pub fn find_all(&self, key: &str) -> Vec<(String, &Value)> {
+        sorted_pre_order(self)
+            .filter(|(_, member, _)| *member == Some(key))
+            .map(|(pointer, _, value)| (pointer, value))
+            .collect()
+    }
+}
+
+/// Every node under `value` in depth-first pre-order, with its pointer and
+/// its key if it is a member, going through members in order of their keys
+fn sorted_pre_order(value: &Value) -> impl Iterator<Item = (String, Option<&str>, &Value)> {
+    let mut stack = vec![(String::new(), None, value)];
+    iter::from_fn(move || {
+        let (pointer, key, node) = stack.pop()?;
+        let mut children: Vec<_> = node.iter().enumerate().collect();
+        children.sort_by_key(|(_, (key, _))| *key);
+        // reversed so the first child is popped first
+        stack.extend(
+            children
+                .into_iter()
+                .rev()
+                .map(|(index, (key, child))| (child_pointer(&pointer, key, index), key, child)),
+        );
+        Some((pointer, key, node))
+    })
+}
+
+/// Whether `value` is an object or array with at least one child
+fn has_children(value: &Value) -> bool {
+    value.iter().next().is_some()
+}
+
+/// JSON Pointer of the child `key`, or of the element `index`, under `parent`
+fn child_pointer(parent: &str, key: Option<&str>, index: usize) -> String {
+    match key {
+        Some(key) => format!("{}/{}", parent, pointer::escape(key)),
+        None => format!("{}/{}", parent, index),
+    }
+}
+
+/// Iterator from `Value::iter_deep`
+#[derive(Debug)]
+pub struct DeepIter<'a> {
+    root: Option<&'a Value>,
+    /// pointer, children and index of the next child of each open container
+    stack: Vec<(String, Iter<'a>, usize)>,
+}
+
+impl<'a> Iterator for DeepIter<'a> {
+    type Item = (String, &'a Value);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(root) = self.root.take() {
+            self.stack.push((String::new(), root.iter(), 0));
+            return Some((String::new(), root));
+        }
+        loop {
+            let (parent, children, index) = self.stack.last_mut()?;
+            match children.next() {
+                Some((key, child)) => {
+                    let pointer = child_pointer(parent, key, *index);
+                    *index += 1;
+                    if has_children(child) {
+                        self.stack.push((pointer.clone(), child.iter(), 0));
+                    }
+                    return Some((pointer, child));
+                }
+                None => {
+                    self.stack.pop();
+                }
+            }
+        }
+    }
+}
+
+/// Iterator from `Value::iter_deep_mut`
+#[derive(Debug)]
+pub struct DeepIterMut<'a> {
+    root: Option<&'a mut Value>,
+    /// pointer, children and index of the next child of each open container
+    stack: Vec<(String, IterMut<'a>, usize)>,
+}
+
+impl<'a> DeepIterMut<'a> {
+    /// Yield `node` if it is a leaf, or open it otherwise
+    fn visit(&mut self, pointer: String, node: &'a mut Value) -> Option<(String, &'a mut Value)> {
+        if !has_children(node) {
+            Some((pointer, node))
+        } else {
+            self.stack.push((pointer, node.iter_mut(), 0));
+            None
+        }
+    }
+}
+
+impl<'a> Iterator for DeepIterMut<'a> {
+    type Item = (String, &'a mut Value);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(root) = self.root.take() {
+            if let Some(leaf) = self.visit(String::new(), root) {
+                return Some(leaf);
+            }
+        }
+        loop {
+            let (parent, children, index) = self.stack.last_mut()?;
+            match children.next() {
+                Some((key, child)) => {
+                    let pointer = child_pointer(parent, key, *index);
+                    *index += 1;
+                    if let Some(leaf) = self.visit(pointer, child) {
+                        return Some(leaf);
+                    }
+                }
+                None => {
+                    self.stack.pop();
+                }
+            }
+        }
+    }
+}
+
+/// Either the members of an object, the elements of an array, or nothing
+#[derive(Debug)]
+enum Children<O, A> {
+    Object(O),
+    Array(A),
+    None,
+}
+
+/// Iterator from `Value::iter`
+#[derive(Debug)]
+pub struct Iter<'a>(Children<hash_map::Iter<'a, String, Value>, slice::Iter<'a, Value>>);
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = (Option<&'a str>, &'a Value);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.0 {
+            Children::Object(members) => members
+                .next()
+                .map(|(key, value)| (Some(key.as_str()), value)),
+            Children::Array(elements) => elements.next().map(|element| (None, element)),
+            Children::None => None,
+        }
+    }
+}
+
+/// Iterator from `Value::iter_mut`
+#[derive(Debug)]
+pub struct IterMut<'a>(Children<hash_map::IterMut<'a, String, Value>, slice::IterMut<'a, Value>>);
+
+impl<'a> Iterator for IterMut<'a> {
+    type Item = (Option<&'a str>, &'a mut Value);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.0 {
+            Children::Object(members) => members
+                .next()
+                .map(|(key, value)| (Some(key.as_str()), value)),
+            Children::Array(elements) => elements.next().map(|element| (None, element)),
+            Children::None => None,
+        }
+    }
+}
+
+/// Iterator which moves members or elements out of a `Value`
+#[derive(Debug)]
+pub struct IntoIter(Children<hash_map::IntoIter<String, Value>, vec::IntoIter<Value>>);
+
+impl Iterator for IntoIter {
+    type Item = (Option<String>, Value);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.0 {
+            Children::Object(members) => members.next().map(|(key, value)| (Some(key), value)),
+            Children::Array(elements) => elements.next().map(|element| (None, element)),
+            Children::None => None,
+        }
+    }
+}
+
+impl IntoIterator for Value {
+    type Item = (Option<String>, Value);
+    type IntoIter = IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter(match self {
+            Value::Object(members) => Children::Object(members.into_iter()),
+            Value::Array(elements) => Children::Array(elements.into_iter()),
+            _ => Children::None,
+        })
+    }
+}
+
+impl<'a> IntoIterator for &'a Value {
+    type Item = (Option<&'a str>, &'a Value);
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut Value {
+    type Item = (Option<&'a str>, &'a mut Value);
+    type IntoIter = IterMut<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::ParseError;
+    use crate::{json, parse, Value};
+    use std::collections::HashMap;
+
+    #[test]
+    fn iterate_objects() {
+        let value = json!({"a": 1, "b": [2]});
+        let mut members: Vec<_> = value.iter().collect();
+        members.sort();
+        assert_eq!(members, [(Some("a"), &json!(1)), (Some("b"), &json!([2]))]);
+    }
+
+    #[test]
+    fn iterate_arrays_in_order() {
+        let value = json!([3, "x", null]);
+        let elements: Vec<_> = (&value).into_iter().collect();
+        assert_eq!(
+            elements,
+            [(None, &json!(3)), (None, &json!("x")), (None, &Value::Null)]
+        );
+    }
+
+    #[test]
+    fn scalars_have_no_children() {
+        let mut scalars = [json!(null), json!(true), json!(1.5), json!("ab")];
+        for scalar in &mut scalars {
+            assert_eq!(scalar.iter().count(), 0);
+            assert_eq!(scalar.iter_mut().count(), 0);
+            assert_eq!(scalar.clone().into_iter().count(), 0);
+        }
+        assert_eq!(json!([]).iter().count(), 0);
+        assert_eq!(json!({}).iter().count(), 0);
+    }
+
+    #[test]
+    fn iter_mut_modifies_children() {
+        let mut value = json!({"a": 1, "b": 2});
+        for (key, member) in &mut value {
+            *member = json!(key);
+        }
+        assert_eq!(value, json!({"a": "a", "b": "b"}));
+
+        let mut value = json!([1, 2]);
+        for (_, element) in value.iter_mut() {
+            element.take();
+        }
+        assert_eq!(value, json!([null, null]));
+    }
+
+    #[test]
+    fn into_iter_moves_children() {
+        let mut members: Vec<_> = json!({"a": [1], "b": null}).into_iter().collect();
+        members.sort();
+        assert_eq!(
+            members,
+            [
+                (Some("a".to_string()), json!([1])),
+                (Some("b".to_string()), Value::Null)
+            ]
+        );
+        let elements: Vec<_> = json!([{}]).into_iter().collect();
+        assert_eq!(elements, [(None, json!({}))]);
+    }
+
+    #[test]
+    fn iter_deep_pointers() {
+        let value = json!([0, {"a/b": [true, {"~": null}]}, []]);
+        let nodes: HashMap<_, _> = value.iter_deep().collect();
+        assert_eq!(nodes[""], &value);
+        assert_eq!(nodes["/1/a~1b"], &json!([true, {"~": null}]));
+        assert_eq!(nodes["/1/a~1b/1/~0"], &Value::Null);
+        assert_eq!(nodes["/2"], &json!([]));
+        for (pointer, node) in value.iter_deep() {
+            assert_eq!(value.pointer(&pointer), Some(node));
+        }
+    }
+
+    #[test]
+    fn iter_deep_is_pre_order() {
+        let value = json!([[1, [2]], 3]);
+        let pointers: Vec<_> = value.iter_deep().map(|(pointer, _)| pointer).collect();
+        assert_eq!(pointers, ["", "/0", "/0/0", "/0/1", "/0/1/0", "/1"]);
+    }
+
+    #[test]
+    fn iter_deep_counts_every_node() {
+        let value = json!({"a": [1, 2, {"b": null, "c": {}}], "d": "x", "e": []});
+        // root, a, 1, 2, {b, c}, b, c, d, e
+        assert_eq!(value.iter_deep().count(), 9);
+        assert_eq!(json!(1).iter_deep().count(), 1);
+    }
+
+    #[test]
+    fn leaf_paths_of_json_org_example() -> Result<(), ParseError> {
+        // https://www.json.org/example.html
+        let value = parse(
+            "{
+                \"glossary\": {
+                    \"title\": \"example glossary\",
+                    \"GlossDiv\": {
+                        \"title\": \"S\",
+                        \"GlossList\": {
+                            \"GlossEntry\": {
+                                \"ID\": \"SGML\",
+                                \"SortAs\": \"SGML\",
+                                \"GlossTerm\": \"Standard Generalized Markup Language\",
+                                \"Acronym\": \"SGML\",
+                                \"Abbrev\": \"ISO 8879:1986\",
+                                \"GlossDef\": {
+                                    \"para\": \"A meta-markup language, used to create markup languages such as DocBook.\",
+                                    \"GlossSeeAlso\": [\"GML\", \"XML\"]
+                                },
+                                \"GlossSee\": \"markup\"
+                            }
+                        }
+                    }
+                }
+            }",
+        )?;
+        let entry = "/glossary/GlossDiv/GlossList/GlossEntry";
+        let expected: Vec<_> = [
+            "/Abbrev",
+            "/Acronym",
+            "/GlossDef/GlossSeeAlso/0",
+            "/GlossDef/GlossSeeAlso/1",
+            "/GlossDef/para",
+            "/GlossSee",
+            "/GlossTerm",
+            "/ID",
+            "/SortAs",
+        ]
+        .iter()
+        .map(|path| format!("{}{}", entry, path))
+        .chain([
+            "/glossary/GlossDiv/title".to_string(),
+            "/glossary/title".to_string(),
+        ])
+        .collect();
+        assert_eq!(value.leaf_paths().collect::<Vec<_>>(), expected);
+        Ok(())
+    }
+
+    #[test]
+    fn leaf_paths_of_scalars_and_empty_containers() {
+        for value in [json!(null), json!(1), json!([]), json!({})] {
+            assert_eq!(value.leaf_paths().collect::<Vec<_>>(), [""]);
+        }
+        let value = json!([{"~/": []}, [[]], {"": 0}]);
+        assert_eq!(
+            value.leaf_paths().collect::<Vec<_>>(),
+            ["/0/~0~1", "/1/0", "/2/"]
+        );
+    }
+
+    #[test]
+    fn find_at_several_depths() {
+        let value = json!({
+            "b": {"code": 2, "inner": {"code": 3}},
+            "a": [{"x": {"code": 1}}, {"code": 0}],
+            "code": 4,
+        });
+        assert_eq!(value.find("code"), Some(&json!(1)));
+        assert_eq!(
+            value.find_all("code"),
+            [
+                ("/a/0/x/code".to_string(), &json!(1)),
+                ("/a/1/code".to_string(), &json!(0)),
+                ("/b/code".to_string(), &json!(2)),
+                ("/b/inner/code".to_string(), &json!(3)),
+                ("/code".to_string(), &json!(4)),
+            ]
+        );
+        assert_eq!(value.find("nothing"), None);
+        assert!(value.find_all("nothing").is_empty());
+    }
+
+    #[test]
+    fn find_member_before_its_contents() {
+        let value = json!([{"k": {"k": 1}}, {"k/": 2}]);
+        assert_eq!(value.find("k"), Some(&json!({"k": 1})));
+        assert_eq!(
+            value.find_all("k"),
+            [
+                ("/0/k".to_string(), &json!({"k": 1})),
+                ("/0/k/k".to_string(), &json!(1)),
+            ]
+        );
+        assert_eq!(value.find_all("k/"), [("/1/k~1".to_string(), &json!(2))]);
+    }
+
+    #[test]
+    fn find_ignores_array_indices_and_scalars() {
+        assert_eq!(json!(["a", {"0": "b"}]).find("0"), Some(&json!("b")));
+        assert_eq!(json!("code").find("code"), None);
+    }
+
+    #[test]
+    fn iter_deep_mut_visits_leaves() {
+        let mut value = json!({"a": [1, {"b": 2}], "c": {}, "d/": "x"});
+        let mut pointers = Vec::new();
+        for (pointer, leaf) in value.iter_deep_mut() {
+            *leaf = json!(pointer.clone());
+            pointers.push(pointer);
+        }
+        pointers.sort();
+        assert_eq!(pointers, ["/a/0", "/a/1/b", "/c", "/d~1"]);
+        assert_eq!(
+            value,
+            json!({"a": ["/a/0", {"b": "/a/1/b"}], "c": "/c", "d/": "/d~1"})
+        );
+
+        let mut scalar = json!(true);
+        let leaves: Vec<_> = scalar.iter_deep_mut().collect();
+        assert_eq!(leaves, [(String::new(), &mut json!(true))]);
+    }
+}
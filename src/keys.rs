@@ -0,0 +1,292 @@
+use crate::error::KeyCollisionError;
+use crate::pointer;
+use crate::Value;
+use std::collections::{HashMap, HashSet};
+use std::mem;
+
+impl Value {
+    /// Rename every object key, anywhere in value, to what `f` returns
+    ///
+    /// Fails without changing anything when two keys of the same object are
+    /// renamed to the same name. `f` is called once for every key.
+    ///
+    /// ```rust
+    /// use wson::json;
+    /// # use std::error;
+    /// # fn main() -> Result<(), Box<dyn error::Error>> {
+    ///
+    ///
+    /// let mut value = json!({"a": {"b": 1}, "c": [{"d": 2}]});
+    /// value.transform_keys(|key| key.to_uppercase())?;
+    /// assert_eq!(value, json!({"A": {"B": 1}, "C": [{"D": 2}]}));
+    ///
+    /// let mut value = json!({"a": 1, "A": 2});
+    /// let error = value.transform_keys(|key| key.to_uppercase()).unwrap_err();
+    /// assert_eq!(error.key(), "A");
+    /// assert_eq!(value, json!({"a": 1, "A": 2}));
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn transform_keys<F>(&mut self, mut f: F) -> Result<(), KeyCollisionError>
+    where
+        F: FnMut(&str) -> String,
+    {
+        let renames = plan_renames(&mut String::new(), self, &mut f)?;
+        rename(self, renames);
+        Ok(())
+    }
+
+    /// Rename every key to camelCase, like `user_id` to `userId`
+    ///
+    /// Keys are split into words as `to_snake_case_keys` does. The first
+    /// word is lowercased, and the others are capitalized with the rest of
+    /// their letters lowercased, so acronyms become `HttpUrl` rather than
+    /// `HTTPURL`. Digits stay with the word before them, and a word starting
+    /// with a digit is kept as it is: `address_line_1` becomes
+    /// `addressLine1`. Leading underscores are kept.
+    pub fn to_camel_case_keys(&mut self) -> Result<(), KeyCollisionError> {
+        self.transform_keys(|key| {
+            convert_case(key, |words| {
+                let mut camel = String::new();
+                for (i, word) in words.iter().enumerate() {
+                    let lower = word.to_lowercase();
+                    let mut chars = lower.chars();
+                    match chars.next() {
+                        Some(first) if i > 0 => {
+                            camel.extend(first.to_uppercase());
+                            camel.push_str(chars.as_str());
+                        }
+                        _ => camel.push_str(&lower),
+                    }
+                }
+                camel
+            })
+        })
+    }
+
+    /// Rename every key to snake_case, like `userId` to `user_id`
+    ///
+    /// A key is split into words at `_`, `-` and spaces, before an
+    /// uppercase letter which follows a lowercase letter or a digit, and
+    /// before the last letter of a run of uppercase letters when a lowercase
+    /// letter follows, so `HTTPServer` is split into `HTTP` and `Server`.
+    /// Digits stay with the word before them: `addressLine1` becomes
+    /// `address_line1`. The words are lowercased and joined with `_`.
+    /// Leading underscores are kept.
+    pub fn to_snake_case_keys(&mut self) -> Result<(), KeyCollisionError> {
+        self.transform_keys(|key| convert_case(key, |words| words.join("_").to_lowercase()))
+    }
+}
+
+/// New keys of the objects in `value` and of the objects nested in them
+enum Renames {
+    Object(Vec<(String, String, Renames)>),
+    Array(Vec<Renames>),
+    None,
+}
+
+/// Work out every new key under `value` at `pointer` before renaming any
+fn plan_renames<F>(
+    pointer: &mut String,
+    value: &Value,
+    f: &mut F,
+) -> Result<Renames, KeyCollisionError>
+where
+    F: FnMut(&str) -> String,
+{
+    let len = pointer.len();
+    let renames = match value {
+        Value::Object(members) => {
+            let mut keys = HashSet::new();
+            let mut renames = Vec::with_capacity(members.len());
+            for (key, member) in members {
+                let renamed = f(key);
+                if !keys.insert(renamed.clone()) {
+                    return Err(KeyCollisionError::new(pointer.clone(), renamed));
+                }
+                pointer.push('/');
+                pointer.push_str(&pointer::escape(key));
+                let nested = plan_renames(pointer, member, f)?;
+                pointer.truncate(len);
+                renames.push((key.clone(), renamed, nested));
+            }
+            Renames::Object(renames)
+        }
+        Value::Array(elements) => {
+            let mut renames = Vec::with_capacity(elements.len());
+            for (index, element) in elements.iter().enumerate() {
+                pointer.push_str(&format!("/{}", index));
+                renames.push(plan_renames(pointer, element, f)?);
+                pointer.truncate(len);
+            }
+            Renames::Array(renames)
+        }
+        _ => Renames::None,
+    };
+    Ok(renames)
+}
+
+fn rename(value: &mut Value, renames: Renames) {
+    match (value, renames) {
+        (Value::Object(members), Renames::Object(renames)) => {
+            let mut renamed = HashMap::with_capacity(renames.len());
+            for (key, new_key, nested) in renames {
+                if let Some(mut member) = members.remove(&key) {
+                    rename(&mut member, nested);
+                    renamed.insert(new_key, member);
+                }
+            }
+            *members = renamed;
+        }
+        (Value::Array(elements), Renames::Array(renames)) => {
+            for (element, nested) in elements.iter_mut().zip(renames) {
+                rename(element, nested);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Split `key` into words, join them with `join`, and put back the leading
+/// underscores
+fn convert_case<F>(key: &str, join: F) -> String
+where
+    F: FnOnce(&[String]) -> String,
+{
+    let words = key.trim_start_matches('_');
+    let prefix = &key[..key.len() - words.len()];
+    format!("{}{}", prefix, join(&split_words(words)))
+}
+
+fn split_words(key: &str) -> Vec<String> {
+    let chars: Vec<char> = key.chars().collect();
+    let mut words = Vec::new();
+    let mut word = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if matches!(c, '_' | '-' | ' ') {
+            if !word.is_empty() {
+                words.push(mem::take(&mut word));
+            }
+            continue;
+        }
+        let previous = i.checked_sub(1).map(|i| chars[i]);
+        let next = chars.get(i + 1);
+        let starts_word = c.is_uppercase()
+            && match previous {
+                Some(previous) if previous.is_lowercase() || previous.is_ascii_digit() => true,
+                Some(previous) if previous.is_uppercase() => {
+                    next.is_some_and(|next| next.is_lowercase())
+                }
+                _ => false,
+            };
+        if starts_word && !word.is_empty() {
+            words.push(mem::take(&mut word));
+        }
+        word.push(c);
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json;
+
+    fn camel(key: &str) -> String {
+        let mut value = json!({ key: null });
+        value.to_camel_case_keys().unwrap();
+        value.as_object().unwrap().keys().next().unwrap().clone()
+    }
+
+    fn snake(key: &str) -> String {
+        let mut value = json!({ key: null });
+        value.to_snake_case_keys().unwrap();
+        value.as_object().unwrap().keys().next().unwrap().clone()
+    }
+
+    #[test]
+    fn camel_case() {
+        assert_eq!(camel("user_id"), "userId");
+        assert_eq!(camel("http_server_url"), "httpServerUrl");
+        assert_eq!(camel("HTTP_SERVER"), "httpServer");
+        assert_eq!(camel("address_line_1"), "addressLine1");
+        assert_eq!(camel("_trace_id"), "_traceId");
+        assert_eq!(camel("kebab-case key"), "kebabCaseKey");
+        assert_eq!(camel("alreadyCamel"), "alreadyCamel");
+        assert_eq!(camel("XMLHttpRequest"), "xmlHttpRequest");
+        assert_eq!(camel("version_2_beta"), "version2Beta");
+        assert_eq!(camel(""), "");
+    }
+
+    #[test]
+    fn snake_case() {
+        assert_eq!(snake("userId"), "user_id");
+        assert_eq!(snake("HTTPServer"), "http_server");
+        assert_eq!(snake("XMLHttpRequest"), "xml_http_request");
+        assert_eq!(snake("addressLine1"), "address_line1");
+        assert_eq!(snake("line1Text"), "line1_text");
+        assert_eq!(snake("userID"), "user_id");
+        assert_eq!(snake("_traceId"), "_trace_id");
+        assert_eq!(snake("already_snake"), "already_snake");
+        assert_eq!(snake("Kebab-Case"), "kebab_case");
+    }
+
+    #[test]
+    fn nested_documents() -> Result<(), KeyCollisionError> {
+        let mut value = json!({
+            "user_name": "a",
+            "home_address": {"zip_code": "1", "line_items": [{"item_id": 1}, {"unit_price": 2}]},
+            "tag_list": ["keep_values", {"created_at": null}],
+        });
+        value.to_camel_case_keys()?;
+        assert_eq!(
+            value,
+            json!({
+                "userName": "a",
+                "homeAddress": {"zipCode": "1", "lineItems": [{"itemId": 1}, {"unitPrice": 2}]},
+                "tagList": ["keep_values", {"createdAt": null}],
+            })
+        );
+        value.to_snake_case_keys()?;
+        assert_eq!(
+            value,
+            json!({
+                "user_name": "a",
+                "home_address": {"zip_code": "1", "line_items": [{"item_id": 1}, {"unit_price": 2}]},
+                "tag_list": ["keep_values", {"created_at": null}],
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn collisions_change_nothing() {
+        let original = json!({"a": {"b": [{"user_id": 1, "userId": 2}]}, "c_d": 3});
+        let mut value = original.clone();
+        let error = value.to_camel_case_keys().unwrap_err();
+        assert_eq!(error.pointer(), "/a/b/0");
+        assert_eq!(error.key(), "userId");
+        assert_eq!(
+            error.to_string(),
+            "more than one key renamed to \"userId\" in \"/a/b/0\""
+        );
+        assert_eq!(value, original);
+    }
+
+    #[test]
+    fn transform_calls_f_once_per_key() -> Result<(), KeyCollisionError> {
+        let mut value = json!({"a": {"a": [{"a": 1}]}, "b": 2});
+        let mut calls = 0;
+        value.transform_keys(|key| {
+            calls += 1;
+            format!("{}{}", key, key)
+        })?;
+        assert_eq!(calls, 4);
+        assert_eq!(value, json!({"aa": {"aa": [{"aa": 1}]}, "bb": 2}));
+        Ok(())
+    }
+}
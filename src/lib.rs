@@ -1,39 +1,146 @@
 //! # wson
 //!
 //! [JSON](https://www.json.org/json-en.html) parser made with [nom](https://docs.rs/nom/latest/nom/).
+mod access;
+pub mod approx;
+mod arithmetic;
 pub mod boolean;
+pub mod builder;
+mod codec;
+#[cfg(feature = "color")]
+pub mod color;
+mod convert;
+mod debug;
+mod edit;
+mod eq;
+pub mod error;
+mod flatten;
+pub mod format;
+mod group;
+mod hash;
+pub mod iter;
+mod keys;
+mod macros;
+pub mod merge;
+mod merge_patch;
+mod normalize;
 pub mod null;
 pub mod number;
+mod ord;
+mod parse_options;
+pub mod patch;
+mod pointer;
+pub mod prune;
+pub mod raw;
+mod redact;
+mod select;
+pub mod serialize;
+pub mod stats;
 pub mod string;
+mod walk;
+pub mod writer;
 
 use boolean::{false_parser, true_parser};
+pub use builder::{ArrayBuilder, ObjectBuilder};
+pub use codec::{from_member, FromJson, ToJson};
+pub use edit::Entry;
+use error::{ParseError, ParseErrorKind};
+pub use format::{minify, prettify};
 use nom::{
     branch::alt,
     bytes::complete::tag,
     character::complete::{newline, space1},
     combinator::{map, recognize, value},
+    error::ErrorKind,
     multi::{many0, many1},
     sequence::{delimited, separated_pair},
     IResult,
 };
 use null::null;
-use number::{number, Number};
-use std::{collections::HashMap, error::Error};
-use string::string;
+use number::{number_with, Number};
+pub use ord::{MissingPosition, SortOrder};
+pub use parse_options::{LoneSurrogates, ParseOptions};
+pub use patch::diff;
+pub use redact::REDACTED;
+pub use serialize::{to_string, to_string_pretty, to_writer, to_writer_pretty};
+use std::{collections::HashMap, str::FromStr};
+use string::string_with;
+pub use walk::WalkAction;
+pub use writer::{
+    to_string_from_iter, to_string_from_members, to_writer_from_iter, to_writer_from_members,
+    JsonWriter,
+};
 
-#[derive(Debug, Clone, PartialEq)]
+/// A json value
+///
+/// `Value` is `Eq` and `Hash`, so it can be a `HashSet` element or a
+/// `HashMap` key. Objects hash independent of member order; see
+/// `Value::canonical_hash`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub enum Value {
     Object(HashMap<String, Value>),
     Array(Vec<Value>),
     Number(Number),
     String(String),
+    #[default]
     Null,
-    True,
-    False,
+    Bool(bool),
+}
+
+impl Value {
+    #[deprecated(since = "0.2.0", note = "use `Value::Bool(true)`")]
+    #[allow(non_upper_case_globals)]
+    pub const True: Value = Value::Bool(true);
+
+    #[deprecated(since = "0.2.0", note = "use `Value::Bool(false)`")]
+    #[allow(non_upper_case_globals)]
+    pub const False: Value = Value::Bool(false);
+
+    /// Empty object
+    pub fn new_object() -> Value {
+        Value::Object(HashMap::new())
+    }
+
+    /// Empty array
+    pub fn new_array() -> Value {
+        Value::Array(Vec::new())
+    }
+
+    /// Empty object which can hold `capacity` members without reallocating
+    ///
+    /// ```rust
+    /// use wson::Value;
+    /// # fn main() {
+    ///
+    ///
+    /// let mut value = Value::object_with_capacity(1000);
+    /// for i in 0..1000 {
+    ///     value.insert(i.to_string(), Value::Null);
+    /// }
+    /// assert_eq!(value.len(), Some(1000));
+    /// # }
+    /// ```
+    pub fn object_with_capacity(capacity: usize) -> Value {
+        Value::Object(HashMap::with_capacity(capacity))
+    }
+
+    /// Empty array which can hold `capacity` elements without reallocating
+    pub fn array_with_capacity(capacity: usize) -> Value {
+        Value::Array(Vec::with_capacity(capacity))
+    }
 }
 
 /// Parse json
 ///
+/// Integers which don't fit `u128` or `i128` are parsed as floats, losing
+/// precision. `ParseOptions::strict_integers` makes them an error.
+///
+/// A number too large for `f64`, like `1e400`, fails with
+/// `ParseErrorKind::NumberOutOfRange` at the number, because json has no
+/// infinity. `ParseOptions::infinite_floats` parses it as infinity instead.
+/// A number too small for `f64`, like `1e-400`, is parsed as `0.0` or
+/// `-0.0`.
+///
 /// ```rust
 /// use nom::error::{ErrorKind, Error};
 /// use nom::Err;
@@ -62,11 +169,11 @@ pub enum Value {
 ///
 /// // the parser will parse "true"
 /// let actual = parse("true")?;
-/// assert_eq!(actual, Value::True);
+/// assert_eq!(actual, Value::Bool(true));
 ///
 /// // the parser will parse "false"
 /// let actual = parse("false")?;
-/// assert_eq!(actual, Value::False);
+/// assert_eq!(actual, Value::Bool(false));
 ///
 /// // the parser will parse "\"hello\""
 /// let actual = parse("\"hello\"")?;
@@ -79,32 +186,105 @@ pub enum Value {
 ///   ("revision".to_string(), Value::Number(Number::PositiveInteger(12)))
 /// ])));
 ///
+/// // errors tell where the input went wrong, and don't borrow it
+/// let error = parse(&String::from("[1, 2"));
+/// assert_eq!(error.unwrap_err().to_string(), "expected ',' or ']' at line 1 column 6");
+///
 /// # Ok(())
 /// # }
 /// ```
-pub fn parse<'a>(input: &'a str) -> Result<Value, Box<dyn Error + 'a>> {
-    let (_, result) = json(input)?;
+pub fn parse(input: &str) -> Result<Value, ParseError> {
+    parse_with(input, &ParseOptions::new())
+}
 
-    Ok(result)
+/// Parse json as `options` say
+///
+/// ```rust
+/// use wson::{json, parse_with, ParseOptions};
+/// # use std::error;
+/// # fn main() -> Result<(), Box<dyn error::Error>> {
+///
+///
+/// let options = ParseOptions::new().normalize_numbers(true);
+/// assert_eq!(parse_with("{\"count\": 12.0}", &options)?, json!({"count": 12}));
+///
+/// # Ok(())
+/// # }
+/// ```
+pub fn parse_with(input: &str, options: &ParseOptions) -> Result<Value, ParseError> {
+    let mut value = match json(input, options) {
+        Ok(("", value)) => value,
+        Ok((rest, _)) => {
+            return Err(ParseError::new(
+                ParseErrorKind::TrailingCharacters,
+                input,
+                rest,
+            ))
+        }
+        Err(nom::Err::Failure(error)) if error.code == ErrorKind::TooLarge => {
+            return Err(ParseError::new(
+                ParseErrorKind::NumberOutOfRange,
+                input,
+                error.input,
+            ))
+        }
+        Err(nom::Err::Failure(error)) if error.code == ErrorKind::Escaped => {
+            return Err(ParseError::new(
+                ParseErrorKind::LoneSurrogate,
+                input,
+                error.input,
+            ))
+        }
+        Err(nom::Err::Failure(error)) if error.code == ErrorKind::Char => {
+            return Err(ParseError::new(
+                ParseErrorKind::ControlCharacter,
+                input,
+                error.input,
+            ))
+        }
+        // the input is checked token by token, which finds the exact position,
+        // falling back to where the parser itself gave up
+        Err(error) => {
+            let rest = match error {
+                nom::Err::Error(error) | nom::Err::Failure(error) => error.input,
+                nom::Err::Incomplete(_) => "",
+            };
+            return Err(format::check(input, options).err().unwrap_or_else(|| {
+                ParseError::new(ParseErrorKind::Expected("value"), input, rest)
+            }));
+        }
+    };
+    if options.normalize_numbers {
+        value.normalize_numbers();
+    }
+    Ok(value)
 }
 
-fn json(input: &str) -> IResult<&str, Value> {
-    element(input)
+impl FromStr for Value {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        parse(input)
+    }
+}
+
+fn json<'a>(input: &'a str, options: &ParseOptions) -> IResult<&'a str, Value> {
+    element(input, options)
 }
 
-fn value_parser(input: &str) -> IResult<&str, Value> {
+fn value_parser<'a>(input: &'a str, options: &ParseOptions) -> IResult<&'a str, Value> {
     alt((
-        map(object, |m| Value::Object(m)),
-        map(array, |v| Value::Array(v)),
-        map(number, |num| Value::Number(num)),
-        map(string, |json_string| Value::String(json_string.0)),
+        map(|input| object(input, options), Value::Object),
+        map(|input| array(input, options), Value::Array),
+        map(|input| number_with(input, options), Value::Number),
+        map(|input| string_with(input, options), Value::from),
         value(Value::Null, null),
-        value(Value::True, true_parser),
-        value(Value::False, false_parser),
+        map(true_parser, Value::Bool),
+        map(false_parser, Value::Bool),
     ))(input)
 }
 
-fn object(input: &str) -> IResult<&str, HashMap<String, Value>> {
+fn object<'a>(input: &'a str, options: &ParseOptions) -> IResult<&'a str, HashMap<String, Value>> {
     delimited(
         ws,
         alt((
@@ -115,7 +295,7 @@ fn object(input: &str) -> IResult<&str, HashMap<String, Value>> {
             map(
                 delimited(
                     delimited(ws, tag("{"), ws),
-                    members,
+                    |input| members(input, options),
                     delimited(ws, tag("}"), ws),
                 ),
                 |v| {
@@ -131,45 +311,60 @@ fn object(input: &str) -> IResult<&str, HashMap<String, Value>> {
     )(input)
 }
 
-fn members(input: &str) -> IResult<&str, Vec<(String, Value)>> {
+fn members<'a>(input: &'a str, options: &ParseOptions) -> IResult<&'a str, Vec<(String, Value)>> {
     alt((
-        map(separated_pair(member, tag(","), members), |(m, ms)| {
-            let vec = vec![m];
-            [vec, ms].concat()
-        }),
-        map(member, |p| vec![p]),
+        map(
+            separated_pair(
+                |input| member(input, options),
+                tag(","),
+                |input| members(input, options),
+            ),
+            |(m, ms)| {
+                let vec = vec![m];
+                [vec, ms].concat()
+            },
+        ),
+        map(|input| member(input, options), |p| vec![p]),
     ))(input)
 }
 
-fn member(input: &str) -> IResult<&str, (String, Value)> {
+fn member<'a>(input: &'a str, options: &ParseOptions) -> IResult<&'a str, (String, Value)> {
     map(
-        separated_pair(delimited(ws, string, ws), tag(":"), element),
-        |(key, value)| (key.0, value),
+        separated_pair(
+            delimited(ws, |input| string_with(input, options), ws),
+            tag(":"),
+            |input| element(input, options),
+        ),
+        |(key, value)| (key.into_string(), value),
     )(input)
 }
 
-fn array(input: &str) -> IResult<&str, Vec<Value>> {
+fn array<'a>(input: &'a str, options: &ParseOptions) -> IResult<&'a str, Vec<Value>> {
     alt((
         value(vec![], delimited(tag("["), ws, tag("]"))),
-        delimited(tag("["), elements, tag("]")),
+        delimited(tag("["), |input| elements(input, options), tag("]")),
     ))(input)
 }
 
-fn elements(input: &str) -> IResult<&str, Vec<Value>> {
+fn elements<'a>(input: &'a str, options: &ParseOptions) -> IResult<&'a str, Vec<Value>> {
     alt((
         map(
-            separated_pair(element, tag(","), elements),
+            separated_pair(
+                |input| element(input, options),
+                tag(","),
+                |input| elements(input, options),
+            ),
             |(e, es): (Value, Vec<Value>)| {
                 let vec = vec![e];
                 [vec, es].concat()
             },
         ),
-        map(element, |e| vec![e]),
+        map(|input| element(input, options), |e| vec![e]),
     ))(input)
 }
 
-fn element(input: &str) -> IResult<&str, Value> {
-    delimited(ws, value_parser, ws)(input)
+fn element<'a>(input: &'a str, options: &ParseOptions) -> IResult<&'a str, Value> {
+    delimited(ws, |input| value_parser(input, options), ws)(input)
 }
 
 fn ws(input: &str) -> IResult<&str, &str> {
@@ -183,6 +378,495 @@ mod tests {
 
     type TestResult = Result<(), Box<dyn error::Error>>;
 
+    #[test]
+    fn parse_booleans() -> TestResult {
+        let value = parse("[true, false]")?;
+        assert_eq!(
+            value,
+            Value::Array(vec![Value::Bool(true), Value::Bool(false)])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn value_from_runtime_bool() {
+        let flags: Vec<Value> = [1, 2, 3].iter().map(|n| Value::from(n % 2 == 1)).collect();
+        assert_eq!(
+            flags,
+            vec![Value::Bool(true), Value::Bool(false), Value::Bool(true)]
+        );
+        let value: Value = false.into();
+        assert_eq!(value.as_bool(), Some(false));
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn deprecated_boolean_constants() {
+        assert_eq!(Value::True, Value::Bool(true));
+        assert_eq!(Value::False, Value::Bool(false));
+    }
+
+    #[test]
+    fn default_is_null() {
+        #[derive(Default)]
+        struct Document {
+            body: Value,
+        }
+        assert_eq!(Value::default(), Value::Null);
+        assert_eq!(Document::default().body, Value::Null);
+    }
+
+    #[test]
+    fn empty_constructors() {
+        assert_eq!(Value::new_object(), Value::Object(HashMap::new()));
+        assert_eq!(Value::new_array(), Value::Array(vec![]));
+        assert_eq!(Value::object_with_capacity(3), Value::new_object());
+        assert_eq!(Value::array_with_capacity(3), Value::new_array());
+    }
+
+    #[test]
+    fn capacity_constructors_reserve() {
+        let n = 10_000;
+        if let Value::Object(mut members) = Value::object_with_capacity(n) {
+            let capacity = members.capacity();
+            assert!(capacity >= n);
+            for i in 0..n {
+                members.insert(i.to_string(), Value::Null);
+            }
+            assert_eq!(members.capacity(), capacity);
+        }
+        if let Value::Array(mut elements) = Value::array_with_capacity(n) {
+            let ptr = elements.as_ptr();
+            elements.extend((0..n).map(|_| Value::Null));
+            assert_eq!(elements.as_ptr(), ptr);
+        }
+    }
+
+    #[test]
+    fn from_str() -> TestResult {
+        assert_eq!("{}".parse::<Value>()?, Value::new_object());
+        assert_eq!(" [1] ".parse::<Value>()?, Value::from(vec![1u8]));
+        for (input, offset) in [
+            ("01", 1),
+            ("[1] x", 4),
+            ("1.5.2", 3),
+            ("nullx", 4),
+            (r#""a""b""#, 3),
+        ] {
+            let error = input.parse::<Value>().unwrap_err();
+            assert_eq!(
+                error.kind(),
+                &ParseErrorKind::TrailingCharacters,
+                "{}",
+                input
+            );
+            assert_eq!(error.offset(), offset, "{}", input);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn parse_error_outlives_input() {
+        let error = {
+            let input = String::from("{\"a\": [1,\n  }");
+            input.parse::<Value>().unwrap_err()
+        };
+        assert_eq!(error.kind(), &ParseErrorKind::Expected("value"));
+        assert_eq!((error.line(), error.column()), (2, 3));
+        let boxed: Box<dyn std::error::Error + 'static> = Box::new(error);
+        assert_eq!(boxed.to_string(), "expected value at line 2 column 3");
+    }
+
+    #[test]
+    fn integer_limits() -> TestResult {
+        assert_eq!(
+            parse("18446744073709551615")?,
+            Value::Number(Number::PositiveInteger(u64::MAX))
+        );
+        assert_eq!(
+            parse("-9223372036854775808")?,
+            Value::Number(Number::NegativeInteger(i64::MIN))
+        );
+        assert_eq!(
+            parse("18446744073709551616")?,
+            Value::Number(Number::PositiveInteger128(1 << 64))
+        );
+        assert_eq!(
+            parse("-9223372036854775809")?,
+            Value::Number(Number::NegativeInteger128(-(1 << 63) - 1))
+        );
+        assert_eq!(
+            parse("340282366920938463463374607431768211455")?,
+            Value::Number(Number::PositiveInteger128(u128::MAX))
+        );
+        assert_eq!(
+            parse("-170141183460469231731687303715884105728")?,
+            Value::Number(Number::NegativeInteger128(i128::MIN))
+        );
+        assert_eq!(
+            parse("340282366920938463463374607431768211456")?,
+            Value::Number(Number::Float(3.402823669209385e38))
+        );
+        assert_eq!(
+            parse("-170141183460469231731687303715884105729")?,
+            Value::Number(Number::Float(-1.7014118346046923e38))
+        );
+        let digits = format!("[1{}]", "0".repeat(99));
+        assert_eq!(parse(&digits)?, Value::from(vec![1e99]));
+        Ok(())
+    }
+
+    #[test]
+    fn strict_integers() -> TestResult {
+        let options = ParseOptions::new().strict_integers(true);
+        assert_eq!(
+            parse_with("[18446744073709551616, -9223372036854775809]", &options)?,
+            Value::from(vec![
+                Value::from(1u128 << 64),
+                Value::from(-(1i128 << 63) - 1)
+            ])
+        );
+        assert_eq!(parse_with("1e100", &options)?, Value::from(1e100));
+        for (input, column) in [
+            ("340282366920938463463374607431768211456", 1),
+            ("[1, -170141183460469231731687303715884105729]", 5),
+            (
+                "{\"a\":\n  [100000000000000000000000000000000000000000]}",
+                4,
+            ),
+        ] {
+            let error = parse_with(input, &options).unwrap_err();
+            assert_eq!(error.kind(), &ParseErrorKind::NumberOutOfRange, "{}", input);
+            assert_eq!(error.column(), column, "{}", input);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn float_overflow() -> TestResult {
+        for (input, literal, column) in [
+            ("1e400", "1e400", 1),
+            ("[1, -1e400]", "-1e400", 5),
+            ("{\"a\":\n  [0.5E+309]}", "0.5E+309", 4),
+            ("1.7976931348623159e308", "1.7976931348623159e308", 1),
+        ] {
+            let error = parse(input).unwrap_err();
+            assert_eq!(error.kind(), &ParseErrorKind::NumberOutOfRange, "{}", input);
+            assert_eq!(error.column(), column, "{}", input);
+            assert!(input[error.offset()..].starts_with(literal), "{}", input);
+        }
+        assert_eq!(
+            parse("1.7976931348623157e308")?,
+            Value::Number(Number::Float(f64::MAX))
+        );
+        assert_eq!(
+            parse("-1.7976931348623158e308")?,
+            Value::Number(Number::Float(f64::MIN))
+        );
+
+        let options = ParseOptions::new().infinite_floats(true);
+        assert_eq!(
+            parse_with("[1e400, -1e400]", &options)?,
+            Value::from(vec![f64::INFINITY, f64::NEG_INFINITY])
+        );
+        let raw = ParseOptions::new().raw_numbers(true);
+        assert!(parse_with("1e400", &raw).is_err());
+        assert_eq!(
+            "1e400".parse::<Number>().unwrap_err().kind(),
+            &ParseErrorKind::NumberOutOfRange
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn float_underflow() -> TestResult {
+        match parse("[1e-400, -1e-400, 4.9e-324]")? {
+            Value::Array(elements) => {
+                let floats: Vec<_> = elements.iter().map(|e| e.as_f64()).collect();
+                assert_eq!(floats[0].map(f64::to_bits), Some(0.0f64.to_bits()));
+                assert_eq!(floats[1].map(f64::to_bits), Some((-0.0f64).to_bits()));
+                assert_eq!(floats[2], Some(f64::from_bits(1)));
+            }
+            value => panic!("{:?}", value),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn leading_zeros() -> TestResult {
+        assert_eq!(parse("0")?, Value::Number(Number::PositiveInteger(0)));
+        assert_eq!(parse("0.05")?, Value::Number(Number::Float(0.05)));
+        assert_eq!(parse("10e00")?, Value::Number(Number::Float(10.0)));
+        for input in ["01", "-01", "00", "00.5", "1.", "1e", "-"] {
+            assert!(parse(input).is_err(), "{}", input);
+            assert!(parse(&format!("[{}]", input)).is_err(), "{}", input);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn million_digits() -> TestResult {
+        let digits = "9".repeat(1_000_000);
+        let options = ParseOptions::new().infinite_floats(true);
+        assert_eq!(
+            parse_with(&digits, &options)?,
+            Value::Number(Number::Float(f64::INFINITY))
+        );
+        let error = parse(&digits).unwrap_err();
+        assert_eq!(error.kind(), &ParseErrorKind::NumberOutOfRange);
+        assert_eq!(
+            parse(&format!("[0.{}e-3]", digits))?,
+            Value::Array(vec![Value::Number(Number::Float(0.001))])
+        );
+        let error = parse_with(&digits, &ParseOptions::new().strict_integers(true)).unwrap_err();
+        assert_eq!(error.kind(), &ParseErrorKind::NumberOutOfRange);
+        Ok(())
+    }
+
+    #[test]
+    fn exponent_integers() -> TestResult {
+        let options = ParseOptions::new().exponent_integers(true);
+        let value = parse_with(r#"{"count": 1e3, "ratio": 1e-2}"#, &options)?;
+        assert_eq!(value["count"], Value::Number(Number::PositiveInteger(1000)));
+        assert_eq!(value["ratio"], Value::Number(Number::Float(0.01)));
+        assert_eq!(parse("1e3")?, Value::Number(Number::Float(1000.0)));
+        Ok(())
+    }
+
+    #[test]
+    fn numeric_separators() -> TestResult {
+        let options = ParseOptions::new().allow_numeric_separators(true);
+        let number = |input| parse_with(input, &options).map(|value| value[0].clone());
+        for (input, expected) in [
+            ("[1_000]", Number::PositiveInteger(1000)),
+            ("[-1_000_000]", Number::NegativeInteger(-1_000_000)),
+            ("[1_2_3]", Number::PositiveInteger(123)),
+            ("[1.234_567]", Number::Float(1.234_567)),
+            ("[1e1_0]", Number::Float(1e10)),
+            ("[1_0.0_1E-0_1]", Number::Float(1.001)),
+            ("[0.0_0]", Number::Float(0.0)),
+            ("[1000]", Number::PositiveInteger(1000)),
+        ] {
+            assert_eq!(number(input)?, Value::Number(expected), "{}", input);
+        }
+        for input in [
+            "[_1]", "[1_]", "[1__0]", "[-_1]", "[0_1]", "[1_.5]", "[1._5]", "[1.5_]", "[1_e5]",
+            "[1e_5]", "[1e+_5]", "[1e5_]",
+        ] {
+            assert!(parse_with(input, &options).is_err(), "{}", input);
+        }
+        for input in ["[1_000]", "[1.234_567]", "[1e1_0]", "1_000", "1_", "1__0"] {
+            assert!(parse(input).is_err(), "{}", input);
+        }
+
+        // outside an array too, where nothing may follow the number
+        assert_eq!(parse_with("1_000", &options)?, json!(1000));
+        assert_eq!(parse_with(" 1_0.0_1 ", &options)?, json!(10.01));
+        for input in ["1_000_", "1__0", "_1", "1_e5", "1e5_"] {
+            assert!(parse_with(input, &options).is_err(), "{}", input);
+        }
+        let error = parse("1_000").unwrap_err();
+        assert_eq!(error.kind(), &ParseErrorKind::TrailingCharacters);
+        assert_eq!(error.offset(), 1);
+        let error = parse_with("1_000_", &options).unwrap_err();
+        assert_eq!(error.kind(), &ParseErrorKind::TrailingCharacters);
+        assert_eq!(error.offset(), 5);
+
+        // other syntax is parsed as without the flag
+        for input in ["[01]", "[1.]", "[.5]", "[+1]", "[1e]", "[_]", "{_: 1}"] {
+            assert!(parse_with(input, &options).is_err(), "{}", input);
+        }
+        assert_eq!(
+            parse_with(r#"{"a_b": "1_0"}"#, &options)?,
+            parse(r#"{"a_b": "1_0"}"#)?
+        );
+        let error = parse_with("[1_000e400]", &options).unwrap_err();
+        assert_eq!(error.kind(), &ParseErrorKind::NumberOutOfRange);
+
+        // syntax errors after a separated number are found where they are
+        let error = parse_with("[1_000, x]", &options).unwrap_err();
+        assert_eq!(error.kind(), &ParseErrorKind::Expected("value"));
+        assert_eq!(error.offset(), 8);
+        let error = parse_with("{\"a\": 1_0 \"b\": 2}", &options).unwrap_err();
+        assert_eq!(error.kind(), &ParseErrorKind::Expected("',' or '}'"));
+        assert_eq!(error.offset(), 10);
+        let raw = options.raw_numbers(true);
+        assert_eq!(to_string(&parse_with("[1_000.5]", &raw)?)?, "[1000.5]");
+        Ok(())
+    }
+
+    #[test]
+    fn lax_numbers() -> TestResult {
+        let plus = ParseOptions::new().allow_plus_sign(true);
+        let lax = ParseOptions::new().allow_lax_decimal(true);
+        let both = plus.clone().allow_lax_decimal(true);
+        for (input, options, expected) in [
+            ("[+1]", &plus, Number::PositiveInteger(1)),
+            ("[+0]", &plus, Number::PositiveInteger(0)),
+            ("[+1.5]", &plus, Number::Float(1.5)),
+            ("[+1e3]", &plus, Number::Float(1000.0)),
+            ("[.5]", &lax, Number::Float(0.5)),
+            ("[-.5]", &lax, Number::Float(-0.5)),
+            ("[2.]", &lax, Number::Float(2.0)),
+            ("[-2.]", &lax, Number::Float(-2.0)),
+            ("[.5e3]", &lax, Number::Float(500.0)),
+            ("[2.e-1]", &lax, Number::Float(0.2)),
+            ("[+.5e3]", &both, Number::Float(500.0)),
+            ("[+2.]", &both, Number::Float(2.0)),
+        ] {
+            assert_eq!(
+                parse_with(input, options)?,
+                Value::Array(vec![Value::Number(expected)]),
+                "{}",
+                input
+            );
+            assert!(parse(input).is_err(), "{}", input);
+        }
+        assert!(parse_with("[+.5]", &plus).is_err());
+        assert!(parse_with("[+.5]", &lax).is_err());
+
+        // outside an array too, where nothing may follow the number
+        assert_eq!(parse_with("2.", &lax)?, json!(2.0));
+        assert_eq!(parse_with(" 2.e3 ", &lax)?, json!(2000.0));
+        assert_eq!(parse_with("+1", &plus)?, json!(1));
+        for (input, offset) in [("2.", 1), ("2.e3", 1), ("1e", 1), ("1.5.2", 3)] {
+            let error = parse(input).unwrap_err();
+            assert_eq!(
+                error.kind(),
+                &ParseErrorKind::TrailingCharacters,
+                "{}",
+                input
+            );
+            assert_eq!(error.offset(), offset, "{}", input);
+        }
+        assert!(parse(".5").is_err());
+        assert!(parse("+1").is_err());
+
+        // syntax errors after lenient numbers are found where they are
+        let error = parse_with("[+1, .5, x]", &both).unwrap_err();
+        assert_eq!(error.kind(), &ParseErrorKind::Expected("value"));
+        assert_eq!(error.offset(), 9);
+        let error = parse_with("[2., +3 4]", &both).unwrap_err();
+        assert_eq!(error.kind(), &ParseErrorKind::Expected("',' or ']'"));
+        assert_eq!(error.offset(), 8);
+        assert_eq!(
+            parse_with(
+                r#"{"a": [+1_0.5_0]}"#,
+                &both.clone().allow_numeric_separators(true)
+            )?,
+            json!({"a": [10.5]})
+        );
+
+        for input in [
+            "[.]", "[-.]", "[+.]", "[++1]", "[+-1]", "[-+1]", "[+]", "[.e3]", "[01.]", "[1..5]",
+        ] {
+            assert!(parse_with(input, &both).is_err(), "{}", input);
+        }
+        let raw = both.raw_numbers(true);
+        assert_eq!(
+            to_string(&parse_with("[+.5, 2., -0.]", &raw)?)?,
+            "[0.5,2.0,-0.0]"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn unicode_escapes() -> TestResult {
+        assert_eq!(
+            parse(r#"{"\u3053": ["\ud83d\ude00", "\u0000"]}"#)?,
+            json!({"こ": ["😀", "\u{0}"]})
+        );
+        let error = parse("{\"a\": [\n  \"x\\ud83d\"]}").unwrap_err();
+        assert_eq!(error.kind(), &ParseErrorKind::LoneSurrogate);
+        assert_eq!((error.line(), error.column()), (2, 5));
+        assert_eq!(minify(r#"["\ud800"]"#)?, r#"["\ud800"]"#);
+        Ok(())
+    }
+
+    #[test]
+    fn lone_surrogates() -> TestResult {
+        let replace = ParseOptions::new().lone_surrogates(LoneSurrogates::ReplacementChar);
+        let skip = ParseOptions::new().lone_surrogates(LoneSurrogates::Skip);
+        // the i_ cases of JSONTestSuite, which parsers may accept or reject
+        for (input, replaced, skipped, column) in [
+            (r#"["\uDADA"]"#, json!(["\u{fffd}"]), json!([""]), 3),
+            (
+                r#"["\uD888\u1234"]"#,
+                json!(["\u{fffd}\u{1234}"]),
+                json!(["\u{1234}"]),
+                3,
+            ),
+            (r#"["\uD800\n"]"#, json!(["\u{fffd}\n"]), json!(["\n"]), 3),
+            (r#"["\uDd1ea"]"#, json!(["\u{fffd}a"]), json!(["a"]), 3),
+            (
+                r#"["\uD800\uD800\n"]"#,
+                json!(["\u{fffd}\u{fffd}\n"]),
+                json!(["\n"]),
+                3,
+            ),
+            (r#"["\ud800"]"#, json!(["\u{fffd}"]), json!([""]), 3),
+            (
+                r#"["\ud800abc"]"#,
+                json!(["\u{fffd}abc"]),
+                json!(["abc"]),
+                3,
+            ),
+            (
+                r#"["\uDd1e\uD834"]"#,
+                json!(["\u{fffd}\u{fffd}"]),
+                json!([""]),
+                3,
+            ),
+            (r#"{"\uDFAA":0}"#, json!({"\u{fffd}": 0}), json!({"": 0}), 3),
+            (r#"["\uDFAA"]"#, json!(["\u{fffd}"]), json!([""]), 3),
+            (
+                r#"["a\ud83d\ude00\ud83d"]"#,
+                json!(["a😀\u{fffd}"]),
+                json!(["a😀"]),
+                16,
+            ),
+        ] {
+            let error = parse(input).unwrap_err();
+            assert_eq!(error.kind(), &ParseErrorKind::LoneSurrogate, "{}", input);
+            assert_eq!(error.column(), column, "{}", input);
+            assert_eq!(parse_with(input, &replace)?, replaced, "{}", input);
+            assert_eq!(parse_with(input, &skip)?, skipped, "{}", input);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn control_characters() -> TestResult {
+        let lenient = ParseOptions::new().allow_control_chars(true);
+        let error = parse("{\"a\": \"line\nbreak\"}").unwrap_err();
+        assert_eq!(error.kind(), &ParseErrorKind::ControlCharacter);
+        assert_eq!((error.line(), error.column()), (1, 12));
+        assert_eq!(
+            parse_with("{\"a\": \"line\nbreak\"}", &lenient)?,
+            json!({"a": "line\nbreak"})
+        );
+        for c in ['\u{0}', '\t', '\u{1f}'] {
+            let input = format!("[\"{}\"]", c);
+            let error = parse(&input).unwrap_err();
+            assert_eq!(error.kind(), &ParseErrorKind::ControlCharacter);
+            assert_eq!(error.offset(), 2);
+            assert_eq!(parse_with(&input, &lenient)?, json!([c.to_string()]));
+            let error = minify(&input).unwrap_err();
+            assert_eq!(error.kind(), &ParseErrorKind::ControlCharacter);
+        }
+        assert_eq!(parse_with("{\"\t\": 1}", &lenient)?, json!({"\t": 1}));
+        assert_eq!(parse("[\"\u{7f}\"]")?, json!(["\u{7f}"]));
+        for options in [ParseOptions::new(), lenient.clone()] {
+            assert_eq!(parse_with(r#"["\u0009"]"#, &options)?, json!(["\t"]));
+        }
+
+        // other errors are still found where they are
+        let error = parse_with("[\"\t\", 1 2]", &lenient).unwrap_err();
+        assert_eq!(error.kind(), &ParseErrorKind::Expected("',' or ']'"));
+        assert_eq!(error.offset(), 8);
+        Ok(())
+    }
+
     #[test]
     fn parse_zero() -> TestResult {
         let value = parse("0")?;
@@ -192,21 +876,21 @@ mod tests {
 
     #[test]
     fn empty_array() -> TestResult {
-        let value = array("[]")?;
+        let value = array("[]", &ParseOptions::new())?;
         assert_eq!(value, ("", vec![]));
         Ok(())
     }
 
     #[test]
     fn a_number_array() -> TestResult {
-        let value = array("[1]")?;
+        let value = array("[1]", &ParseOptions::new())?;
         assert_eq!(value, ("", vec![Value::Number(Number::PositiveInteger(1))]));
         Ok(())
     }
 
     #[test]
     fn multiple_number_array() -> TestResult {
-        let value = array("[1, 2]")?;
+        let value = array("[1, 2]", &ParseOptions::new())?;
         assert_eq!(
             value,
             (
@@ -222,7 +906,7 @@ mod tests {
 
     #[test]
     fn multiple_string_and_number_array() -> TestResult {
-        let value = array("[1, \"str\", 2.5e3]")?;
+        let value = array("[1, \"str\", 2.5e3]", &ParseOptions::new())?;
         assert_eq!(
             value,
             (
@@ -239,21 +923,21 @@ mod tests {
 
     #[test]
     fn parse_empty_object() -> TestResult {
-        let value = object("{ }")?;
+        let value = object("{ }", &ParseOptions::new())?;
         assert_eq!(value, ("", HashMap::new()));
         Ok(())
     }
 
     #[test]
     fn parse_empty_object2() -> TestResult {
-        let value = object(" { } ")?;
+        let value = object(" { } ", &ParseOptions::new())?;
         assert_eq!(value, ("", HashMap::new()));
         Ok(())
     }
 
     #[test]
     fn parse_a_object() -> TestResult {
-        let value = object("{\"key\": 1}")?;
+        let value = object("{\"key\": 1}", &ParseOptions::new())?;
         let mut expected = HashMap::new();
         expected.insert("key".to_string(), Value::Number(Number::PositiveInteger(1)));
 
@@ -263,7 +947,7 @@ mod tests {
 
     #[test]
     fn a_members() -> TestResult {
-        let value = members("\"key\": 1")?;
+        let value = members("\"key\": 1", &ParseOptions::new())?;
         assert_eq!(
             value,
             (
@@ -276,7 +960,7 @@ mod tests {
 
     #[test]
     fn multi_members() -> TestResult {
-        let value = members("\"key1\": 1, \"key2\": 2")?;
+        let value = members("\"key1\": 1, \"key2\": 2", &ParseOptions::new())?;
         assert_eq!(
             value,
             (
@@ -0,0 +1,217 @@
+/// Build a `Value` with json syntax
+///
+/// Any expression which implements `Into<Value>` can be a value, and any
+/// expression which implements `Into<String>` can be a key. Trailing commas
+/// are allowed.
+///
+/// ```rust
+/// use wson::{json, parse};
+/// # use std::error;
+/// # fn main() -> Result<(), Box<dyn error::Error>> {
+///
+///
+/// let name = "wson";
+/// let value = json!({
+///     "name": name,
+///     "tags": ["a", "b"],
+///     "count": 1 + 2,
+///     "extra": null,
+/// });
+/// assert_eq!(
+///     value,
+///     parse("{\"name\": \"wson\", \"tags\": [\"a\", \"b\"], \"count\": 3, \"extra\": null}")?
+/// );
+///
+/// # Ok(())
+/// # }
+/// ```
+#[macro_export]
+macro_rules! json {
+    (null) => {
+        $crate::Value::Null
+    };
+    ([]) => {
+        $crate::Value::Array(::std::vec::Vec::new())
+    };
+    ([ $($tt:tt)+ ]) => {
+        $crate::Value::Array($crate::json_internal!(@array [] $($tt)+))
+    };
+    ({}) => {
+        $crate::Value::Object(::std::collections::HashMap::new())
+    };
+    ({ $($tt:tt)+ }) => {{
+        let mut object = ::std::collections::HashMap::new();
+        $crate::json_internal!(@object object () ($($tt)+) ($($tt)+));
+        $crate::Value::Object(object)
+    }};
+    ($other:expr) => {
+        $crate::Value::from($other)
+    };
+}
+
+/// Munchers behind `json!`
+///
+/// `@array [elements] rest` collects array elements, and
+/// `@object map (key) (rest) (copy of rest)` inserts members into `map`.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! json_internal {
+    // done with an array, with or without a trailing comma
+    (@array [$($elements:expr,)*]) => {
+        ::std::vec![$($elements,)*]
+    };
+    (@array [$($elements:expr),*]) => {
+        ::std::vec![$($elements),*]
+    };
+    // next element is null, an array or an object
+    (@array [$($elements:expr,)*] null $($rest:tt)*) => {
+        $crate::json_internal!(@array [$($elements,)* $crate::json!(null)] $($rest)*)
+    };
+    (@array [$($elements:expr,)*] [$($array:tt)*] $($rest:tt)*) => {
+        $crate::json_internal!(@array [$($elements,)* $crate::json!([$($array)*])] $($rest)*)
+    };
+    (@array [$($elements:expr,)*] {$($object:tt)*} $($rest:tt)*) => {
+        $crate::json_internal!(@array [$($elements,)* $crate::json!({$($object)*})] $($rest)*)
+    };
+    // next element is an expression
+    (@array [$($elements:expr,)*] $next:expr, $($rest:tt)*) => {
+        $crate::json_internal!(@array [$($elements,)* $crate::json!($next),] $($rest)*)
+    };
+    (@array [$($elements:expr,)*] $last:expr) => {
+        $crate::json_internal!(@array [$($elements,)* $crate::json!($last)])
+    };
+    // comma after an element
+    (@array [$($elements:expr),*] , $($rest:tt)*) => {
+        $crate::json_internal!(@array [$($elements,)*] $($rest)*)
+    };
+
+    // done with an object
+    (@object $object:ident () () ()) => {};
+    // insert a member, followed by more members or by nothing
+    (@object $object:ident [$($key:tt)+] ($value:expr) , $($rest:tt)*) => {
+        let _ = $object.insert(::std::string::String::from($($key)+), $value);
+        $crate::json_internal!(@object $object () ($($rest)*) ($($rest)*));
+    };
+    (@object $object:ident [$($key:tt)+] ($value:expr)) => {
+        let _ = $object.insert(::std::string::String::from($($key)+), $value);
+    };
+    // value is null, an array or an object
+    (@object $object:ident ($($key:tt)+) (: null $($rest:tt)*) $copy:tt) => {
+        $crate::json_internal!(@object $object [$($key)+] ($crate::json!(null)) $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) (: [$($array:tt)*] $($rest:tt)*) $copy:tt) => {
+        $crate::json_internal!(@object $object [$($key)+] ($crate::json!([$($array)*])) $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) (: {$($map:tt)*} $($rest:tt)*) $copy:tt) => {
+        $crate::json_internal!(@object $object [$($key)+] ($crate::json!({$($map)*})) $($rest)*);
+    };
+    // value is an expression
+    (@object $object:ident ($($key:tt)+) (: $value:expr , $($rest:tt)*) $copy:tt) => {
+        $crate::json_internal!(@object $object [$($key)+] ($crate::json!($value)) , $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) (: $value:expr) $copy:tt) => {
+        $crate::json_internal!(@object $object [$($key)+] ($crate::json!($value)));
+    };
+    // key in parentheses, or one more token of the key
+    (@object $object:ident () (($key:expr) : $($rest:tt)*) $copy:tt) => {
+        $crate::json_internal!(@object $object ($key) (: $($rest)*) (: $($rest)*));
+    };
+    (@object $object:ident ($($key:tt)*) ($tt:tt $($rest:tt)*) $copy:tt) => {
+        $crate::json_internal!(@object $object ($($key)* $tt) ($($rest)*) ($($rest)*));
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::number::Number;
+    use crate::{parse, Value};
+    use std::collections::HashMap;
+
+    #[test]
+    fn scalars() {
+        assert_eq!(json!(null), Value::Null);
+        assert_eq!(json!(true), Value::Bool(true));
+        assert_eq!(json!(1), Value::Number(Number::PositiveInteger(1)));
+        assert_eq!(json!(-1.5), Value::Number(Number::Float(-1.5)));
+        assert_eq!(json!("a"), Value::String("a".to_string()));
+    }
+
+    #[test]
+    fn empty_containers() {
+        assert_eq!(json!([]), Value::Array(vec![]));
+        assert_eq!(json!({}), Value::Object(HashMap::new()));
+        assert_eq!(json!([[], {}]), Value::Array(vec![json!([]), json!({})]));
+    }
+
+    #[test]
+    fn arrays() {
+        assert_eq!(
+            json!([1, "a", null, true, [2], {"b": 3}]),
+            parse("[1, \"a\", null, true, [2], {\"b\": 3}]").unwrap()
+        );
+        assert_eq!(json!([1, 2,]), json!([1, 2]));
+        assert_eq!(json!([null]), Value::Array(vec![Value::Null]));
+        assert_eq!(json!([null,]), Value::Array(vec![Value::Null]));
+    }
+
+    #[test]
+    fn objects() {
+        assert_eq!(
+            json!({"a": 1, "b": [null], "c": {"d": false}, "e": null}),
+            parse("{\"a\": 1, \"b\": [null], \"c\": {\"d\": false}, \"e\": null}").unwrap()
+        );
+        assert_eq!(json!({"a": 1,}), json!({"a": 1}));
+        assert_eq!(json!({"a": null,}), json!({"a": null}));
+        assert_eq!(json!({"a": {},}), json!({"a": {}}));
+    }
+
+    #[test]
+    fn interpolated_values() {
+        let name = String::from("wson");
+        let tags = vec!["x", "y"];
+        let missing: Option<u8> = None;
+        let value = json!({
+            "name": name.clone(),
+            "len": name.len() as u64,
+            "sum": 1 + 2 * 3,
+            "tags": tags.clone(),
+            "first": tags[0],
+            "missing": missing,
+            "nested": [json!({"a": 1}), if tags.is_empty() { 0 } else { 1 }],
+        });
+        assert_eq!(
+            value,
+            parse(
+                "{\"name\": \"wson\", \"len\": 4, \"sum\": 7, \"tags\": [\"x\", \"y\"], \
+                 \"first\": \"x\", \"missing\": null, \"nested\": [{\"a\": 1}, 1]}"
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn expression_keys() {
+        let key = "dynamic";
+        let prefix = String::from("p");
+        let value = json!({
+            key: 1,
+            (format!("{}-{}", prefix, 2)): 2,
+            prefix.clone() + "x": 3,
+        });
+        assert_eq!(
+            value,
+            parse("{\"dynamic\": 1, \"p-2\": 2, \"px\": 3}").unwrap()
+        );
+    }
+
+    #[test]
+    fn deeply_nested() {
+        let value = json!({"a": [{"b": [[{"c": {"d": [1, [2, [3, {"e": null}]]]}}]]}]});
+        assert_eq!(value.pointer("/a/0/b/0/0/c/d/1/1/1/e"), Some(&Value::Null));
+        assert_eq!(
+            value,
+            parse("{\"a\": [{\"b\": [[{\"c\": {\"d\": [1, [2, [3, {\"e\": null}]]]}}]]}]}")
+                .unwrap()
+        );
+    }
+}
@@ -0,0 +1,299 @@
+//! Deep merge of values
+
+use crate::Value;
+use std::collections::hash_map;
+
+/// Options to control how `Value::merge_with` merges
+///
+/// The default is what `Value::merge` does: arrays are replaced and `null`
+/// overwrites.
+///
+/// ```rust
+/// use wson::json;
+/// use wson::merge::{ArrayMergeStrategy, MergeOptions, NullStrategy};
+/// # fn main() {
+///
+///
+/// let mut config = json!({"plugins": ["a"], "theme": "dark"});
+/// let options = MergeOptions::new()
+///     .arrays(ArrayMergeStrategy::Concat)
+///     .nulls(NullStrategy::Remove);
+/// config.merge_with(json!({"plugins": ["b"], "theme": null}), &options);
+/// assert_eq!(config, json!({"plugins": ["a", "b"]}));
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeOptions {
+    arrays: ArrayMergeStrategy,
+    nulls: NullStrategy,
+}
+
+/// How an array is merged into an array
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArrayMergeStrategy {
+    /// Replace the array with the other one
+    Replace,
+    /// Append the other elements
+    Concat,
+    /// Append the other elements which are not in the array yet
+    Union,
+    /// Merge elements at the same index, and append the rest of the other
+    /// elements
+    ByIndex,
+}
+
+/// What a `null` member of the other object does
+///
+/// `null` anywhere else replaces the value like any other value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NullStrategy {
+    /// Set the member to `null`
+    Overwrite,
+    /// Remove the member, like JSON Merge Patch
+    Remove,
+    /// Leave the member as it is
+    Skip,
+}
+
+impl MergeOptions {
+    /// Options to replace arrays and overwrite with `null`
+    pub fn new() -> Self {
+        MergeOptions {
+            arrays: ArrayMergeStrategy::Replace,
+            nulls: NullStrategy::Overwrite,
+        }
+    }
+
+    /// How arrays are merged, `ArrayMergeStrategy::Replace` by default
+    pub fn arrays(mut self, arrays: ArrayMergeStrategy) -> Self {
+        self.arrays = arrays;
+        self
+    }
+
+    /// What `null` members do, `NullStrategy::Overwrite` by default
+    pub fn nulls(mut self, nulls: NullStrategy) -> Self {
+        self.nulls = nulls;
+        self
+    }
+}
+
+impl Default for MergeOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Value {
+    /// Deep merge `other` into value, like defaults with overrides on top
+    ///
+    /// When both are objects, each member of `other` is merged into the
+    /// member with the same key, or added if there is none. Any other pair,
+    /// arrays included, is replaced with `other`. A `null` in `other`
+    /// overwrites too and doesn't delete anything.
+    ///
+    /// ```rust
+    /// use wson::json;
+    /// # fn main() {
+    ///
+    ///
+    /// let mut config = json!({"server": {"host": "localhost", "port": 80}, "tags": ["a"]});
+    /// config.merge(json!({"server": {"port": 8080}, "tags": ["b"], "debug": null}));
+    /// assert_eq!(
+    ///     config,
+    ///     json!({"server": {"host": "localhost", "port": 8080}, "tags": ["b"], "debug": null})
+    /// );
+    /// # }
+    /// ```
+    pub fn merge(&mut self, other: Value) {
+        self.merge_with(other, &MergeOptions::new())
+    }
+
+    /// Deep merge `other` into value, with arrays and `null` members handled
+    /// as `options` says
+    ///
+    /// Members added to an object and elements appended to an array are
+    /// taken from `other` as they are.
+    pub fn merge_with(&mut self, other: Value, options: &MergeOptions) {
+        match (self, other) {
+            (Value::Object(members), Value::Object(others)) => {
+                for (key, other) in others {
+                    match (other.is_null(), options.nulls) {
+                        (true, NullStrategy::Remove) => {
+                            members.remove(&key);
+                            continue;
+                        }
+                        (true, NullStrategy::Skip) => continue,
+                        _ => {}
+                    }
+                    match members.entry(key) {
+                        hash_map::Entry::Occupied(mut entry) => {
+                            entry.get_mut().merge_with(other, options)
+                        }
+                        hash_map::Entry::Vacant(entry) => {
+                            entry.insert(other);
+                        }
+                    }
+                }
+            }
+            (Value::Array(elements), Value::Array(others)) => match options.arrays {
+                ArrayMergeStrategy::Replace => *elements = others,
+                ArrayMergeStrategy::Concat => elements.extend(others),
+                ArrayMergeStrategy::Union => {
+                    for other in others {
+                        if !elements.contains(&other) {
+                            elements.push(other);
+                        }
+                    }
+                }
+                ArrayMergeStrategy::ByIndex => {
+                    let mut others = others.into_iter();
+                    for (element, other) in elements.iter_mut().zip(&mut others) {
+                        element.merge_with(other, options);
+                    }
+                    elements.extend(others);
+                }
+            },
+            (value, other) => *value = other,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json;
+
+    fn merged(arrays: ArrayMergeStrategy, nulls: NullStrategy) -> Value {
+        let mut value = json!({
+            "list": [1, {"a": 1, "b": 2}],
+            "tags": ["x", "y"],
+            "name": "base",
+            "nested": {"keep": true, "drop": 1},
+        });
+        value.merge_with(
+            json!({
+                "list": [2, {"a": 3, "b": null}, 4],
+                "tags": ["y", "z", "z"],
+                "name": null,
+                "nested": {"drop": null, "new": null},
+            }),
+            &MergeOptions::new().arrays(arrays).nulls(nulls),
+        );
+        value
+    }
+
+    #[test]
+    fn merge_replace_arrays() {
+        assert_eq!(
+            merged(ArrayMergeStrategy::Replace, NullStrategy::Overwrite),
+            json!({
+                "list": [2, {"a": 3, "b": null}, 4],
+                "tags": ["y", "z", "z"],
+                "name": null,
+                "nested": {"keep": true, "drop": null, "new": null},
+            })
+        );
+    }
+
+    #[test]
+    fn merge_concat_arrays() {
+        assert_eq!(
+            merged(ArrayMergeStrategy::Concat, NullStrategy::Overwrite),
+            json!({
+                "list": [1, {"a": 1, "b": 2}, 2, {"a": 3, "b": null}, 4],
+                "tags": ["x", "y", "y", "z", "z"],
+                "name": null,
+                "nested": {"keep": true, "drop": null, "new": null},
+            })
+        );
+    }
+
+    #[test]
+    fn merge_union_arrays() {
+        assert_eq!(
+            merged(ArrayMergeStrategy::Union, NullStrategy::Overwrite),
+            json!({
+                "list": [1, {"a": 1, "b": 2}, 2, {"a": 3, "b": null}, 4],
+                "tags": ["x", "y", "z"],
+                "name": null,
+                "nested": {"keep": true, "drop": null, "new": null},
+            })
+        );
+    }
+
+    #[test]
+    fn merge_arrays_by_index() {
+        assert_eq!(
+            merged(ArrayMergeStrategy::ByIndex, NullStrategy::Overwrite),
+            json!({
+                "list": [2, {"a": 3, "b": null}, 4],
+                "tags": ["y", "z", "z"],
+                "name": null,
+                "nested": {"keep": true, "drop": null, "new": null},
+            })
+        );
+        assert_eq!(
+            merged(ArrayMergeStrategy::ByIndex, NullStrategy::Skip)["list"],
+            json!([2, {"a": 3, "b": 2}, 4])
+        );
+    }
+
+    #[test]
+    fn merge_remove_nulls() {
+        assert_eq!(
+            merged(ArrayMergeStrategy::ByIndex, NullStrategy::Remove),
+            json!({
+                "list": [2, {"a": 3}, 4],
+                "tags": ["y", "z", "z"],
+                "nested": {"keep": true},
+            })
+        );
+    }
+
+    #[test]
+    fn merge_skip_nulls() {
+        assert_eq!(
+            merged(ArrayMergeStrategy::Replace, NullStrategy::Skip),
+            json!({
+                "list": [2, {"a": 3, "b": null}, 4],
+                "tags": ["y", "z", "z"],
+                "name": "base",
+                "nested": {"keep": true, "drop": 1},
+            })
+        );
+    }
+
+    #[test]
+    fn merge_nested_objects() {
+        let mut value = json!({"a": {"b": 1, "c": {"d": 2}}, "e": 3});
+        value.merge(json!({"a": {"c": {"f": 4}, "g": 5}, "h": 6}));
+        assert_eq!(
+            value,
+            json!({"a": {"b": 1, "c": {"d": 2, "f": 4}, "g": 5}, "e": 3, "h": 6})
+        );
+    }
+
+    #[test]
+    fn merge_replaces_arrays_and_scalars() {
+        let mut value = json!({"list": [1, 2, 3], "object": {"a": 1}, "n": 1});
+        value.merge(json!({"list": [4], "object": "x", "n": {"b": 2}}));
+        assert_eq!(value, json!({"list": [4], "object": "x", "n": {"b": 2}}));
+
+        let mut value = json!([1, {"a": 1}]);
+        value.merge(json!([{"b": 2}]));
+        assert_eq!(value, json!([{"b": 2}]));
+    }
+
+    #[test]
+    fn merge_null() {
+        let mut value = Value::Null;
+        value.merge(json!({"a": {"b": 1}}));
+        assert_eq!(value, json!({"a": {"b": 1}}));
+
+        value.merge(json!({"a": null}));
+        assert_eq!(value, json!({"a": null}));
+
+        value.merge(Value::Null);
+        assert_eq!(value, Value::Null);
+    }
+}
@@ -0,0 +1,141 @@
+//! [JSON Merge Patch](https://www.rfc-editor.org/rfc/rfc7386)
+
+use crate::Value;
+use std::collections::HashMap;
+
+impl Value {
+    /// Apply a JSON Merge Patch
+    ///
+    /// An object patch is applied member by member: `null` removes the
+    /// member, anything else is merge patched into it. Any other patch
+    /// replaces the whole value.
+    ///
+    /// ```rust
+    /// use wson::json;
+    /// # fn main() {
+    ///
+    ///
+    /// let mut settings = json!({"theme": "dark", "font": {"size": 12, "family": "mono"}});
+    /// settings.merge_patch(&json!({"theme": null, "font": {"size": 14}}));
+    /// assert_eq!(settings, json!({"font": {"size": 14, "family": "mono"}}));
+    /// # }
+    /// ```
+    pub fn merge_patch(&mut self, patch: &Value) {
+        let Value::Object(patches) = patch else {
+            *self = patch.clone();
+            return;
+        };
+        if !self.is_object() {
+            *self = Value::Object(HashMap::new());
+        }
+        if let Value::Object(members) = self {
+            for (key, patch) in patches {
+                if patch.is_null() {
+                    members.remove(key);
+                } else {
+                    members.entry(key.clone()).or_default().merge_patch(patch);
+                }
+            }
+        }
+    }
+
+    /// Smallest JSON Merge Patch which turns `original` into `target`
+    ///
+    /// A merge patch can't set a member to `null`, so applying the patch
+    /// gives back `target` only when none of its object members is `null`.
+    ///
+    /// ```rust
+    /// use wson::{json, Value};
+    /// # fn main() {
+    ///
+    ///
+    /// let original = json!({"a": 1, "b": {"c": 2, "d": 3}});
+    /// let target = json!({"b": {"c": 2, "d": 4}, "e": [5]});
+    /// let patch = Value::merge_patch_from(&original, &target);
+    /// assert_eq!(patch, json!({"a": null, "b": {"d": 4}, "e": [5]}));
+    /// # }
+    /// ```
+    pub fn merge_patch_from(original: &Value, target: &Value) -> Value {
+        let (Value::Object(originals), Value::Object(targets)) = (original, target) else {
+            return target.clone();
+        };
+        let removed = originals
+            .keys()
+            .filter(|key| !targets.contains_key(*key))
+            .map(|key| (key.clone(), Value::Null));
+        let changed = targets
+            .iter()
+            .filter_map(|(key, target)| match originals.get(key) {
+                Some(original) if original == target => None,
+                Some(original) => Some((key.clone(), Value::merge_patch_from(original, target))),
+                None => Some((key.clone(), target.clone())),
+            });
+        Value::Object(removed.chain(changed).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::ParseError;
+    use crate::{json, parse, Value};
+
+    // https://www.rfc-editor.org/rfc/rfc7386#appendix-A
+    const RFC_EXAMPLES: [(&str, &str, &str); 15] = [
+        (r#"{"a":"b"}"#, r#"{"a":"c"}"#, r#"{"a":"c"}"#),
+        (r#"{"a":"b"}"#, r#"{"b":"c"}"#, r#"{"a":"b","b":"c"}"#),
+        (r#"{"a":"b"}"#, r#"{"a":null}"#, r#"{}"#),
+        (r#"{"a":"b","b":"c"}"#, r#"{"a":null}"#, r#"{"b":"c"}"#),
+        (r#"{"a":["b"]}"#, r#"{"a":"c"}"#, r#"{"a":"c"}"#),
+        (r#"{"a":"c"}"#, r#"{"a":["b"]}"#, r#"{"a":["b"]}"#),
+        (
+            r#"{"a": {"b": "c"}}"#,
+            r#"{"a": {"b": "d","c": null}}"#,
+            r#"{"a": {"b": "d"}}"#,
+        ),
+        (r#"{"a": [{"b":"c"}]}"#, r#"{"a": [1]}"#, r#"{"a": [1]}"#),
+        (r#"["a","b"]"#, r#"["c","d"]"#, r#"["c","d"]"#),
+        (r#"{"a":"b"}"#, r#"["c"]"#, r#"["c"]"#),
+        (r#"{"a":"foo"}"#, r#"null"#, r#"null"#),
+        (r#"{"a":"foo"}"#, r#""bar""#, r#""bar""#),
+        (r#"{"e":null}"#, r#"{"a":1}"#, r#"{"e":null,"a":1}"#),
+        (r#"[1,2]"#, r#"{"a":"b","c":null}"#, r#"{"a":"b"}"#),
+        (
+            r#"{}"#,
+            r#"{"a":{"bb":{"ccc":null}}}"#,
+            r#"{"a":{"bb":{}}}"#,
+        ),
+    ];
+
+    #[test]
+    fn rfc_examples() -> Result<(), ParseError> {
+        for (original, patch, result) in RFC_EXAMPLES {
+            let mut value = parse(original)?;
+            value.merge_patch(&parse(patch)?);
+            assert_eq!(value, parse(result)?, "{} patched with {}", original, patch);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn merge_patch_from_rfc_examples() -> Result<(), ParseError> {
+        for (original, _, result) in RFC_EXAMPLES {
+            let (original, result) = (parse(original)?, parse(result)?);
+            let mut value = original.clone();
+            value.merge_patch(&Value::merge_patch_from(&original, &result));
+            assert_eq!(value, result, "{:?} into {:?}", original, result);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn merge_patch_from_is_minimal() {
+        let original = json!({"a": {"b": 1, "c": [1]}, "d": "x", "e": null});
+        let target = json!({"a": {"b": 1, "c": [1, 2]}, "d": "x", "f": {}});
+        assert_eq!(
+            Value::merge_patch_from(&original, &target),
+            json!({"a": {"c": [1, 2]}, "e": null, "f": {}})
+        );
+        assert_eq!(Value::merge_patch_from(&original, &original), json!({}));
+        assert_eq!(Value::merge_patch_from(&json!([1]), &json!(2)), json!(2));
+    }
+}
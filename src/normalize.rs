@@ -0,0 +1,129 @@
+use crate::number::Number;
+use crate::Value;
+
+impl Value {
+    /// Turn integral floats into integers, in the whole value
+    ///
+    /// A `Float` which is finite, has no fractional part and is within the
+    /// `u64` range, or the `i64` range when negative, becomes a
+    /// `PositiveInteger` or a `NegativeInteger`. Other floats are kept.
+    /// `-0.0` becomes `PositiveInteger(0)`.
+    ///
+    /// ```rust
+    /// use wson::json;
+    /// use wson::number::Number;
+    /// use wson::Value;
+    /// # fn main() {
+    ///
+    ///
+    /// let mut value = json!({"count": 12.0, "ratio": 1.5});
+    /// value.normalize_numbers();
+    /// assert_eq!(value["count"], Value::Number(Number::PositiveInteger(12)));
+    /// assert_eq!(value["ratio"], Value::Number(Number::Float(1.5)));
+    /// # }
+    /// ```
+    pub fn normalize_numbers(&mut self) {
+        let mut stack = vec![self];
+        while let Some(value) = stack.pop() {
+            match value {
+                Value::Object(members) => stack.extend(members.values_mut()),
+                Value::Array(elements) => stack.extend(elements.iter_mut()),
+                Value::Number(number) => {
+                    if let Some(integer) = integer(number) {
+                        *number = integer;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Integer with the same value as a float `number`, if there is one
+fn integer(number: &Number) -> Option<Number> {
+    match *number {
+        Number::Float(f) if f < 0.0 => number.as_i64().map(Number::NegativeInteger),
+        Number::Float(_) => number.as_u64().map(Number::PositiveInteger),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::number::Number;
+    use crate::{json, parse, Value};
+    use std::error;
+
+    fn normalized(value: Value) -> Value {
+        let mut value = value;
+        value.normalize_numbers();
+        value
+    }
+
+    fn float(f: f64) -> Value {
+        Value::Number(Number::Float(f))
+    }
+
+    #[test]
+    fn integral_floats() -> Result<(), Box<dyn error::Error>> {
+        assert_eq!(
+            normalized(float(12.0)),
+            Value::Number(Number::PositiveInteger(12))
+        );
+        assert_eq!(
+            normalized(float(-3.0)),
+            Value::Number(Number::NegativeInteger(-3))
+        );
+        assert_eq!(
+            normalized(parse("1e3")?),
+            Value::Number(Number::PositiveInteger(1000))
+        );
+        assert_eq!(
+            normalized(float(-0.0)),
+            Value::Number(Number::PositiveInteger(0))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn other_floats_are_kept() {
+        for f in [
+            1.5,
+            -0.25,
+            1e30,
+            -1e30,
+            18446744073709551616.0,
+            f64::INFINITY,
+        ] {
+            assert_eq!(normalized(float(f)), float(f));
+        }
+        assert_eq!(normalized(float(f64::NAN)), float(f64::NAN));
+    }
+
+    #[test]
+    fn range_limits() {
+        assert_eq!(
+            normalized(float(18446744073709549568.0)),
+            Value::Number(Number::PositiveInteger(18446744073709549568))
+        );
+        assert_eq!(
+            normalized(float(-9223372036854775808.0)),
+            Value::Number(Number::NegativeInteger(i64::MIN))
+        );
+        assert_eq!(
+            normalized(float(-9223372036854777856.0)),
+            float(-9223372036854777856.0)
+        );
+    }
+
+    #[test]
+    fn nested() {
+        let value = normalized(json!({"a": [1.0, {"b": -2.0}, 2.5], "c": "3.0", "d": 4}));
+        assert_eq!(value, json!({"a": [1, {"b": -2}, 2.5], "c": "3.0", "d": 4}));
+        assert_eq!(value["a"][0], Value::Number(Number::PositiveInteger(1)));
+        assert_eq!(
+            value["a"][1]["b"],
+            Value::Number(Number::NegativeInteger(-2))
+        );
+    }
+}
@@ -1,66 +1,503 @@
+//! Json numbers
+//!
+//! # Negative zero
+//!
+//! The integer `-0` is zero, so it is parsed as `PositiveInteger(0)` like
+//! `0`. `-0.0` and `-0e0` are floats and are parsed as `Float(-0.0)`, which
+//! keeps its sign when serialized, but equals, hashes and orders like `0`.
+//! A `NegativeInteger(0)` made by hand equals `PositiveInteger(0)`.
+//!
+//! ```rust
+//! use wson::number::Number;
+//! use wson::{parse, to_string, Value};
+//! # use std::error;
+//! # fn main() -> Result<(), Box<dyn error::Error>> {
+//!
+//!
+//! assert_eq!(parse("-0")?, Value::Number(Number::PositiveInteger(0)));
+//! assert_eq!(parse("-0e0")?, Value::Number(Number::Float(-0.0)));
+//! assert_eq!(parse("-0.0")?, parse("0.0")?);
+//! assert_eq!(to_string(&parse("-0.0")?)?, "-0.0");
+//!
+//! # Ok(())
+//! # }
+//! ```
+
+use std::cmp::Ordering;
 use std::fmt;
+use std::str::FromStr;
 
 use nom::branch::alt;
-use nom::character::complete::{char, one_of};
-use nom::combinator::{map, opt, recognize, value};
+use nom::character::complete::{char, digit0, digit1, one_of};
+use nom::combinator::{all_consuming, map, opt, recognize, value};
+use nom::error::{Error, ErrorKind};
+use nom::multi::many0_count;
 use nom::sequence::{pair, preceded, tuple};
 use nom::IResult;
 
-#[derive(Debug, Clone, PartialEq)]
+use crate::error::{ParseError, ParseErrorKind};
+use crate::ord::numeric_cmp;
+use crate::ParseOptions;
+
+/// A json number
+///
+/// Numbers compare by their value whatever their variant, so
+/// `PositiveInteger(3) == Float(3.0)` and
+/// `PositiveInteger(3) == PositiveInteger128(3)`. An integer equals a float
+/// only when the float is exactly that integer, so
+/// `PositiveInteger(u64::MAX) != Float(u64::MAX as f64)`. Floats compare
+/// with `==`, except that NaN equals NaN so that `Number` can be `Eq`.
+/// `0.0` and `-0.0` are equal.
+#[derive(Debug, Clone)]
 pub enum Number {
     PositiveInteger(u64),
     NegativeInteger(i64),
     Float(f64),
+    /// Integer above `u64::MAX`. Parsing makes it only for such integers.
+    PositiveInteger128(u128),
+    /// Integer below `i64::MIN`. Parsing makes it only for such integers.
+    NegativeInteger128(i128),
+    /// A json number literal kept as written, so that no precision is lost.
+    /// Made by `Number::arbitrary` and `ParseOptions::arbitrary_precision`.
+    /// Two of them are equal when they are written the same, and one never
+    /// equals another variant.
+    #[cfg(feature = "arbitrary_precision")]
+    Arbitrary(String),
+    /// A number with the text it was written as, from
+    /// `ParseOptions::raw_numbers`. It is serialized as that text, and
+    /// compares and converts as the number it stands for.
+    Raw(RawNumber),
 }
 
-#[derive(Debug)]
-struct Num {
-    integer: Integer,
-    fraction: Option<String>,
-    exponent: Option<Exponent>,
+/// A json number literal as it was written, like `1.50` or `-0`
+///
+/// ```rust
+/// use wson::number::{Number, RawNumber};
+/// use wson::{to_string, Value};
+/// # use std::error;
+/// # fn main() -> Result<(), Box<dyn error::Error>> {
+///
+///
+/// let raw = RawNumber::new("1.50").unwrap();
+/// assert_eq!(raw.number(), Number::Float(1.5));
+/// assert_eq!(to_string(&Value::Number(Number::Raw(raw)))?, "1.50");
+/// assert!(RawNumber::new("1.5.0").is_none());
+///
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct RawNumber(String);
+
+impl RawNumber {
+    /// Keep `literal` as it is written, or `None` if it isn't a json number
+    pub fn new(literal: &str) -> Option<RawNumber> {
+        all_consuming(number_literal)(literal)
+            .ok()
+            .map(|_| RawNumber(literal.to_string()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The number the literal stands for, as `parse` reads it, except that
+    /// a number too large for `f64` is infinity
+    pub fn number(&self) -> Number {
+        number_with(&self.0, &ParseOptions::new().infinite_floats(true))
+            .map_or(Number::Float(f64::NAN), |(_, number)| number)
+    }
+}
+
+impl PartialEq for Number {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            #[cfg(feature = "arbitrary_precision")]
+            (Number::Arbitrary(a), Number::Arbitrary(b)) => a == b,
+            #[cfg(feature = "arbitrary_precision")]
+            (Number::Arbitrary(_), _) | (_, Number::Arbitrary(_)) => false,
+            _ => numeric_cmp(self, other) == Ordering::Equal,
+        }
+    }
 }
 
-impl Into<Number> for Num {
-    fn into(self) -> Number {
-        match (self.integer, self.fraction, self.exponent) {
-            (Integer::Positive(str), None, None) => {
-                Number::PositiveInteger(str.parse::<u64>().unwrap())
+impl Eq for Number {}
+
+impl Number {
+    /// Float which json can represent, or `None` for NaN and infinities
+    ///
+    /// ```rust
+    /// use wson::number::Number;
+    /// # fn main() {
+    ///
+    ///
+    /// assert_eq!(Number::from_f64(1.5), Some(Number::Float(1.5)));
+    /// assert_eq!(Number::from_f64(f64::NAN), None);
+    /// # }
+    /// ```
+    pub fn from_f64(f: f64) -> Option<Number> {
+        f.is_finite().then_some(Number::Float(f))
+    }
+
+    /// Number kept as `literal` is written, or `None` if `literal` isn't a
+    /// json number
+    ///
+    /// ```rust
+    /// use wson::number::Number;
+    /// use wson::{to_string, Value};
+    /// # use std::error;
+    /// # fn main() -> Result<(), Box<dyn error::Error>> {
+    ///
+    ///
+    /// let number = Number::arbitrary("0.10000000000000000000000001").unwrap();
+    /// assert_eq!(to_string(&Value::Number(number))?, "0.10000000000000000000000001");
+    /// assert_eq!(Number::arbitrary("1."), None);
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "arbitrary_precision")]
+    pub fn arbitrary(literal: &str) -> Option<Number> {
+        all_consuming(number_literal)(literal)
+            .ok()
+            .map(|_| Number::Arbitrary(literal.to_string()))
+    }
+
+    /// Value as `i64` if it fits without loss
+    ///
+    /// A float converts only when it is integral and within the `i64` range,
+    /// and an arbitrary number only when it is written as an integer.
+    pub fn as_i64(&self) -> Option<i64> {
+        self.as_i128().and_then(|n| i64::try_from(n).ok())
+    }
+
+    /// Value as `u64` if it fits without loss
+    ///
+    /// Negative numbers and non-integral floats return `None`.
+    pub fn as_u64(&self) -> Option<u64> {
+        self.as_u128().and_then(|n| u64::try_from(n).ok())
+    }
+
+    /// Value as `i128` if it fits without loss
+    ///
+    /// Like `as_i64`, within the `i128` range.
+    pub fn as_i128(&self) -> Option<i128> {
+        match *self {
+            Number::PositiveInteger(n) => Some(i128::from(n)),
+            Number::NegativeInteger(n) => Some(i128::from(n)),
+            Number::PositiveInteger128(n) => i128::try_from(n).ok(),
+            Number::NegativeInteger128(n) => Some(n),
+            Number::Float(f) => {
+                // -2^127 and 2^127 are exactly representable as f64
+                if f.fract() == 0.0 && (-1.7014118346046923e38..1.7014118346046923e38).contains(&f)
+                {
+                    Some(f as i128)
+                } else {
+                    None
+                }
             }
-            (Integer::Negative(str), None, None) => {
-                Number::NegativeInteger(str.parse::<i64>().unwrap())
+            #[cfg(feature = "arbitrary_precision")]
+            Number::Arbitrary(ref literal) => literal.parse().ok(),
+            Number::Raw(ref raw) => raw.number().as_i128(),
+        }
+    }
+
+    /// Value as `u128` if it fits without loss
+    ///
+    /// Like `as_u64`, within the `u128` range.
+    pub fn as_u128(&self) -> Option<u128> {
+        match *self {
+            Number::PositiveInteger(n) => Some(u128::from(n)),
+            Number::NegativeInteger(n) => u128::try_from(n).ok(),
+            Number::PositiveInteger128(n) => Some(n),
+            Number::NegativeInteger128(n) => u128::try_from(n).ok(),
+            Number::Float(f) => {
+                // 2^128 is exactly representable as f64
+                if f.fract() == 0.0 && (0.0..3.402823669209385e38).contains(&f) {
+                    Some(f as u128)
+                } else {
+                    None
+                }
             }
-            (int, Some(decimal), None) => {
-                Number::Float(format!("{}.{}", int, decimal).parse::<f64>().unwrap())
+            #[cfg(feature = "arbitrary_precision")]
+            Number::Arbitrary(ref literal) => literal.parse().ok(),
+            Number::Raw(ref raw) => raw.number().as_u128(),
+        }
+    }
+
+    /// Value as `usize` if it fits without loss
+    ///
+    /// Like `as_u64`, and also `None` above `usize::MAX`.
+    pub fn as_usize(&self) -> Option<usize> {
+        self.as_u64().and_then(|n| usize::try_from(n).ok())
+    }
+
+    /// Value as `f64`
+    ///
+    /// Integers above 2^53 in magnitude are rounded to the nearest `f64`,
+    /// as are arbitrary numbers.
+    pub fn as_f64(&self) -> f64 {
+        match *self {
+            Number::PositiveInteger(n) => n as f64,
+            Number::NegativeInteger(n) => n as f64,
+            Number::PositiveInteger128(n) => n as f64,
+            Number::NegativeInteger128(n) => n as f64,
+            Number::Float(f) => f,
+            #[cfg(feature = "arbitrary_precision")]
+            Number::Arbitrary(ref literal) => parse_float(literal),
+            Number::Raw(ref raw) => raw.number().as_f64(),
+        }
+    }
+
+    /// Whether the value is a whole number, whatever the variant
+    ///
+    /// Integral floats like `3.0` are integers, as they equal `3`. NaN and
+    /// infinities are not.
+    ///
+    /// ```rust
+    /// use wson::number::Number;
+    /// # fn main() {
+    ///
+    ///
+    /// assert!(Number::Float(3.0).is_integer());
+    /// assert!(Number::Float(3.0).is_float());
+    /// assert!(!Number::Float(3.5).is_integer());
+    /// assert!(Number::NegativeInteger(-3).is_negative());
+    /// assert!(Number::Float(-1.0).fits_i64() && !Number::Float(-1.0).fits_u64());
+    /// # }
+    /// ```
+    pub fn is_integer(&self) -> bool {
+        match *self {
+            Number::Float(f) => f.is_finite() && f.fract() == 0.0,
+            #[cfg(feature = "arbitrary_precision")]
+            Number::Arbitrary(_) => {
+                self.as_i128().is_some() || self.as_u128().is_some() || {
+                    let f = self.as_f64();
+                    f.is_finite() && f.fract() == 0.0
+                }
             }
-            (int, None, Some(exponent)) => Number::Float(
-                format!("{}E{}", int, exponent.to_string())
-                    .parse::<f64>()
-                    .unwrap(),
-            ),
-            (int, Some(decimal), Some(exponent)) => Number::Float(
-                format!("{}.{}E{}", int, decimal, exponent.to_string())
-                    .parse::<f64>()
-                    .unwrap(),
+            Number::Raw(ref raw) => raw.number().is_integer(),
+            _ => true,
+        }
+    }
+
+    /// Whether the number is a float, written with a fraction or an exponent
+    ///
+    /// This is by variant, so `Float(3.0)` is a float and an integer.
+    pub fn is_float(&self) -> bool {
+        match *self {
+            Number::Float(_) => true,
+            #[cfg(feature = "arbitrary_precision")]
+            Number::Arbitrary(ref literal) => literal.contains(['.', 'e', 'E']),
+            Number::Raw(ref raw) => raw.number().is_float(),
+            _ => false,
+        }
+    }
+
+    /// Whether the value is below zero
+    ///
+    /// Zero is not negative, even as `-0.0`, and neither is NaN.
+    pub fn is_negative(&self) -> bool {
+        match *self {
+            Number::PositiveInteger(_) | Number::PositiveInteger128(_) => false,
+            Number::NegativeInteger(n) => n < 0,
+            Number::NegativeInteger128(n) => n < 0,
+            Number::Float(f) => f < 0.0,
+            #[cfg(feature = "arbitrary_precision")]
+            Number::Arbitrary(_) => self.as_f64() < 0.0,
+            Number::Raw(ref raw) => raw.number().is_negative(),
+        }
+    }
+
+    /// Whether `as_u64` converts the number
+    pub fn fits_u64(&self) -> bool {
+        self.as_u64().is_some()
+    }
+
+    /// Whether `as_i64` converts the number
+    pub fn fits_i64(&self) -> bool {
+        self.as_i64().is_some()
+    }
+
+    /// Whether `as_f64` converts the number without rounding
+    ///
+    /// Every float does, and integers do up to 2^53 in magnitude. Larger
+    /// integers do only when they are a multiple of a large enough power of
+    /// two, like 2^60. Arbitrary numbers which aren't integers are assumed
+    /// not to, as they may have more digits than a float holds.
+    pub fn fits_f64_exactly(&self) -> bool {
+        match *self {
+            Number::Float(_) => true,
+            #[cfg(feature = "arbitrary_precision")]
+            Number::Arbitrary(_) => match (self.as_u128(), self.as_i128()) {
+                (Some(n), _) => Number::PositiveInteger128(n).fits_f64_exactly(),
+                (_, Some(n)) => Number::NegativeInteger128(n).fits_f64_exactly(),
+                _ => false,
+            },
+            Number::Raw(ref raw) => raw.number().fits_f64_exactly(),
+            _ => numeric_cmp(self, &Number::Float(self.as_f64())) == Ordering::Equal,
+        }
+    }
+}
+
+/// Parse a json number, with nothing around it
+///
+/// ```rust
+/// use wson::number::Number;
+/// # use std::error;
+/// # fn main() -> Result<(), Box<dyn error::Error>> {
+///
+///
+/// assert_eq!("-12".parse::<Number>()?, Number::NegativeInteger(-12));
+/// assert_eq!("2.5e1".parse::<Number>()?, Number::Float(25.0));
+/// assert!(" 1".parse::<Number>().is_err());
+/// assert!("1x".parse::<Number>().is_err());
+///
+/// # Ok(())
+/// # }
+/// ```
+impl FromStr for Number {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match all_consuming(number)(input) {
+            Ok((_, number)) => Ok(number),
+            Err(nom::Err::Error(error) | nom::Err::Failure(error))
+                if error.code == ErrorKind::Eof =>
+            {
+                Err(ParseError::new(
+                    ParseErrorKind::TrailingCharacters,
+                    input,
+                    error.input,
+                ))
+            }
+            Err(nom::Err::Failure(error)) if error.code == ErrorKind::TooLarge => Err(
+                ParseError::new(ParseErrorKind::NumberOutOfRange, input, error.input),
             ),
+            Err(_) => Err(ParseError::new(
+                ParseErrorKind::Expected("number"),
+                input,
+                input,
+            )),
         }
     }
 }
 
-#[derive(Debug, PartialEq)]
-enum Integer {
-    Positive(String),
-    Negative(String),
+#[derive(Debug)]
+struct Num<'a> {
+    integer: Integer<'a>,
+    fraction: Option<&'a str>,
+    exponent: Option<Exponent<'a>>,
+    /// The whole number as written
+    literal: &'a str,
 }
 
-impl fmt::Display for Integer {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Self::Positive(str) => write!(f, "{}", str.to_string()),
-            Self::Negative(str) => write!(f, "{}", str.to_string()),
+impl Num<'_> {
+    /// Convert into a `Number`, or `None` for a float which is infinite
+    /// and an integer which doesn't fit when `options` say so
+    fn into_number(self, options: &ParseOptions) -> Option<Number> {
+        if options.exponent_integers {
+            if let Some(number) = self.exponent_integer() {
+                return Some(number);
+            }
+        }
+        let float = || {
+            let f = parse_float(self.literal);
+            (f.is_finite() || options.infinite_floats).then_some(Number::Float(f))
+        };
+        let is_integer = self.fraction.is_none() && self.exponent.is_none();
+        let number = match self.integer {
+            // the wider integer is tried only when the narrower overflows
+            Integer::Positive(str) if is_integer => match str.parse::<u64>() {
+                Ok(n) => Number::PositiveInteger(n),
+                Err(_) => match str.parse::<u128>() {
+                    Ok(n) => Number::PositiveInteger128(n),
+                    Err(_) if options.strict_integers => return None,
+                    Err(_) => float()?,
+                },
+            },
+            Integer::Negative(str) if is_integer => match str.parse::<i64>() {
+                Ok(0) => Number::PositiveInteger(0),
+                Ok(n) => Number::NegativeInteger(n),
+                Err(_) => match str.parse::<i128>() {
+                    Ok(n) => Number::NegativeInteger128(n),
+                    Err(_) if options.strict_integers => return None,
+                    Err(_) => float()?,
+                },
+            },
+            _ => float()?,
+        };
+        Some(number)
+    }
+}
+
+/// Nearest float to a json number `literal`
+///
+/// With the `fast_float` feature a faster parser is used, which rounds the
+/// same as `str::parse`.
+#[cfg(not(feature = "fast_float"))]
+fn parse_float(literal: &str) -> f64 {
+    literal.parse().unwrap_or(f64::NAN)
+}
+
+#[cfg(feature = "fast_float")]
+fn parse_float(literal: &str) -> f64 {
+    fast_float2::parse(literal).unwrap_or(f64::NAN)
+}
+
+impl Num<'_> {
+    /// Integer with the value of a number with an exponent, if the value is
+    /// integral and fits `u64` or `i64`
+    ///
+    /// The digits are shifted as text, so that no precision is lost.
+    fn exponent_integer(&self) -> Option<Number> {
+        let exponent = self.exponent.as_ref()?;
+        let exponent = exponent.to_string().parse::<i64>().ok()?;
+        let fraction = self.fraction.unwrap_or("");
+        let (negative, int) = match self.integer {
+            Integer::Positive(str) => (false, str),
+            Integer::Negative(str) => (true, &str[1..]),
+        };
+        let digits = format!("{}{}", int, fraction);
+        let scale = exponent.checked_sub(fraction.len() as i64)?;
+        let digits = if scale < 0 {
+            let split = digits
+                .len()
+                .saturating_sub(usize::try_from(scale.unsigned_abs()).ok()?);
+            let (digits, dropped) = digits.split_at(split);
+            if !dropped.bytes().all(|b| b == b'0') {
+                return None;
+            }
+            digits.to_string()
+        } else {
+            let significant = digits.trim_start_matches('0');
+            if significant.is_empty() {
+                String::new()
+            } else if scale > 20 - significant.len() as i64 {
+                // more digits than u64::MAX has
+                return None;
+            } else {
+                format!("{}{}", significant, "0".repeat(scale as usize))
+            }
+        };
+        let digits = if digits.is_empty() { "0" } else { &digits };
+        if negative {
+            match format!("-{}", digits).parse::<i64>().ok()? {
+                0 => Some(Number::PositiveInteger(0)),
+                n => Some(Number::NegativeInteger(n)),
+            }
+        } else {
+            digits.parse::<u64>().ok().map(Number::PositiveInteger)
         }
     }
 }
 
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Integer<'a> {
+    Positive(&'a str),
+    Negative(&'a str),
+}
+
 /// Recognize number
 ///
 /// ```rust
@@ -100,16 +537,149 @@ impl fmt::Display for Integer {
 /// ```
 // number = integer fraction
 pub fn number(input: &str) -> IResult<&str, Number> {
+    number_with(input, &ParseOptions::new())
+}
+
+/// Recognize number as `options` say
+///
+/// An integer which doesn't fit `u128` or `i128` is parsed as a float, or
+/// fails with `ErrorKind::TooLarge` at the number when integers are strict.
+/// A float too large for `f64` fails the same, unless infinite floats are
+/// allowed.
+/// With raw numbers, every number is kept with its text. With arbitrary
+/// precision, every number but integers which fit is kept as written.
+/// A number written leniently is made strict json before any of these.
+pub(crate) fn number_with<'a>(input: &'a str, options: &ParseOptions) -> IResult<&'a str, Number> {
+    if options.is_lenient() {
+        let (rest, literal) = lenient_number_literal(input, options)?;
+        if let Some(literal) = strict_literal(literal) {
+            let options = options.strict();
+            return match number_with(&literal, &options) {
+                Ok((_, number)) => Ok((rest, number)),
+                Err(_) => Err(nom::Err::Failure(Error::new(input, ErrorKind::TooLarge))),
+            };
+        }
+    }
+
     let (rest, integer) = integer(input)?;
     let (rest, fraction) = fraction(rest)?;
     let (rest, exponent) = exponent(rest)?;
+    let literal = &input[..input.len() - rest.len()];
     let num = Num {
         integer,
         fraction,
         exponent,
+        literal,
+    };
+
+    if options.raw_numbers {
+        return match num.into_number(options) {
+            Some(_) => Ok((rest, Number::Raw(RawNumber(literal.to_string())))),
+            None => Err(nom::Err::Failure(Error::new(input, ErrorKind::TooLarge))),
+        };
+    }
+
+    #[cfg(feature = "arbitrary_precision")]
+    if options.arbitrary_precision {
+        let number = match num.into_number(&options.clone().strict_integers(true)) {
+            Some(
+                number @ (Number::PositiveInteger(_)
+                | Number::NegativeInteger(_)
+                | Number::PositiveInteger128(_)
+                | Number::NegativeInteger128(_)),
+            ) => number,
+            _ => Number::Arbitrary(literal.to_string()),
+        };
+        return Ok((rest, number));
+    }
+
+    match num.into_number(options) {
+        Some(number) => Ok((rest, number)),
+        None => Err(nom::Err::Failure(Error::new(input, ErrorKind::TooLarge))),
+    }
+}
+
+/// Recognize number and return it as written, without converting it
+pub(crate) fn number_literal(input: &str) -> IResult<&str, &str> {
+    recognize(tuple((integer, fraction, exponent)))(input)
+}
+
+/// Recognize number with the lenient syntax `options` allow, as written
+pub(crate) fn lenient_number_literal<'a>(
+    input: &'a str,
+    options: &ParseOptions,
+) -> IResult<&'a str, &'a str> {
+    let lenient_digits = |input| {
+        if options.allow_numeric_separators {
+            separated_digits(input)
+        } else {
+            digit1(input)
+        }
     };
+    let lenient_unsigned = |input| {
+        if options.allow_numeric_separators {
+            alt((
+                recognize(char('0')),
+                recognize(pair(
+                    one_of("123456789"),
+                    many0_count(pair(opt(char('_')), digit1)),
+                )),
+            ))(input)
+        } else {
+            unsigned(input)
+        }
+    };
+    let sign = if options.allow_plus_sign { "+-" } else { "-" };
+    let (rest, _) = opt(one_of(sign))(input)?;
+    let (rest, _) = if options.allow_lax_decimal {
+        // digits on at least one side of the point
+        alt((
+            recognize(pair(
+                lenient_unsigned,
+                opt(pair(char('.'), opt(lenient_digits))),
+            )),
+            recognize(pair(char('.'), lenient_digits)),
+        ))(rest)?
+    } else {
+        recognize(pair(lenient_unsigned, opt(pair(char('.'), lenient_digits))))(rest)?
+    };
+    let (rest, _) = opt(tuple((one_of("eE"), opt(one_of("+-")), lenient_digits)))(rest)?;
+    Ok((rest, &input[..input.len() - rest.len()]))
+}
+
+/// Json text of a number written leniently, or `None` if it is json
+/// already
+///
+/// Separators and a leading `+` are removed, and a point without digits
+/// on one side gets a `0` there.
+fn strict_literal(literal: &str) -> Option<String> {
+    let mut strict = String::with_capacity(literal.len() + 2);
+    let unsigned = match literal.strip_prefix(['+', '-']) {
+        Some(unsigned) => {
+            if literal.starts_with('-') {
+                strict.push('-');
+            }
+            unsigned
+        }
+        None => literal,
+    };
+    if unsigned.starts_with('.') {
+        strict.push('0');
+    }
+    let mut chars = unsigned.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '_' => {}
+            '.' if !chars.peek().is_some_and(char::is_ascii_digit) => strict.push_str(".0"),
+            c => strict.push(c),
+        }
+    }
+    (strict != literal).then_some(strict)
+}
 
-    Ok((rest, num.into()))
+/// Recognize digits with single `_` between them
+fn separated_digits(input: &str) -> IResult<&str, &str> {
+    recognize(pair(digit1, many0_count(pair(char('_'), digit1))))(input)
 }
 
 /// Recognize integer
@@ -117,79 +687,45 @@ pub fn number(input: &str) -> IResult<&str, Number> {
 ///         | onenine digits
 ///         | '-' digit
 ///         | '-' onenine digits
-fn integer(input: &str) -> IResult<&str, Integer> {
+fn integer(input: &str) -> IResult<&str, Integer<'_>> {
     alt((
-        map(
-            alt((
-                recognize(tuple((char('-'), onenine, digits))),
-                recognize(pair(char('-'), digit)),
-            )),
-            |str| Integer::Negative(str.to_string()),
-        ),
-        map(
-            alt((
-                map(recognize(pair(onenine, digits)), |str| str.to_string()),
-                digit,
-            )),
-            |str| Integer::Positive(str.to_string()),
-        ),
+        map(recognize(pair(char('-'), unsigned)), Integer::Negative),
+        map(unsigned, Integer::Positive),
     ))(input)
 }
 
-/// Recognize digits
-/// digits = digit
-///        | digit digits
-fn digits(input: &str) -> IResult<&str, String> {
+/// Recognize integer without a sign, which has no leading zeros
+fn unsigned(input: &str) -> IResult<&str, &str> {
     alt((
-        map(recognize(pair(digit, digits)), |str| str.to_string()),
-        digit,
+        recognize(char('0')),
+        recognize(pair(one_of("123456789"), digit0)),
     ))(input)
-    // alt((recognize(pair(digit, digits)), digit))(input)
-}
-
-/// Recognize a digit
-/// digit = zero
-///       | onenine
-pub fn digit(input: &str) -> IResult<&str, String> {
-    alt((zero, onenine))(input)
-}
-
-/// Recognize '1' ... '9'
-/// onenine = 1...9
-fn onenine(input: &str) -> IResult<&str, String> {
-    map(one_of("123456789"), |c| c.to_string())(input)
-}
-
-/// Recognize "0"
-/// zero = 0
-fn zero(input: &str) -> IResult<&str, String> {
-    map(char('0'), |c| c.to_string())(input)
 }
 
 /// graction = ""
 ///          | "." digits
-fn fraction(input: &str) -> IResult<&str, Option<String>> {
-    opt(preceded(char('.'), digits))(input)
+fn fraction(input: &str) -> IResult<&str, Option<&str>> {
+    opt(preceded(char('.'), digit1))(input)
 }
 
 #[derive(Debug, PartialEq)]
-struct Exponent {
+struct Exponent<'a> {
     sign: Sign,
-    digits: String,
+    digits: &'a str,
 }
 
-impl ToString for Exponent {
-    fn to_string(&self) -> String {
-        format!("{}{}", self.sign.to_string(), self.digits.to_string())
+impl fmt::Display for Exponent<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.sign, self.digits)
     }
 }
 
 /// exponent = ""
 ///          | 'E' sign digits
 ///          | 'e' sign digits
-fn exponent(input: &str) -> IResult<&str, Option<Exponent>> {
+fn exponent(input: &str) -> IResult<&str, Option<Exponent<'_>>> {
     opt(map(
-        tuple((alt((char('E'), char('e'))), sign, digits)),
+        tuple((alt((char('E'), char('e'))), sign, digit1)),
         |(_, s, d)| Exponent { sign: s, digits: d },
     ))(input)
 }
@@ -200,11 +736,11 @@ enum Sign {
     Minus,
 }
 
-impl ToString for Sign {
-    fn to_string(&self) -> String {
+impl fmt::Display for Sign {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Sign::Plus => "+".to_string(),
-            Sign::Minus => "-".to_string(),
+            Sign::Plus => write!(f, "+"),
+            Sign::Minus => write!(f, "-"),
         }
     }
 }
@@ -221,83 +757,518 @@ fn sign(input: &str) -> IResult<&str, Sign> {
 
 #[cfg(test)]
 mod tests {
-    use nom::error::{Error, ErrorKind};
+    use nom::error::Error;
     use nom::Err;
 
     use super::*;
 
     #[test]
-    fn assert_zero() {
-        assert_eq!(zero("0"), Ok(("", "0".to_string())));
+    fn integer_conversions() {
+        let max = Number::PositiveInteger(u64::MAX);
+        assert_eq!(max.as_u64(), Some(u64::MAX));
+        assert_eq!(max.as_i64(), None);
+        assert_eq!(max.as_f64(), 18446744073709551616.0);
+
+        let i64_max = Number::PositiveInteger(i64::MAX as u64);
+        assert_eq!(i64_max.as_i64(), Some(i64::MAX));
+        assert_eq!(Number::PositiveInteger(i64::MAX as u64 + 1).as_i64(), None);
+
+        let min = Number::NegativeInteger(i64::MIN);
+        assert_eq!(min.as_i64(), Some(i64::MIN));
+        assert_eq!(min.as_u64(), None);
+        assert_eq!(min.as_f64(), -9223372036854775808.0);
+
+        assert_eq!(Number::NegativeInteger(0).as_u64(), Some(0));
+    }
+
+    #[test]
+    fn float_conversions() {
+        assert_eq!(Number::Float(3.0).as_u64(), Some(3));
+        assert_eq!(Number::Float(-3.0).as_i64(), Some(-3));
+        assert_eq!(Number::Float(-3.0).as_u64(), None);
+        assert_eq!(Number::Float(2.5).as_i64(), None);
+        assert_eq!(Number::Float(2.5).as_u64(), None);
+        assert_eq!(Number::Float(2.5).as_f64(), 2.5);
+
+        assert_eq!(
+            Number::Float(-9223372036854775808.0).as_i64(),
+            Some(i64::MIN)
+        );
+        assert_eq!(Number::Float(9223372036854775808.0).as_i64(), None);
+        assert_eq!(Number::Float(18446744073709551616.0).as_u64(), None);
+        assert_eq!(Number::Float(f64::NAN).as_i64(), None);
+        assert_eq!(Number::Float(f64::INFINITY).as_u64(), None);
+    }
+
+    #[test]
+    fn conversion_boundaries() {
+        let two_53 = 9007199254740992;
+        assert_eq!(Number::PositiveInteger(two_53).as_f64(), 9007199254740992.0);
+        // 2^53 + 1 has no f64, so it rounds to 2^53
+        assert_eq!(
+            Number::PositiveInteger(two_53 + 1).as_f64(),
+            9007199254740992.0
+        );
+        assert_eq!(Number::Float(9007199254740992.0).as_u64(), Some(two_53));
+        assert_eq!(
+            Number::NegativeInteger(-(two_53 as i64)).as_f64(),
+            -9007199254740992.0
+        );
+
+        assert_eq!(Number::Float(-0.0).as_u64(), Some(0));
+        assert_eq!(Number::Float(-0.0).as_i64(), Some(0));
+        assert_eq!(Number::Float(-0.0).as_usize(), Some(0));
+        assert!(Number::Float(-0.0).as_f64().is_sign_negative());
+
+        assert_eq!(Number::PositiveInteger(7).as_usize(), Some(7));
+        assert_eq!(Number::NegativeInteger(-1).as_usize(), None);
+        assert_eq!(Number::Float(7.5).as_usize(), None);
+        assert_eq!(
+            Number::PositiveInteger(u64::MAX).as_usize(),
+            usize::try_from(u64::MAX).ok()
+        );
+    }
+
+    #[test]
+    fn integers_128() {
+        let u64_max_plus_one = Number::PositiveInteger128(1 << 64);
+        assert_eq!(u64_max_plus_one.as_u64(), None);
+        assert_eq!(u64_max_plus_one.as_u128(), Some(1 << 64));
+        assert_eq!(u64_max_plus_one.as_i128(), Some(1 << 64));
+        assert_eq!(u64_max_plus_one.as_f64(), 18446744073709551616.0);
+
+        let max = Number::PositiveInteger128(u128::MAX);
+        assert_eq!(max.as_u128(), Some(u128::MAX));
+        assert_eq!(max.as_i128(), None);
+        let min = Number::NegativeInteger128(i128::MIN);
+        assert_eq!(min.as_i128(), Some(i128::MIN));
+        assert_eq!(min.as_u128(), None);
+        assert_eq!(min.as_i64(), None);
+
+        // in range values work whichever variant holds them
+        assert_eq!(Number::PositiveInteger128(7).as_u64(), Some(7));
+        assert_eq!(Number::NegativeInteger128(-7).as_i64(), Some(-7));
+        assert_eq!(Number::PositiveInteger(7).as_u128(), Some(7));
+        assert_eq!(Number::NegativeInteger(-7).as_i128(), Some(-7));
+        assert_eq!(
+            Number::Float(1.7014118346046923e38).as_u128(),
+            Some(1 << 127)
+        );
+        assert_eq!(Number::Float(1.7014118346046923e38).as_i128(), None);
+        assert_eq!(
+            Number::Float(-1.7014118346046923e38).as_i128(),
+            Some(i128::MIN)
+        );
+        assert_eq!(Number::Float(3.402823669209385e38).as_u128(), None);
+    }
+
+    #[test]
+    fn integers_128_compare_by_value() {
+        assert_eq!(Number::PositiveInteger128(7), Number::PositiveInteger(7));
+        assert_eq!(Number::NegativeInteger(-7), Number::NegativeInteger128(-7));
+        assert_eq!(Number::PositiveInteger128(7), Number::Float(7.0));
+        assert_ne!(
+            Number::PositiveInteger128(1 << 64),
+            Number::Float(1.8446744073709552e19 + 4096.0)
+        );
+        assert!(Number::PositiveInteger128(1 << 64) > Number::PositiveInteger(u64::MAX));
+        assert!(Number::NegativeInteger128(i128::MIN) < Number::NegativeInteger(i64::MIN));
+        assert!(Number::PositiveInteger128(1 << 64) < Number::Float(1e20));
+        assert!(Number::PositiveInteger128(u128::MAX) < Number::Float(f64::INFINITY));
+        assert!(Number::NegativeInteger128(i128::MIN) > Number::Float(-1e39));
+        assert_eq!(
+            Number::PositiveInteger128(7).cmp(&Number::PositiveInteger(7)),
+            std::cmp::Ordering::Equal
+        );
+
+        use std::collections::HashSet;
+        let set: HashSet<_> = [Number::PositiveInteger(7), Number::PositiveInteger128(7)].into();
+        assert_eq!(set.len(), 1);
     }
 
     #[test]
-    fn failed_parse_one() {
-        assert_eq!(zero("1"), Err(Err::Error(Error::new("1", ErrorKind::Char))))
+    fn equal_across_variants() {
+        use std::collections::HashSet;
+
+        assert_eq!(Number::PositiveInteger(3), Number::Float(3.0));
+        assert_eq!(Number::Float(-1.0), Number::NegativeInteger(-1));
+        assert_ne!(Number::PositiveInteger(3), Number::Float(3.5));
+        assert_ne!(
+            Number::PositiveInteger(u64::MAX),
+            Number::Float(u64::MAX as f64)
+        );
+        assert_ne!(
+            Number::NegativeInteger(i64::MIN + 1),
+            Number::Float(i64::MIN as f64)
+        );
+        assert_eq!(
+            Number::NegativeInteger(i64::MIN),
+            Number::Float(i64::MIN as f64)
+        );
+        assert_eq!(Number::Float(f64::NAN), Number::Float(-f64::NAN));
+        assert_ne!(Number::Float(f64::NAN), Number::PositiveInteger(0));
+        assert_ne!(
+            Number::Float(f64::INFINITY),
+            Number::PositiveInteger128(u128::MAX)
+        );
+
+        let set: HashSet<_> = [
+            Number::PositiveInteger(3),
+            Number::Float(3.0),
+            Number::PositiveInteger128(3),
+            Number::NegativeInteger(-1),
+            Number::Float(-1.0),
+            Number::Float(f64::NAN),
+            Number::Float(-f64::NAN),
+            Number::PositiveInteger(u64::MAX),
+            Number::Float(u64::MAX as f64),
+        ]
+        .into();
+        assert_eq!(set.len(), 5);
     }
 
     #[test]
-    fn parse_one() {
-        assert_eq!(onenine("1"), Ok(("", "1".to_string())));
+    fn predicates() {
+        let two_53 = 1u64 << 53;
+        for (number, integer, float, negative, exact) in [
+            (Number::PositiveInteger(two_53), true, false, false, true),
+            (
+                Number::PositiveInteger(two_53 + 1),
+                true,
+                false,
+                false,
+                false,
+            ),
+            (
+                Number::PositiveInteger(two_53 + 2),
+                true,
+                false,
+                false,
+                true,
+            ),
+            (Number::PositiveInteger(u64::MAX), true, false, false, false),
+            (Number::PositiveInteger(1 << 60), true, false, false, true),
+            (Number::NegativeInteger(-(1 << 53)), true, false, true, true),
+            (
+                Number::NegativeInteger(-(1 << 53) - 1),
+                true,
+                false,
+                true,
+                false,
+            ),
+            (Number::NegativeInteger(i64::MIN), true, false, true, true),
+            (
+                Number::PositiveInteger128(u128::MAX),
+                true,
+                false,
+                false,
+                false,
+            ),
+            (
+                Number::NegativeInteger128(i128::MIN),
+                true,
+                false,
+                true,
+                true,
+            ),
+            (Number::Float(9007199254740992.0), true, true, false, true),
+            (Number::Float(0.5), false, true, false, true),
+            (Number::Float(-2.0), true, true, true, true),
+            (Number::Float(f64::INFINITY), false, true, false, true),
+            (Number::Float(f64::NAN), false, true, false, true),
+        ] {
+            assert_eq!(number.is_integer(), integer, "{:?}", number);
+            assert_eq!(number.is_float(), float, "{:?}", number);
+            assert_eq!(number.is_negative(), negative, "{:?}", number);
+            assert_eq!(number.fits_f64_exactly(), exact, "{:?}", number);
+        }
+
+        assert!(Number::PositiveInteger(u64::MAX).fits_u64());
+        assert!(!Number::PositiveInteger(u64::MAX).fits_i64());
+        assert!(Number::NegativeInteger(i64::MIN).fits_i64());
+        assert!(!Number::NegativeInteger(-1).fits_u64());
+        assert!(Number::Float(9007199254740992.0).fits_u64());
+        assert!(!Number::Float(18446744073709551616.0).fits_u64());
+        assert!(!Number::Float(1.5).fits_i64());
+        assert!(!Number::PositiveInteger128(1 << 64).fits_u64());
     }
 
     #[test]
-    fn parse_nine() {
-        assert_eq!(onenine("9"), Ok(("", "9".to_string())));
+    fn predicates_of_zero() -> Result<(), ParseError> {
+        for number in [
+            Number::PositiveInteger(0),
+            Number::NegativeInteger(0),
+            Number::Float(0.0),
+            Number::Float(-0.0),
+            "-0".parse()?,
+            "-0.0".parse()?,
+        ] {
+            assert!(number.is_integer(), "{:?}", number);
+            assert!(!number.is_negative(), "{:?}", number);
+            assert!(number.fits_u64() && number.fits_i64(), "{:?}", number);
+            assert!(number.fits_f64_exactly(), "{:?}", number);
+        }
+        assert!(!"-0".parse::<Number>()?.is_float());
+        assert!("-0.0".parse::<Number>()?.is_float());
+        assert!("-0e0".parse::<Number>()?.is_float());
+        Ok(())
     }
 
     #[test]
-    fn failed_parse_a() {
+    fn integers_128_from_primitives() {
+        assert_eq!(
+            Number::from(u128::from(u64::MAX)),
+            Number::PositiveInteger(u64::MAX)
+        );
+        assert!(matches!(
+            Number::from(1u128 << 64),
+            Number::PositiveInteger128(_)
+        ));
+        assert!(matches!(
+            Number::from(i128::from(i64::MIN)),
+            Number::NegativeInteger(_)
+        ));
+        assert!(matches!(
+            Number::from(i128::from(i64::MIN) - 1),
+            Number::NegativeInteger128(_)
+        ));
+        assert!(matches!(Number::from(5i128), Number::PositiveInteger(5)));
+        assert_eq!(
+            Number::from(u128::MAX).to_string(),
+            "340282366920938463463374607431768211455"
+        );
         assert_eq!(
-            digit("a"),
-            Err(Err::Error(Error::new("a", ErrorKind::OneOf)))
-        )
+            Number::from(i128::MIN).to_string(),
+            "-170141183460469231731687303715884105728"
+        );
+    }
+
+    #[test]
+    fn negative_zero() -> Result<(), Box<dyn std::error::Error>> {
+        use crate::{parse, to_string, Value};
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+
+        let digest = |value: &Value| {
+            let mut hasher = DefaultHasher::new();
+            value.hash(&mut hasher);
+            hasher.finish()
+        };
+        for (json, expected, serialized) in [
+            ("0", Number::PositiveInteger(0), "0"),
+            ("-0", Number::PositiveInteger(0), "0"),
+            ("0.0", Number::Float(0.0), "0.0"),
+            ("-0.0", Number::Float(-0.0), "-0.0"),
+            ("-0e0", Number::Float(-0.0), "-0.0"),
+        ] {
+            let value = parse(json)?;
+            assert_eq!(
+                format!("{:?}", value),
+                format!("{:?}", Value::Number(expected))
+            );
+            assert_eq!(to_string(&value)?, serialized);
+        }
+        assert_eq!(parse("-0")?, parse("0")?);
+        assert_eq!(digest(&parse("-0")?), digest(&parse("0")?));
+        assert_eq!(parse("-0.0")?, parse("0.0")?);
+        assert_eq!(digest(&parse("-0e0")?), digest(&parse("0.0")?));
+        assert_eq!(parse("-0")?, parse("-0.0")?);
+
+        let negative = Value::Number(Number::NegativeInteger(0));
+        assert_eq!(negative, Value::Number(Number::PositiveInteger(0)));
+        assert_eq!(negative, Value::Number(Number::NegativeInteger128(0)));
+        assert_eq!(digest(&negative), digest(&parse("0")?));
+        assert_eq!(negative.cmp(&parse("0")?), std::cmp::Ordering::Equal);
+        assert_eq!(to_string(&negative)?, "0");
+        assert_eq!(Number::NegativeInteger(5), Number::PositiveInteger(5));
+        Ok(())
+    }
+
+    #[test]
+    fn from_f64_rejects_non_finite() {
+        assert_eq!(Number::from_f64(-0.0), Some(Number::Float(-0.0)));
+        assert_eq!(Number::from_f64(f64::MAX), Some(Number::Float(f64::MAX)));
+        assert_eq!(Number::from_f64(f64::INFINITY), None);
+        assert_eq!(Number::from_f64(f64::NEG_INFINITY), None);
+        assert_eq!(Number::from_f64(f64::NAN), None);
     }
 
     #[test]
-    fn digit_zero() {
-        assert_eq!(digit("0"), Ok(("", "0".to_string())));
+    fn from_str_errors() {
+        let error = "".parse::<Number>().unwrap_err();
+        assert_eq!(error.kind(), &ParseErrorKind::Expected("number"));
+        let error = "12.5.1".parse::<Number>().unwrap_err();
+        assert_eq!(error.kind(), &ParseErrorKind::TrailingCharacters);
+        assert_eq!(error.offset(), 4);
+        assert!("+1".parse::<Number>().is_err());
+        assert!("01".parse::<Number>().is_err());
+        assert!("NaN".parse::<Number>().is_err());
+    }
+
+    struct Random(u64);
+
+    impl Random {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn number(&mut self) -> Number {
+            let bits = self.next();
+            match bits % 5 {
+                0 => Number::PositiveInteger(self.next() >> (bits % 64)),
+                1 => Number::NegativeInteger(-((self.next() >> (bits % 63 + 1)) as i64) - 1),
+                2 => Number::PositiveInteger128((u128::from(self.next()) << 64) | u128::from(bits)),
+                3 => Number::NegativeInteger128(
+                    -((u128::from(self.next()) << 63) as i128) - i128::from(bits) - (1 << 63) - 1,
+                ),
+                _ => Number::Float(f64::from_bits(self.next())),
+            }
+        }
     }
 
     #[test]
-    fn digit_one() {
-        assert_eq!(digit("1"), Ok(("", "1".to_string())));
+    fn display_round_trips() -> Result<(), ParseError> {
+        let mut random = Random(0x2545f4914f6cdd1d);
+        for _ in 0..10000 {
+            let number = random.number();
+            if matches!(number, Number::Float(f) if !f.is_finite()) {
+                continue;
+            }
+            assert_eq!(number.to_string().parse::<Number>()?, number, "{}", number);
+        }
+        for number in [
+            Number::PositiveInteger(0),
+            Number::PositiveInteger(u64::MAX),
+            Number::NegativeInteger(i64::MIN),
+            Number::PositiveInteger128(u128::MAX),
+            Number::NegativeInteger128(i128::MIN),
+            Number::Float(0.0),
+            Number::Float(-0.0),
+            Number::Float(1e300),
+            Number::Float(f64::MIN_POSITIVE),
+            Number::Float(5e-324),
+            Number::Float(0.1),
+        ] {
+            assert_eq!(number.to_string().parse::<Number>()?, number, "{}", number);
+        }
+        Ok(())
     }
 
     #[test]
-    fn digit_one_nine() {
-        assert_eq!(digit("19"), Ok(("9", "1".to_string())));
+    fn raw_numbers_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+        use crate::{parse_with, to_string};
+
+        let options = ParseOptions::new().raw_numbers(true);
+        for json in ["1.50", "3.2E-1", "-0", "1e3", "[1.0,-0.0,{\"a\":10E+2}]"] {
+            assert_eq!(to_string(&parse_with(json, &options)?)?, json);
+        }
+        let value = parse_with("[1.50, -1, 1e3, 1e40]", &options)?;
+        assert_eq!(value, crate::json!([1.5, -1, 1000.0, 1e40]));
+        assert_eq!(value[0], 1.5);
+        assert_eq!(value[1].as_i64(), Some(-1));
+        assert!(value[2].is_f64());
+        assert!(!value[1].is_f64());
+
+        let strict = options.strict_integers(true);
+        assert!(parse_with("1000000000000000000000000000000000000000", &strict).is_err());
+        Ok(())
     }
 
     #[test]
-    fn digit_one_alpha() {
-        assert_eq!(digit("1a"), Ok(("a", "1".to_string())));
+    fn raw_numbers_compare_by_value() {
+        let raw = |literal| Number::Raw(RawNumber::new(literal).unwrap());
+        assert_eq!(raw("1.50"), Number::Float(1.5));
+        assert_eq!(Number::Float(1.5), raw("1.50"));
+        assert_eq!(raw("1.50"), raw("15e-1"));
+        assert_eq!(raw("-2"), Number::NegativeInteger(-2));
+        assert_eq!(raw("3"), Number::Float(3.0));
+        assert_ne!(raw("3"), Number::Float(3.5));
+        assert!(raw("2") < raw("10"));
+        assert_eq!(
+            raw("1.50").cmp(&Number::Float(1.5)),
+            std::cmp::Ordering::Equal
+        );
+
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+        let digest = |number: Number| {
+            let mut hasher = DefaultHasher::new();
+            number.hash(&mut hasher);
+            hasher.finish()
+        };
+        assert_eq!(digest(raw("1.50")), digest(Number::Float(1.5)));
+        assert_eq!(raw("1.50").as_f64(), 1.5);
+        assert_eq!(raw("12").as_u64(), Some(12));
+        assert!(RawNumber::new("").is_none());
+        assert!(RawNumber::new(" 1").is_none());
     }
 
+    #[cfg(feature = "arbitrary_precision")]
     #[test]
-    fn digit_alpha() {
+    fn arbitrary_precision_round_trips() -> Result<(), Box<dyn std::error::Error>> {
+        use crate::{parse_with, to_string, Value};
+
+        let options = ParseOptions::new().arbitrary_precision(true);
+        for json in [
+            "1234567890123456789012345678901234567890",
+            "-1234567890123456789012345678901234567890",
+            "0.10000000000000000000000001",
+            "1.50",
+            "1e400",
+            "-0.0",
+        ] {
+            let value = parse_with(json, &options)?;
+            assert_eq!(value, Value::Number(Number::Arbitrary(json.to_string())));
+            assert_eq!(to_string(&value)?, json);
+        }
+        assert_eq!(
+            parse_with("[18446744073709551615, -1]", &options)?,
+            Value::from(vec![Value::from(u64::MAX), Value::from(-1)])
+        );
         assert_eq!(
-            digit("a"),
-            Err(Err::Error(Error::new("a", ErrorKind::OneOf)))
+            parse_with("0.1", &ParseOptions::new())?,
+            Value::Number(Number::Float(0.1))
         );
+        Ok(())
     }
 
+    #[cfg(feature = "arbitrary_precision")]
     #[test]
-    fn digits1() {
-        assert_eq!(digits("123"), Ok(("", "123".to_string())))
+    fn arbitrary_accessors() {
+        let big = Number::arbitrary("1234567890123456789012345678901234567890").unwrap();
+        assert_eq!(big.as_u64(), None);
+        assert_eq!(big.as_f64(), 1.2345678901234568e39);
+        let small = Number::arbitrary("-12").unwrap();
+        assert_eq!(small.as_i64(), Some(-12));
+        assert_eq!(small.as_u64(), None);
+        assert_eq!(Number::arbitrary("2.5").unwrap().as_i64(), None);
+        assert_eq!(Number::arbitrary("2.5").unwrap().as_f64(), 2.5);
+        assert_eq!(Number::arbitrary(""), None);
+        assert_eq!(Number::arbitrary("1 "), None);
+        assert_eq!(Number::arbitrary("+1"), None);
+
+        assert_ne!(Number::arbitrary("1.50"), Number::arbitrary("1.5"));
+        assert!(Number::arbitrary("1.50").unwrap() != Number::Float(1.5));
+        assert!(Number::arbitrary("0.2").unwrap() > Number::Float(0.1));
+        assert!(Number::arbitrary("1.50").unwrap() > Number::arbitrary("1.5").unwrap());
+
+        assert!(big.is_integer() && !big.is_float() && !big.fits_f64_exactly());
+        assert!(small.is_negative() && small.fits_f64_exactly());
+        let float = Number::arbitrary("2.5e0").unwrap();
+        assert!(float.is_float() && !float.is_integer() && !float.fits_f64_exactly());
     }
 
     #[test]
     fn parse_negative_digit() {
-        assert_eq!(integer("-1"), Ok(("", Integer::Negative("-1".to_string()))));
+        assert_eq!(integer("-1"), Ok(("", Integer::Negative("-1"))));
     }
 
     #[test]
     fn parse_negative_digits() {
-        assert_eq!(
-            integer("-123"),
-            Ok(("", Integer::Negative("-123".to_string())))
-        );
+        assert_eq!(integer("-123"), Ok(("", Integer::Negative("-123"))));
     }
 
     #[test]
@@ -307,7 +1278,7 @@ mod tests {
 
     #[test]
     fn rest_fraction() {
-        assert_eq!(fraction(".123"), Ok(("", Some("123".to_string()))))
+        assert_eq!(fraction(".123"), Ok(("", Some("123"))))
     }
 
     #[test]
@@ -338,7 +1309,7 @@ mod tests {
                 "",
                 Some(Exponent {
                     sign: Sign::Plus,
-                    digits: "23".to_string()
+                    digits: "23"
                 })
             ))
         );
@@ -352,7 +1323,7 @@ mod tests {
                 "",
                 Some(Exponent {
                     sign: Sign::Minus,
-                    digits: "23".to_string()
+                    digits: "23"
                 })
             ))
         );
@@ -366,7 +1337,7 @@ mod tests {
                 "",
                 Some(Exponent {
                     sign: Sign::Plus,
-                    digits: "23".to_string()
+                    digits: "23"
                 })
             ))
         );
@@ -380,9 +1351,142 @@ mod tests {
                 "",
                 Some(Exponent {
                     sign: Sign::Minus,
-                    digits: "23".to_string()
+                    digits: "23"
                 })
             ))
         );
     }
+
+    #[test]
+    fn floats_round_like_std() {
+        let mut literals: Vec<String> = [
+            "0.0",
+            "-0.0",
+            "1e-400",
+            "4.9406564584124654e-324",
+            "2.4703282292062327e-324",
+            "2.4703282292062328e-324",
+            "2.2250738585072011e-308",
+            "2.2250738585072014e-308",
+            "1.7976931348623157e308",
+            "1.7976931348623158e308",
+            "1.7976931348623159e308",
+            "1e308",
+            "1e309",
+            "-1e400",
+            "1e99999999999999999999",
+            "1e-99999999999999999999",
+            "0.1",
+            "0.30000000000000004",
+            "9007199254740993",
+            "9007199254740993.0",
+            "123456789012345678901234567890e-10",
+            "0.000000000000000000000000000000000000000000001e45",
+            "7.2057594037927933e16",
+            "2.225073858507201136057409796709131975934819546351645648e-308",
+        ]
+        .iter()
+        .map(|literal| literal.to_string())
+        .collect();
+        literals.push(format!("0.{}", "3".repeat(10_000)));
+        literals.push(format!("1{}e-10000", "0".repeat(10_000)));
+        let mut random = Random(11);
+        for _ in 0..10_000 {
+            let bits = random.next();
+            let f = f64::from_bits(bits);
+            if f.is_finite() {
+                literals.push(format!("{:e}", f));
+                literals.push(format!("{}", f));
+            }
+            literals.push(format!(
+                "{}.{}e{}",
+                bits % 1000,
+                random.next(),
+                bits as i16 % 330
+            ));
+        }
+        for literal in &literals {
+            assert_eq!(
+                parse_float(literal).to_bits(),
+                literal.parse::<f64>().unwrap().to_bits(),
+                "{}",
+                literal
+            );
+        }
+    }
+
+    #[test]
+    fn variant_by_literal() {
+        for (input, expected) in [
+            ("0", Number::PositiveInteger(0)),
+            ("7", Number::PositiveInteger(7)),
+            ("-7", Number::NegativeInteger(-7)),
+            ("18446744073709551615", Number::PositiveInteger(u64::MAX)),
+            ("18446744073709551616", Number::PositiveInteger128(1 << 64)),
+            ("-9223372036854775808", Number::NegativeInteger(i64::MIN)),
+            (
+                "-9223372036854775809",
+                Number::NegativeInteger128(-(1 << 63) - 1),
+            ),
+            (
+                "340282366920938463463374607431768211456",
+                Number::Float(3.402823669209385e38),
+            ),
+            ("7.0", Number::Float(7.0)),
+            ("7e0", Number::Float(7.0)),
+            ("7E+1", Number::Float(70.0)),
+            ("-7.5e-1", Number::Float(-0.75)),
+            ("-0.0", Number::Float(-0.0)),
+            ("0.1", Number::Float(0.1)),
+        ] {
+            let (rest, number) = number(input).unwrap();
+            assert_eq!(rest, "", "{}", input);
+            assert_eq!(
+                format!("{:?}", number),
+                format!("{:?}", expected),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn exponent_integers() {
+        let options = ParseOptions::new().exponent_integers(true);
+        for (input, expected) in [
+            ("1e3", Number::PositiveInteger(1000)),
+            ("-2E2", Number::NegativeInteger(-200)),
+            ("1.5e1", Number::PositiveInteger(15)),
+            ("2500e-2", Number::PositiveInteger(25)),
+            ("0.0e5", Number::PositiveInteger(0)),
+            ("0e-400", Number::PositiveInteger(0)),
+            ("0.000001e6", Number::PositiveInteger(1)),
+            (
+                "1.8446744073709551615e19",
+                Number::PositiveInteger(u64::MAX),
+            ),
+            (
+                "-9.223372036854775808E+18",
+                Number::NegativeInteger(i64::MIN),
+            ),
+            ("1e30", Number::Float(1e30)),
+            ("1e-2", Number::Float(0.01)),
+            ("1.25e1", Number::Float(12.5)),
+            (
+                "1.8446744073709551616e19",
+                Number::Float(18446744073709551616.0),
+            ),
+            ("1e-99999999999999999999", Number::Float(0.0)),
+            ("1.5", Number::Float(1.5)),
+        ] {
+            let number = number_with(input, &options).map(|(_, number)| number);
+            assert_eq!(
+                format!("{:?}", number),
+                format!("{:?}", Ok::<_, Err<Error<&str>>>(expected)),
+                "{}",
+                input
+            );
+        }
+        assert_eq!(number("1e3"), Ok(("", Number::Float(1000.0))));
+    }
 }
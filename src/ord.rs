@@ -0,0 +1,549 @@
+use crate::number::Number;
+use crate::Value;
+use std::cmp::{Ordering, Reverse};
+
+/// Values of different kinds are ordered
+/// `null < bool < number < string < array < object`.
+///
+/// Numbers compare by their numeric value, strings by bytes, arrays element
+/// by element, and objects as their members sorted by key.
+///
+/// ```rust
+/// use wson::parse;
+/// # use std::error;
+/// # fn main() -> Result<(), Box<dyn error::Error>> {
+///
+///
+/// let mut values = vec![parse("[1]")?, parse("\"a\"")?, parse("2.5")?, parse("2")?, parse("null")?];
+/// values.sort();
+/// assert_eq!(values, vec![parse("null")?, parse("2")?, parse("2.5")?, parse("\"a\"")?, parse("[1]")?]);
+///
+/// # Ok(())
+/// # }
+/// ```
+impl Ord for Value {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
+            (Value::Number(a), Value::Number(b)) => a.cmp(b),
+            (Value::String(a), Value::String(b)) => a.cmp(b),
+            (Value::Array(a), Value::Array(b)) => a.cmp(b),
+            (Value::Object(a), Value::Object(b)) => {
+                let mut a: Vec<_> = a.iter().collect();
+                let mut b: Vec<_> = b.iter().collect();
+                a.sort_unstable_by_key(|(key, _)| *key);
+                b.sort_unstable_by_key(|(key, _)| *key);
+                a.cmp(&b)
+            }
+            _ => rank(self).cmp(&rank(other)),
+        }
+    }
+}
+
+impl PartialOrd for Value {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Direction of `Value::sort_by_pointer`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+/// Where `Value::sort_by_pointer_with` puts elements without a sort key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MissingPosition {
+    First,
+    Last,
+}
+
+impl Value {
+    /// Sort an array by the value at `pointer` in each element
+    ///
+    /// Keys are compared with the total order of `Value`. Elements where
+    /// nothing is at `pointer` go last, in both orders. The sort is stable,
+    /// so elements with equal keys keep their order, and sorting by one key
+    /// and then another sorts by the second and then the first. Does
+    /// nothing when value isn't an array.
+    ///
+    /// ```rust
+    /// use wson::{json, SortOrder};
+    /// # fn main() {
+    ///
+    ///
+    /// let mut users = json!([
+    ///     {"name": "b", "age": 30},
+    ///     {"name": "a"},
+    ///     {"name": "c", "age": 25},
+    /// ]);
+    /// users.sort_by_pointer("/age", SortOrder::Descending);
+    /// assert_eq!(
+    ///     users,
+    ///     json!([{"name": "b", "age": 30}, {"name": "c", "age": 25}, {"name": "a"}])
+    /// );
+    /// # }
+    /// ```
+    pub fn sort_by_pointer(&mut self, pointer: &str, order: SortOrder) {
+        self.sort_by_pointer_with(pointer, order, MissingPosition::Last)
+    }
+
+    /// Sort an array like `sort_by_pointer`, with elements without a key
+    /// put where `missing` says
+    pub fn sort_by_pointer_with(
+        &mut self,
+        pointer: &str,
+        order: SortOrder,
+        missing: MissingPosition,
+    ) {
+        let elements = match self.as_array_mut() {
+            Some(elements) => elements,
+            None => return,
+        };
+        elements.sort_by(|a, b| match (a.pointer(pointer), b.pointer(pointer)) {
+            (Some(a), Some(b)) => match order {
+                SortOrder::Ascending => a.cmp(b),
+                SortOrder::Descending => b.cmp(a),
+            },
+            (Some(_), None) => match missing {
+                MissingPosition::First => Ordering::Greater,
+                MissingPosition::Last => Ordering::Less,
+            },
+            (None, Some(_)) => match missing {
+                MissingPosition::First => Ordering::Less,
+                MissingPosition::Last => Ordering::Greater,
+            },
+            (None, None) => Ordering::Equal,
+        });
+    }
+}
+
+fn rank(value: &Value) -> u8 {
+    match value {
+        Value::Null => 0,
+        Value::Bool(_) => 1,
+        Value::Number(_) => 2,
+        Value::String(_) => 3,
+        Value::Array(_) => 4,
+        Value::Object(_) => 5,
+    }
+}
+
+/// Numbers compare by their numeric value, exactly even across variants.
+///
+/// `0.0` and `-0.0` are equal, NaN is greater than every other number.
+/// Different variants with the same value, like `PositiveInteger(3)` and
+/// `Float(3.0)`, are equal as with `==`. A raw number is ordered as the
+/// number it stands for. Arbitrary numbers compare as the nearest `f64`,
+/// come after the other variants on a tie, and then compare as written.
+///
+/// Integers are never rounded to compare with floats, so a float just above
+/// `u64::MAX` is greater than it even though `u64::MAX as f64` is not.
+///
+/// ```rust
+/// use wson::number::Number;
+/// # fn main() {
+///
+///
+/// let mut numbers = vec![
+///     Number::Float(10.5),
+///     Number::Float(f64::NAN),
+///     Number::PositiveInteger(10),
+///     Number::NegativeInteger(-1),
+///     Number::Float(9.5),
+/// ];
+/// numbers.sort();
+/// assert_eq!(numbers[..4], [
+///     Number::NegativeInteger(-1),
+///     Number::Float(9.5),
+///     Number::PositiveInteger(10),
+///     Number::Float(10.5),
+/// ]);
+/// assert!(Number::PositiveInteger(u64::MAX) < Number::Float(1.8446744073709552e19));
+/// # }
+/// ```
+impl Ord for Number {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let order = numeric_cmp(self, other);
+        #[cfg(feature = "arbitrary_precision")]
+        match (self, other) {
+            (Number::Arbitrary(a), Number::Arbitrary(b)) => return order.then_with(|| a.cmp(b)),
+            (Number::Arbitrary(_), _) => return order.then(Ordering::Greater),
+            (_, Number::Arbitrary(_)) => return order.then(Ordering::Less),
+            _ => {}
+        }
+        order
+    }
+}
+
+impl PartialOrd for Number {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Compare the numeric values of `a` and `b` only, ignoring their variants
+///
+/// Raw numbers compare as the number they stand for, and arbitrary numbers
+/// as the nearest `f64`.
+pub(crate) fn numeric_cmp(a: &Number, b: &Number) -> Ordering {
+    match (numeric(a), numeric(b)) {
+        (Numeric::Integer(a), Numeric::Integer(b)) => a.cmp(&b),
+        (Numeric::Float(a), Numeric::Float(b)) => canonical(a).total_cmp(&canonical(b)),
+        (Numeric::Integer(a), Numeric::Float(b)) => compare_integer(a, b),
+        (Numeric::Float(a), Numeric::Integer(b)) => compare_integer(b, a).reverse(),
+    }
+}
+
+/// A number as an integer or a float, whatever its variant
+enum Numeric {
+    Integer(Integer),
+    Float(f64),
+}
+
+/// An integer as its sign and magnitude, which orders every integer variant
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+enum Integer {
+    // a larger magnitude is smaller, so it is ordered as reversed
+    Negative(Reverse<u128>),
+    NonNegative(u128),
+}
+
+impl Integer {
+    fn new(negative: bool, magnitude: u128) -> Self {
+        if negative && magnitude > 0 {
+            Integer::Negative(Reverse(magnitude))
+        } else {
+            Integer::NonNegative(magnitude)
+        }
+    }
+}
+
+fn numeric(number: &Number) -> Numeric {
+    match *number {
+        Number::PositiveInteger(n) => Numeric::Integer(Integer::new(false, u128::from(n))),
+        Number::PositiveInteger128(n) => Numeric::Integer(Integer::new(false, n)),
+        Number::NegativeInteger(n) => {
+            Numeric::Integer(Integer::new(n < 0, n.unsigned_abs().into()))
+        }
+        Number::NegativeInteger128(n) => Numeric::Integer(Integer::new(n < 0, n.unsigned_abs())),
+        Number::Float(f) => Numeric::Float(f),
+        Number::Raw(ref raw) => numeric(&raw.number()),
+        #[cfg(feature = "arbitrary_precision")]
+        Number::Arbitrary(_) => Numeric::Float(number.as_f64()),
+    }
+}
+
+/// `-0.0` as `0.0` and every NaN as the same NaN, which `total_cmp` puts last
+fn canonical(f: f64) -> f64 {
+    if f == 0.0 {
+        0.0
+    } else if f.is_nan() {
+        f64::NAN
+    } else {
+        f
+    }
+}
+
+/// Compare an integer within the `u128` and `i128` ranges with a float exactly
+fn compare_integer(integer: Integer, float: f64) -> Ordering {
+    if float.is_nan() {
+        return Ordering::Less;
+    }
+    // every such integer is in (-2^128, 2^128), which are exactly representable
+    if float >= 3.402823669209385e38 {
+        return Ordering::Less;
+    }
+    if float <= -3.402823669209385e38 {
+        return Ordering::Greater;
+    }
+    // floats this large are integral, and smaller ones fit in u128 after
+    // truncation; compare the integer parts, then the fraction
+    let truncated = float.trunc();
+    let truncated_integer = Integer::new(truncated < 0.0, truncated.abs() as u128);
+    match integer.cmp(&truncated_integer) {
+        Ordering::Equal => 0.0
+            .partial_cmp(&(float - truncated))
+            .unwrap_or(Ordering::Equal),
+        ordering => ordering,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json;
+    use std::collections::HashMap;
+
+    fn positive(n: u64) -> Number {
+        Number::PositiveInteger(n)
+    }
+
+    fn negative(n: i64) -> Number {
+        Number::NegativeInteger(n)
+    }
+
+    fn float(f: f64) -> Number {
+        Number::Float(f)
+    }
+
+    #[test]
+    fn numbers_across_variants() {
+        assert!(positive(2) < float(2.5));
+        assert!(float(2.5) < positive(3));
+        assert!(negative(-3) < float(-2.5));
+        assert!(float(-3.5) < negative(-3));
+        assert!(negative(-1) < positive(0));
+        assert!(float(f64::NEG_INFINITY) < negative(i64::MIN));
+        assert!(positive(u64::MAX) < float(18446744073709551616.0));
+        assert!(float(18446744073709549568.0) < positive(u64::MAX));
+        assert!(positive(9007199254740993) > float(9007199254740992.0));
+        assert!(positive(u64::MAX) < float(f64::NAN));
+        assert!(float(f64::INFINITY) < float(f64::NAN));
+    }
+
+    #[test]
+    fn equal_values_across_variants() {
+        assert_eq!(float(0.0).cmp(&float(-0.0)), Ordering::Equal);
+        assert_eq!(float(f64::NAN).cmp(&float(-f64::NAN)), Ordering::Equal);
+        assert_eq!(negative(0).cmp(&positive(0)), Ordering::Equal);
+        assert_eq!(positive(3).cmp(&float(3.0)), Ordering::Equal);
+        assert_eq!(negative(-3).cmp(&float(-3.0)), Ordering::Equal);
+        assert_eq!(positive(0).cmp(&float(-0.0)), Ordering::Equal);
+    }
+
+    #[test]
+    fn near_misses() {
+        // the floats next to 2^64, which u64::MAX rounds to
+        assert!(positive(u64::MAX) < float(1.8446744073709552e19));
+        assert!(positive(u64::MAX) < float(1.8446744073709553e19));
+        assert!(positive(u64::MAX) > float(1.844674407370955e19));
+        assert!(positive(u64::MAX - 2046) > float(1.844674407370955e19));
+        assert!(positive(u64::MAX - 2047) == float(1.844674407370955e19));
+        assert!(positive(u64::MAX - 2048) < float(1.844674407370955e19));
+        // around 2^53, where floats stop holding every integer
+        assert!(positive((1 << 53) + 1) > float(9007199254740992.0));
+        assert!(positive((1 << 53) + 1) < float(9007199254740994.0));
+        assert!(negative(-(1 << 53) - 1) < float(-9007199254740992.0));
+        assert!(negative(i64::MIN) == float(-9.223372036854776e18));
+        assert!(negative(i64::MIN + 1) > float(-9.223372036854776e18));
+        // and around the 128-bit limits
+        let max = Number::PositiveInteger128(u128::MAX);
+        assert!(max < float(3.402823669209385e38));
+        let below_2_128 = f64::from_bits(3.402823669209385e38f64.to_bits() - 1);
+        assert!(max > float(below_2_128));
+        let min = Number::NegativeInteger128(i128::MIN);
+        assert!(min == float(-1.7014118346046923e38));
+        assert!(Number::NegativeInteger128(i128::MIN + 1) > float(-1.7014118346046923e38));
+        assert!(positive(10) > float(9.5) && positive(10) < float(10.5));
+        assert!(negative(-10) < float(-9.5) && negative(-10) > float(-10.5));
+    }
+
+    #[test]
+    fn total_order_on_random_numbers() {
+        let mut state = 88172645463325252u64;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        let numbers: Vec<Number> = (0..300)
+            .map(|_| {
+                let bits = next();
+                // integers near floats, so that ties and near misses happen
+                let f = f64::from_bits(next() % 0x43f0_0000_0000_0000 + 0x3ff0_0000_0000_0000)
+                    .min(1.8e19);
+                match bits % 6 {
+                    0 => positive(f as u64),
+                    1 => positive((f as u64).wrapping_add(bits % 3)),
+                    2 => negative(-(f.min(9.2e18) as i64) - (bits % 3) as i64),
+                    3 => float(f),
+                    4 => float(-f),
+                    _ => float(f64::from_bits(next())),
+                }
+            })
+            .collect();
+        for a in &numbers {
+            for b in &numbers {
+                let order = a.cmp(b);
+                assert_eq!(order, b.cmp(a).reverse(), "{:?} {:?}", a, b);
+                assert_eq!(order == Ordering::Equal, a == b, "{:?} {:?}", a, b);
+                let (x, y) = (a.as_f64(), b.as_f64());
+                // rounding to f64 may only turn an order into a tie
+                if let Some(rounded) = x.partial_cmp(&y) {
+                    assert!(
+                        rounded == order || rounded == Ordering::Equal,
+                        "{:?} {:?}",
+                        a,
+                        b
+                    );
+                }
+            }
+        }
+        let mut sorted = numbers.clone();
+        sorted.sort();
+        for window in sorted.windows(2) {
+            assert!(window[0] <= window[1]);
+        }
+        for triple in sorted.windows(3) {
+            assert!(triple[0] <= triple[2]);
+        }
+    }
+
+    #[test]
+    fn ordering_agrees_with_eq() {
+        let numbers = [
+            positive(0),
+            positive(3),
+            positive(u64::MAX),
+            negative(0),
+            negative(-3),
+            negative(i64::MIN),
+            float(0.0),
+            float(-0.0),
+            float(3.0),
+            float(-3.0),
+            float(2.5),
+            float(f64::NAN),
+            float(f64::INFINITY),
+        ];
+        for a in &numbers {
+            for b in &numbers {
+                assert_eq!(a == b, a.cmp(b) == Ordering::Equal, "{:?} {:?}", a, b);
+                assert_eq!(a.cmp(b), b.cmp(a).reverse(), "{:?} {:?}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn values_by_kind() {
+        let values = [
+            Value::Null,
+            Value::Bool(false),
+            Value::Bool(true),
+            Value::Number(negative(-1)),
+            Value::Number(float(0.5)),
+            Value::String(String::new()),
+            Value::String("a".to_string()),
+            Value::Array(vec![]),
+            Value::Array(vec![Value::Null]),
+            Value::Array(vec![Value::Null, Value::Null]),
+            Value::Array(vec![Value::Bool(false)]),
+            Value::Object(HashMap::new()),
+            Value::Object(HashMap::from([("a".to_string(), Value::Null)])),
+            Value::Object(HashMap::from([
+                ("a".to_string(), Value::Null),
+                ("b".to_string(), Value::Null),
+            ])),
+            Value::Object(HashMap::from([("a".to_string(), Value::Bool(true))])),
+            Value::Object(HashMap::from([("b".to_string(), Value::Null)])),
+        ];
+        for window in values.windows(2) {
+            assert!(window[0] < window[1], "{:?} {:?}", window[0], window[1]);
+        }
+    }
+
+    #[test]
+    fn sort_mixed_array() {
+        let mut values = vec![
+            Value::Object(HashMap::new()),
+            Value::String("b".to_string()),
+            Value::Number(float(2.5)),
+            Value::Bool(true),
+            Value::Number(positive(2)),
+            Value::Array(vec![]),
+            Value::Null,
+            Value::String("a".to_string()),
+            Value::Number(negative(-7)),
+            Value::Bool(false),
+        ];
+        values.sort();
+        assert_eq!(
+            values,
+            vec![
+                Value::Null,
+                Value::Bool(false),
+                Value::Bool(true),
+                Value::Number(negative(-7)),
+                Value::Number(positive(2)),
+                Value::Number(float(2.5)),
+                Value::String("a".to_string()),
+                Value::String("b".to_string()),
+                Value::Array(vec![]),
+                Value::Object(HashMap::new()),
+            ]
+        );
+    }
+
+    fn ids(value: &Value) -> Vec<u64> {
+        value
+            .iter()
+            .filter_map(|(_, element)| element["id"].as_u64())
+            .collect()
+    }
+
+    #[test]
+    fn sort_by_mixed_numbers() {
+        let mut value = json!([
+            {"id": 1, "score": 2.5},
+            {"id": 2, "score": 2},
+            {"id": 3, "score": -1},
+            {"id": 4, "score": 10u64},
+            {"id": 5, "score": 2.0},
+            {"id": 6, "score": 2},
+        ]);
+        value.sort_by_pointer("/score", SortOrder::Ascending);
+        // 2, 2.0 and 2 are equal and keep their order
+        assert_eq!(ids(&value), [3, 2, 5, 6, 1, 4]);
+        value.sort_by_pointer("/score", SortOrder::Descending);
+        assert_eq!(ids(&value), [4, 1, 2, 5, 6, 3]);
+    }
+
+    #[test]
+    fn sort_by_strings_is_stable() {
+        let mut value = json!([
+            {"id": 1, "last": "b", "first": "y"},
+            {"id": 2, "last": "a", "first": "z"},
+            {"id": 3, "last": "b", "first": "x"},
+            {"id": 4, "last": "a", "first": "x"},
+        ]);
+        // by last name, then first name
+        value.sort_by_pointer("/first", SortOrder::Ascending);
+        value.sort_by_pointer("/last", SortOrder::Ascending);
+        assert_eq!(ids(&value), [4, 2, 3, 1]);
+    }
+
+    #[test]
+    fn sort_with_missing_keys() {
+        let elements = json!([
+            {"id": 1},
+            {"id": 2, "meta": {"rank": 2}},
+            {"id": 3, "meta": {}},
+            {"id": 4, "meta": {"rank": 1}},
+            {"id": 5, "meta": {"rank": null}},
+        ]);
+        let mut value = elements.clone();
+        value.sort_by_pointer("/meta/rank", SortOrder::Ascending);
+        assert_eq!(ids(&value), [5, 4, 2, 1, 3]);
+        value.sort_by_pointer("/meta/rank", SortOrder::Descending);
+        assert_eq!(ids(&value), [2, 4, 5, 1, 3]);
+
+        let mut value = elements.clone();
+        value.sort_by_pointer_with("/meta/rank", SortOrder::Ascending, MissingPosition::First);
+        assert_eq!(ids(&value), [1, 3, 5, 4, 2]);
+
+        let mut value = elements.clone();
+        value.sort_by_pointer("rank", SortOrder::Ascending);
+        assert_eq!(value, elements);
+    }
+
+    #[test]
+    fn sort_non_arrays_does_nothing() {
+        let mut value = json!({"b": 1, "a": 2});
+        value.sort_by_pointer("", SortOrder::Ascending);
+        assert_eq!(value, json!({"b": 1, "a": 2}));
+    }
+}
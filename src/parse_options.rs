@@ -0,0 +1,171 @@
+/// Options to control how json is parsed, used by `parse_with`
+///
+/// The default parses like `parse`.
+///
+/// ```rust
+/// use wson::number::Number;
+/// use wson::{parse_with, ParseOptions, Value};
+/// # use std::error;
+/// # fn main() -> Result<(), Box<dyn error::Error>> {
+///
+///
+/// let options = ParseOptions::new().normalize_numbers(true);
+/// assert_eq!(
+///     parse_with("[1.0, 1.5]", &options)?,
+///     Value::Array(vec![
+///         Value::Number(Number::PositiveInteger(1)),
+///         Value::Number(Number::Float(1.5)),
+///     ])
+/// );
+///
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseOptions {
+    pub(crate) normalize_numbers: bool,
+    pub(crate) strict_integers: bool,
+    pub(crate) raw_numbers: bool,
+    pub(crate) exponent_integers: bool,
+    pub(crate) infinite_floats: bool,
+    pub(crate) allow_numeric_separators: bool,
+    pub(crate) allow_plus_sign: bool,
+    pub(crate) allow_lax_decimal: bool,
+    pub(crate) lone_surrogates: LoneSurrogates,
+    pub(crate) allow_control_chars: bool,
+    #[cfg(feature = "arbitrary_precision")]
+    pub(crate) arbitrary_precision: bool,
+}
+
+impl ParseOptions {
+    /// Options for strict json
+    pub fn new() -> Self {
+        ParseOptions {
+            normalize_numbers: false,
+            strict_integers: false,
+            raw_numbers: false,
+            exponent_integers: false,
+            infinite_floats: false,
+            allow_numeric_separators: false,
+            allow_plus_sign: false,
+            allow_lax_decimal: false,
+            lone_surrogates: LoneSurrogates::Error,
+            allow_control_chars: false,
+            #[cfg(feature = "arbitrary_precision")]
+            arbitrary_precision: false,
+        }
+    }
+
+    /// Turn integral floats into integers, as `Value::normalize_numbers` does
+    pub fn normalize_numbers(mut self, normalize: bool) -> Self {
+        self.normalize_numbers = normalize;
+        self
+    }
+
+    /// Fail with `ParseErrorKind::NumberOutOfRange` on an integer which
+    /// doesn't fit `u128` or `i128`, instead of parsing it as a float
+    pub fn strict_integers(mut self, strict: bool) -> Self {
+        self.strict_integers = strict;
+        self
+    }
+
+    /// Keep every number as `Number::Raw` with the text it is written as,
+    /// so that it is serialized the same. Takes precedence over
+    /// `arbitrary_precision`.
+    pub fn raw_numbers(mut self, raw: bool) -> Self {
+        self.raw_numbers = raw;
+        self
+    }
+
+    /// Parse a number with an exponent as an integer when its value is
+    /// integral and fits `u64` or `i64`, so that `1e3` and `1.5e1` are
+    /// `PositiveInteger`. Other numbers with an exponent, like `1e30` or
+    /// `1e-2`, are still floats.
+    pub fn exponent_integers(mut self, exponent_integers: bool) -> Self {
+        self.exponent_integers = exponent_integers;
+        self
+    }
+
+    /// Parse a number too large for `f64`, like `1e400`, as infinity
+    /// instead of failing with `ParseErrorKind::NumberOutOfRange`
+    pub fn infinite_floats(mut self, infinite: bool) -> Self {
+        self.infinite_floats = infinite;
+        self
+    }
+
+    /// Accept `_` between two digits of a number, as in `1_000`,
+    /// `3.141_592` and `1e1_0`, which is parsed as if it weren't there.
+    /// An `_` anywhere else, or next to another, is still an error.
+    pub fn allow_numeric_separators(mut self, allow: bool) -> Self {
+        self.allow_numeric_separators = allow;
+        self
+    }
+
+    /// Accept a `+` before a number, as in `+1.5`
+    pub fn allow_plus_sign(mut self, allow: bool) -> Self {
+        self.allow_plus_sign = allow;
+        self
+    }
+
+    /// Accept a decimal point without digits before or after it, as in
+    /// `.5` and `2.`, which are parsed as `0.5` and `2.0`
+    pub fn allow_lax_decimal(mut self, allow: bool) -> Self {
+        self.allow_lax_decimal = allow;
+        self
+    }
+
+    /// What to do with a `\u` escape of a surrogate which isn't part of a
+    /// pair, like `"\ud800"`, which json allows but a `String` can't hold
+    pub fn lone_surrogates(mut self, policy: LoneSurrogates) -> Self {
+        self.lone_surrogates = policy;
+        self
+    }
+
+    /// Accept control characters U+0000 to U+001F in a string as they are,
+    /// instead of failing with `ParseErrorKind::ControlCharacter`, as json
+    /// requires them to be escaped
+    pub fn allow_control_chars(mut self, allow: bool) -> Self {
+        self.allow_control_chars = allow;
+        self
+    }
+
+    /// Keep floats and integers which don't fit `u128` or `i128` as
+    /// `Number::Arbitrary`, written as in the input
+    #[cfg(feature = "arbitrary_precision")]
+    pub fn arbitrary_precision(mut self, arbitrary: bool) -> Self {
+        self.arbitrary_precision = arbitrary;
+        self
+    }
+}
+
+/// What `ParseOptions::lone_surrogates` does with a lone surrogate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoneSurrogates {
+    /// Fail with `ParseErrorKind::LoneSurrogate` at the escape
+    Error,
+    /// Decode it as U+FFFD, like browsers do
+    ReplacementChar,
+    /// Leave it out
+    Skip,
+}
+
+impl ParseOptions {
+    /// Whether any number syntax beyond json is accepted
+    pub(crate) fn is_lenient(&self) -> bool {
+        self.allow_numeric_separators || self.allow_plus_sign || self.allow_lax_decimal
+    }
+
+    /// The same options with only json number syntax accepted
+    pub(crate) fn strict(&self) -> Self {
+        self.clone()
+            .allow_numeric_separators(false)
+            .allow_plus_sign(false)
+            .allow_lax_decimal(false)
+    }
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
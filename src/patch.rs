@@ -0,0 +1,777 @@
+//! [JSON Patch](https://www.rfc-editor.org/rfc/rfc6902)
+//!
+//! ```rust
+//! use wson::patch::{self, Op};
+//! use wson::{diff, json};
+//! # use std::error;
+//! # fn main() -> Result<(), Box<dyn error::Error>> {
+//!
+//!
+//! let before = json!({"retries": 3, "hosts": ["a"]});
+//! let after = json!({"retries": 5, "hosts": ["a", "b"]});
+//! let ops = diff(&before, &after);
+//! assert_eq!(
+//!     ops,
+//!     [
+//!         Op::Add { path: "/hosts/1".to_string(), value: json!("b") },
+//!         Op::Replace { path: "/retries".to_string(), value: json!(5) },
+//!     ]
+//! );
+//!
+//! let mut value = before.clone();
+//! patch::apply(&mut value, &ops)?;
+//! assert_eq!(value, after);
+//!
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::PatchError;
+use crate::pointer::{self, array_index, unescape};
+use crate::{ToJson, Value};
+
+/// One operation of a JSON Patch
+#[derive(Debug, Clone, PartialEq)]
+pub enum Op {
+    /// Insert `value` at `path`, shifting array elements up
+    Add { path: String, value: Value },
+    /// Remove the value at `path`, shifting array elements down
+    Remove { path: String },
+    /// Replace the value at `path`, which must exist
+    Replace { path: String, value: Value },
+    /// Remove the value at `from` and add it at `path`
+    Move { from: String, path: String },
+    /// Add a copy of the value at `from` at `path`
+    Copy { from: String, path: String },
+    /// Check that the value at `path` equals `value`
+    Test { path: String, value: Value },
+}
+
+/// `{"op": "add", "path": ..., "value": ...}` and so on
+impl ToJson for Op {
+    fn to_json(&self) -> Value {
+        let (op, members) = match self {
+            Op::Add { path, value } => {
+                ("add", [("path", path.to_json()), ("value", value.clone())])
+            }
+            Op::Remove { path } => return [("op", "remove"), ("path", path)].into_iter().collect(),
+            Op::Replace { path, value } => (
+                "replace",
+                [("path", path.to_json()), ("value", value.clone())],
+            ),
+            Op::Move { from, path } => {
+                ("move", [("from", from.to_json()), ("path", path.to_json())])
+            }
+            Op::Copy { from, path } => {
+                ("copy", [("from", from.to_json()), ("path", path.to_json())])
+            }
+            Op::Test { path, value } => {
+                ("test", [("path", path.to_json()), ("value", value.clone())])
+            }
+        };
+        [("op", Value::from(op))]
+            .into_iter()
+            .chain(members)
+            .collect()
+    }
+}
+
+/// Apply `ops` to `doc` in order
+///
+/// Either every operation is applied or, when one fails, `doc` is left as
+/// it was.
+pub fn apply(doc: &mut Value, ops: &[Op]) -> Result<(), PatchError> {
+    let mut patched = doc.clone();
+    for op in ops {
+        apply_op(&mut patched, op)?;
+    }
+    *doc = patched;
+    Ok(())
+}
+
+fn apply_op(doc: &mut Value, op: &Op) -> Result<(), PatchError> {
+    match op {
+        Op::Add { path, value } => add(doc, path, value.clone()),
+        Op::Remove { path } => remove(doc, path).map(drop),
+        Op::Replace { path, value } => {
+            *get_mut(doc, path)? = value.clone();
+            Ok(())
+        }
+        Op::Move { from, path } => {
+            if is_inside(path, from) {
+                return Err(PatchError::MoveIntoChild {
+                    from: from.clone(),
+                    path: path.clone(),
+                });
+            }
+            let value = remove(doc, from)?;
+            add(doc, path, value)
+        }
+        Op::Copy { from, path } => {
+            let value = get_mut(doc, from)?.clone();
+            add(doc, path, value)
+        }
+        Op::Test { path, value } => {
+            if get_mut(doc, path)? == value {
+                Ok(())
+            } else {
+                Err(PatchError::TestFailed {
+                    pointer: path.clone(),
+                })
+            }
+        }
+    }
+}
+
+/// Whether `pointer` points inside the value at `parent`
+fn is_inside(pointer: &str, parent: &str) -> bool {
+    pointer
+        .strip_prefix(parent)
+        .is_some_and(|rest| rest.starts_with('/'))
+}
+
+fn validate(pointer: &str) -> Result<(), PatchError> {
+    if pointer::is_valid(pointer) {
+        Ok(())
+    } else {
+        Err(PatchError::InvalidPointer {
+            pointer: pointer.to_string(),
+        })
+    }
+}
+
+fn get_mut<'v>(doc: &'v mut Value, path: &str) -> Result<&'v mut Value, PatchError> {
+    validate(path)?;
+    doc.pointer_mut(path).ok_or_else(|| PatchError::NotFound {
+        pointer: path.to_string(),
+    })
+}
+
+fn add(doc: &mut Value, path: &str, value: Value) -> Result<(), PatchError> {
+    validate(path)?;
+    let (parent, last) = match path.rsplit_once('/') {
+        Some(split) => split,
+        None => {
+            *doc = value;
+            return Ok(());
+        }
+    };
+    match get_mut(doc, parent) {
+        Ok(Value::Object(members)) => {
+            members.insert(unescape(last).unwrap_or_default(), value);
+            Ok(())
+        }
+        Ok(Value::Array(elements)) => {
+            let index = match array_index(last) {
+                Some(index) if index <= elements.len() => index,
+                None if last == "-" => elements.len(),
+                _ => {
+                    return Err(PatchError::InvalidIndex {
+                        pointer: path.to_string(),
+                    })
+                }
+            };
+            elements.insert(index, value);
+            Ok(())
+        }
+        _ => Err(PatchError::NotFound {
+            pointer: path.to_string(),
+        }),
+    }
+}
+
+fn remove(doc: &mut Value, path: &str) -> Result<Value, PatchError> {
+    validate(path)?;
+    doc.remove_pointer(path)
+        .ok_or_else(|| PatchError::NotFound {
+            pointer: path.to_string(),
+        })
+}
+
+/// JSON Patch which undoes `ops` after they are applied to `doc`
+///
+/// `doc` is the document before the patch, which gives the values that
+/// `ops` remove or replace. Fails with the same error as `apply` when an
+/// operation can't be applied.
+///
+/// ```rust
+/// use wson::json;
+/// use wson::patch::{self, Op};
+/// # use std::error;
+/// # fn main() -> Result<(), Box<dyn error::Error>> {
+///
+///
+/// let doc = json!({"a": 1, "b": [2]});
+/// let ops = [
+///     Op::Remove { path: "/a".to_string() },
+///     Op::Add { path: "/b/-".to_string(), value: json!(3) },
+/// ];
+/// let undo = patch::invert(&doc, &ops)?;
+///
+/// let mut value = doc.clone();
+/// patch::apply(&mut value, &ops)?;
+/// patch::apply(&mut value, &undo)?;
+/// assert_eq!(value, doc);
+///
+/// # Ok(())
+/// # }
+/// ```
+pub fn invert(doc: &Value, ops: &[Op]) -> Result<Vec<Op>, PatchError> {
+    let mut current = doc.clone();
+    let mut inverted = Vec::new();
+    for op in ops {
+        let mut undo = invert_op(&current, op)?;
+        apply_op(&mut current, op)?;
+        // the last operation is undone first
+        undo.reverse();
+        inverted.append(&mut undo);
+    }
+    inverted.reverse();
+    Ok(inverted)
+}
+
+/// Operations which undo `op` applied to `doc`
+fn invert_op(doc: &Value, op: &Op) -> Result<Vec<Op>, PatchError> {
+    let undo = match op {
+        Op::Add { path, .. } | Op::Copy { path, .. } => vec![undo_add(doc, path)],
+        Op::Remove { path } => vec![Op::Add {
+            path: path.clone(),
+            value: doc.pointer(path).cloned().unwrap_or_default(),
+        }],
+        Op::Replace { path, .. } => vec![Op::Replace {
+            path: path.clone(),
+            value: doc.pointer(path).cloned().unwrap_or_default(),
+        }],
+        Op::Move { from, path } if from == path => vec![],
+        Op::Move { path, .. } if path.is_empty() => vec![Op::Replace {
+            path: String::new(),
+            value: doc.clone(),
+        }],
+        Op::Move { from, path } => {
+            let mut moved = doc.clone();
+            let value = remove(&mut moved, from)?;
+            let to = resolve(&moved, path);
+            let overwritten = match op_replaced(&moved, path) {
+                Some(Op::Replace { value, .. }) => Some(Op::Add {
+                    path: to.clone(),
+                    value,
+                }),
+                _ => None,
+            };
+            if is_inside(from, &to) {
+                // moving back would be a move into a child, which isn't
+                // allowed, so restore the parent first and then the value
+                let mut undo = vec![Op::Remove { path: to }];
+                undo.extend(overwritten);
+                undo.push(Op::Add {
+                    path: from.clone(),
+                    value,
+                });
+                undo
+            } else {
+                // move the value back, then restore what it overwrote, which
+                // may have shifted when the value was put back into an array
+                let mut undo = vec![Op::Move {
+                    from: to,
+                    path: from.clone(),
+                }];
+                undo.extend(overwritten.map(|op| match op {
+                    Op::Add { path, value } => Op::Add {
+                        path: before_removal(doc, &path, from),
+                        value,
+                    },
+                    op => op,
+                }));
+                undo
+            }
+        }
+        Op::Test { path, value } => vec![Op::Test {
+            path: path.clone(),
+            value: value.clone(),
+        }],
+    };
+    Ok(undo)
+}
+
+/// Operation which undoes adding something at `path` to `doc`
+fn undo_add(doc: &Value, path: &str) -> Op {
+    op_replaced(doc, path).unwrap_or_else(|| Op::Remove {
+        path: resolve(doc, path),
+    })
+}
+
+/// `replace` with the value that adding at `path` overwrites, if any
+fn op_replaced(doc: &Value, path: &str) -> Option<Op> {
+    let (parent, _) = match path.rsplit_once('/') {
+        Some(split) => split,
+        None => {
+            return Some(Op::Replace {
+                path: String::new(),
+                value: doc.clone(),
+            })
+        }
+    };
+    match doc.pointer(parent) {
+        Some(Value::Object(_)) => doc.pointer(path).map(|value| Op::Replace {
+            path: path.to_string(),
+            value: value.clone(),
+        }),
+        _ => None,
+    }
+}
+
+/// Where `pointer` was in `doc` before the value at `removed` was taken out
+fn before_removal(doc: &Value, pointer: &str, removed: &str) -> String {
+    let shifted = removed.rsplit_once('/').and_then(|(parent, last)| {
+        let index = array_index(last)?;
+        let rest = pointer.strip_prefix(parent)?.strip_prefix('/')?;
+        let (token, rest) = rest.find('/').map_or((rest, ""), |end| rest.split_at(end));
+        match (doc.pointer(parent), array_index(token)) {
+            (Some(Value::Array(_)), Some(i)) if i >= index => {
+                Some(format!("{}/{}{}", parent, i + 1, rest))
+            }
+            _ => None,
+        }
+    });
+    shifted.unwrap_or_else(|| pointer.to_string())
+}
+
+/// `path` with a trailing `-` replaced with the index it appends at
+fn resolve(doc: &Value, path: &str) -> String {
+    match path.rsplit_once('/') {
+        Some((parent, "-")) => match doc.pointer(parent) {
+            Some(Value::Array(elements)) => format!("{}/{}", parent, elements.len()),
+            _ => path.to_string(),
+        },
+        _ => path.to_string(),
+    }
+}
+
+/// JSON Patch which turns `a` into `b`
+///
+/// Only `add`, `remove` and `replace` are used. Members are visited in
+/// order of their keys. Arrays are compared index by index, with elements
+/// added or removed at the end.
+pub fn diff(a: &Value, b: &Value) -> Vec<Op> {
+    let mut ops = Vec::new();
+    diff_into(&mut String::new(), a, b, &mut ops);
+    ops
+}
+
+fn diff_into(path: &mut String, a: &Value, b: &Value, ops: &mut Vec<Op>) {
+    if a == b {
+        return;
+    }
+    let len = path.len();
+    match (a, b) {
+        (Value::Object(a), Value::Object(b)) => {
+            let mut removed: Vec<_> = a.keys().filter(|key| !b.contains_key(*key)).collect();
+            removed.sort();
+            for key in removed {
+                ops.push(Op::Remove {
+                    path: format!("{}/{}", path, pointer::escape(key)),
+                });
+            }
+            let mut members: Vec<_> = b.iter().collect();
+            members.sort_by_key(|(key, _)| *key);
+            for (key, b) in members {
+                path.push('/');
+                path.push_str(&pointer::escape(key));
+                match a.get(key) {
+                    Some(a) => diff_into(path, a, b, ops),
+                    None => ops.push(Op::Add {
+                        path: path.clone(),
+                        value: b.clone(),
+                    }),
+                }
+                path.truncate(len);
+            }
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            for (index, (a, b)) in a.iter().zip(b).enumerate() {
+                path.push_str(&format!("/{}", index));
+                diff_into(path, a, b, ops);
+                path.truncate(len);
+            }
+            for (index, b) in b.iter().enumerate().skip(a.len()) {
+                ops.push(Op::Add {
+                    path: format!("{}/{}", path, index),
+                    value: b.clone(),
+                });
+            }
+            // from the end, so the indices stay valid
+            for index in (b.len()..a.len()).rev() {
+                ops.push(Op::Remove {
+                    path: format!("{}/{}", path, index),
+                });
+            }
+        }
+        _ => ops.push(Op::Replace {
+            path: path.clone(),
+            value: b.clone(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json;
+
+    fn add(path: &str, value: Value) -> Op {
+        Op::Add {
+            path: path.to_string(),
+            value,
+        }
+    }
+
+    fn remove(path: &str) -> Op {
+        Op::Remove {
+            path: path.to_string(),
+        }
+    }
+
+    fn replace(path: &str, value: Value) -> Op {
+        Op::Replace {
+            path: path.to_string(),
+            value,
+        }
+    }
+
+    #[test]
+    fn apply_each_op() -> Result<(), PatchError> {
+        let mut value = json!({"a": [1, 2], "b": {"c": 3}});
+        apply(
+            &mut value,
+            &[
+                add("/a/1", json!(9)),
+                add("/a/-", json!(8)),
+                remove("/a/0"),
+                replace("/b/c", json!(4)),
+                Op::Move {
+                    from: "/b/c".to_string(),
+                    path: "/d".to_string(),
+                },
+                Op::Copy {
+                    from: "/a".to_string(),
+                    path: "/b/a".to_string(),
+                },
+                Op::Test {
+                    path: "/d".to_string(),
+                    value: json!(4),
+                },
+            ],
+        )?;
+        assert_eq!(
+            value,
+            json!({"a": [9, 2, 8], "b": {"a": [9, 2, 8]}, "d": 4})
+        );
+
+        apply(&mut value, &[add("", json!([])), add("/0", json!(null))])?;
+        assert_eq!(value, json!([null]));
+        Ok(())
+    }
+
+    #[test]
+    fn failed_apply_leaves_doc() {
+        let original = json!({"a": [1], "b": "x"});
+        let cases = [
+            (
+                add("a", json!(1)),
+                PatchError::InvalidPointer {
+                    pointer: "a".to_string(),
+                },
+            ),
+            (
+                add("/x/y", json!(1)),
+                PatchError::NotFound {
+                    pointer: "/x/y".to_string(),
+                },
+            ),
+            (
+                add("/b/0", json!(1)),
+                PatchError::NotFound {
+                    pointer: "/b/0".to_string(),
+                },
+            ),
+            (
+                add("/a/2", json!(1)),
+                PatchError::InvalidIndex {
+                    pointer: "/a/2".to_string(),
+                },
+            ),
+            (
+                remove("/a/1"),
+                PatchError::NotFound {
+                    pointer: "/a/1".to_string(),
+                },
+            ),
+            (
+                remove(""),
+                PatchError::NotFound {
+                    pointer: "".to_string(),
+                },
+            ),
+            (
+                replace("/z", json!(1)),
+                PatchError::NotFound {
+                    pointer: "/z".to_string(),
+                },
+            ),
+            (
+                Op::Move {
+                    from: "/a".to_string(),
+                    path: "/a/0".to_string(),
+                },
+                PatchError::MoveIntoChild {
+                    from: "/a".to_string(),
+                    path: "/a/0".to_string(),
+                },
+            ),
+            (
+                Op::Test {
+                    path: "/b".to_string(),
+                    value: json!("y"),
+                },
+                PatchError::TestFailed {
+                    pointer: "/b".to_string(),
+                },
+            ),
+        ];
+        for (op, error) in cases {
+            let mut value = original.clone();
+            assert_eq!(apply(&mut value, &[add("/c", json!(1)), op]), Err(error));
+            assert_eq!(value, original);
+        }
+    }
+
+    #[test]
+    fn move_to_sibling_with_common_prefix() -> Result<(), PatchError> {
+        let mut value = json!({"a": 1});
+        let op = Op::Move {
+            from: "/a".to_string(),
+            path: "/ab".to_string(),
+        };
+        apply(&mut value, &[op])?;
+        assert_eq!(value, json!({"ab": 1}));
+        Ok(())
+    }
+
+    #[test]
+    fn diff_ops() {
+        let a = json!({"a": 1, "b": [1, 2, 3], "c": {"d/e": true}, "x": null});
+        let b = json!({"a": "1", "b": [1, 5], "c": {"d/e": true, "f": []}});
+        assert_eq!(
+            diff(&a, &b),
+            [
+                remove("/x"),
+                replace("/a", json!("1")),
+                replace("/b/1", json!(5)),
+                remove("/b/2"),
+                add("/c/f", json!([])),
+            ]
+        );
+        assert!(diff(&a, &a).is_empty());
+        assert_eq!(diff(&a, &json!(1)), [replace("", json!(1))]);
+    }
+
+    #[test]
+    fn applying_diff_gives_target() -> Result<(), PatchError> {
+        let corpus = [
+            json!(null),
+            json!(1),
+            json!("a"),
+            json!([]),
+            json!({}),
+            json!([1, 2, 3]),
+            json!([3, [4, 5], {"a": 6}]),
+            json!([[]]),
+            json!({"a": 1}),
+            json!({"a": {"b": {"c": [1, {"d": null}]}}, "e": "f"}),
+            json!({"a": {"b": {"c": [1, {"d": 2}, 3]}}, "g": true}),
+            json!({"a": [], "a/b": {"~": 1}}),
+            json!({"a": [1, 2, 3, 4, 5, 6], "a/b": {"~": "x"}}),
+            json!({"": [{"": {}}]}),
+        ];
+        for a in &corpus {
+            for b in &corpus {
+                let ops = diff(a, b);
+                let mut value = a.clone();
+                apply(&mut value, &ops)?;
+                assert_eq!(&value, b, "{:?} with {:?}", a, ops);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn ops_to_json() {
+        let ops = [
+            add("/a", json!([1])),
+            remove("/b"),
+            Op::Move {
+                from: "/c".to_string(),
+                path: "/d".to_string(),
+            },
+        ];
+        assert_eq!(
+            ops.to_json(),
+            json!([
+                {"op": "add", "path": "/a", "value": [1]},
+                {"op": "remove", "path": "/b"},
+                {"op": "move", "from": "/c", "path": "/d"},
+            ])
+        );
+    }
+
+    #[test]
+    fn invert_each_op() -> Result<(), PatchError> {
+        let doc = json!({"a": [1, 2], "b": {"c": 3}, "d": "x"});
+        let ops = [
+            add("/a/0", json!(0)),
+            add("/a/-", json!(9)),
+            add("/b/c", json!(4)),
+            remove("/d"),
+            replace("/a/1", json!(5)),
+            Op::Move {
+                from: "/b".to_string(),
+                path: "/a/-".to_string(),
+            },
+            Op::Copy {
+                from: "/a/0".to_string(),
+                path: "/e".to_string(),
+            },
+            Op::Test {
+                path: "/e".to_string(),
+                value: json!(0),
+            },
+        ];
+        for end in 0..=ops.len() {
+            let ops = &ops[..end];
+            let mut value = doc.clone();
+            apply(&mut value, ops)?;
+            apply(&mut value, &invert(&doc, ops)?)?;
+            assert_eq!(value, doc, "{:?}", ops);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn invert_overwrites() -> Result<(), PatchError> {
+        let doc = json!({"a": {"b": 1}, "c": [2]});
+        let ops = [
+            Op::Move {
+                from: "/a/b".to_string(),
+                path: "/c".to_string(),
+            },
+            Op::Copy {
+                from: "/c".to_string(),
+                path: "/a".to_string(),
+            },
+            add("", json!([doc.clone()])),
+            Op::Move {
+                from: "/0/a".to_string(),
+                path: "".to_string(),
+            },
+        ];
+        let mut value = doc.clone();
+        apply(&mut value, &ops)?;
+        assert_eq!(value, json!({"b": 1}));
+        apply(&mut value, &invert(&doc, &ops)?)?;
+        assert_eq!(value, doc);
+        Ok(())
+    }
+
+    #[test]
+    fn invert_fails_like_apply() {
+        let doc = json!({"a": 1});
+        let ops = [remove("/a"), remove("/a")];
+        assert_eq!(
+            invert(&doc, &ops),
+            Err(PatchError::NotFound {
+                pointer: "/a".to_string()
+            })
+        );
+    }
+
+    /// xorshift, to generate the same documents and patches on every run
+    struct Random(u64);
+
+    impl Random {
+        fn below(&mut self, n: usize) -> usize {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            (self.0 % n as u64) as usize
+        }
+
+        fn value(&mut self, depth: usize) -> Value {
+            match self.below(if depth == 0 { 3 } else { 5 }) {
+                0 => Value::Null,
+                1 => json!(self.below(10) as u64),
+                2 => json!(["a", "b/", "~c"][self.below(3)]),
+                3 => (0..self.below(4)).map(|_| self.value(depth - 1)).collect(),
+                _ => (0..self.below(4))
+                    .map(|i| (["x", "y", "z", "a/b"][i], self.value(depth - 1)))
+                    .collect(),
+            }
+        }
+
+        fn pick<'a>(&mut self, pointers: &'a [String]) -> &'a str {
+            &pointers[self.below(pointers.len())]
+        }
+
+        /// Operation on an existing node of `doc`, which may not apply
+        fn op(&mut self, doc: &Value) -> Op {
+            let mut pointers: Vec<_> = doc.iter_deep().map(|(pointer, _)| pointer).collect();
+            pointers.sort();
+            let from = self.pick(&pointers).to_string();
+            let parent = self.pick(&pointers);
+            let path = match self.below(3) {
+                0 => format!("{}/-", parent),
+                1 => format!("{}/{}", parent, self.below(3)),
+                _ => format!("{}/{}", parent, ["x", "a~1b", "new"][self.below(3)]),
+            };
+            match self.below(6) {
+                0 => Op::Add {
+                    path,
+                    value: self.value(1),
+                },
+                1 => Op::Remove { path: from },
+                2 => Op::Replace {
+                    path: from,
+                    value: self.value(1),
+                },
+                3 => Op::Move { from, path },
+                4 => Op::Copy { from, path },
+                _ => Op::Test {
+                    value: doc.pointer(&from).cloned().unwrap_or_default(),
+                    path: from,
+                },
+            }
+        }
+    }
+
+    #[test]
+    fn invert_generated_patches() -> Result<(), PatchError> {
+        let mut random = Random(0x2545_f491_4f6c_dd1d);
+        for _ in 0..200 {
+            let doc = random.value(3);
+            let mut ops = Vec::new();
+            let mut patched = doc.clone();
+            for _ in 0..8 {
+                let op = random.op(&patched);
+                if apply_op(&mut patched.clone(), &op).is_ok() {
+                    apply_op(&mut patched, &op)?;
+                    ops.push(op);
+                }
+            }
+            let mut value = doc.clone();
+            apply(&mut value, &ops)?;
+            assert_eq!(value, patched);
+            apply(&mut value, &invert(&doc, &ops)?)?;
+            assert_eq!(value, doc, "{:?}", ops);
+        }
+        Ok(())
+    }
+}
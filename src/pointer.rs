@@ -0,0 +1,516 @@
+use crate::error::PointerError;
+use crate::Value;
+use std::collections::HashMap;
+use std::mem;
+
+impl Value {
+    /// Look up a value by [JSON Pointer](https://www.rfc-editor.org/rfc/rfc6901)
+    ///
+    /// `""` points at the whole value and `/a/0` at the first element of the
+    /// member `a`. `~1` and `~0` in a token stand for `/` and `~`. Array
+    /// indices are decimal without leading zeros.
+    ///
+    /// Returns `None` for a malformed pointer or when nothing is there.
+    ///
+    /// ```rust
+    /// use wson::{parse, Value};
+    /// # use std::error;
+    /// # fn main() -> Result<(), Box<dyn error::Error>> {
+    ///
+    ///
+    /// let value = parse("{\"data\": {\"items\": [{\"name\": \"a\"}, {\"name\": \"b\"}]}}")?;
+    /// assert_eq!(value.pointer("/data/items/1/name"), Some(&Value::String("b".to_string())));
+    /// assert_eq!(value.pointer("/data/items/2"), None);
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn pointer(&self, pointer: &str) -> Option<&Value> {
+        tokens(pointer)?.try_fold(self, |value, token| match value {
+            Value::Object(members) => members.get(&unescape(token)?),
+            Value::Array(elements) => elements.get(array_index(token)?),
+            _ => None,
+        })
+    }
+
+    /// Mutable value at a JSON Pointer, resolved the same way as `pointer`
+    ///
+    /// Missing members or elements are not created.
+    ///
+    /// ```rust
+    /// use wson::number::Number;
+    /// use wson::{parse, Value};
+    /// # use std::error;
+    /// # fn main() -> Result<(), Box<dyn error::Error>> {
+    ///
+    ///
+    /// let mut value = parse("{\"settings\": {\"retries\": 3}}")?;
+    /// if let Some(retries) = value.pointer_mut("/settings/retries") {
+    ///     *retries = Value::Number(Number::PositiveInteger(5));
+    /// }
+    /// assert_eq!(value, parse("{\"settings\": {\"retries\": 5}}")?);
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn pointer_mut(&mut self, pointer: &str) -> Option<&mut Value> {
+        tokens(pointer)?.try_fold(self, |value, token| match value {
+            Value::Object(members) => members.get_mut(&unescape(token)?),
+            Value::Array(elements) => elements.get_mut(array_index(token)?),
+            _ => None,
+        })
+    }
+
+    /// Put `value` at a JSON Pointer and return the value it replaced
+    ///
+    /// Missing members along the way are created as objects, and `null` is
+    /// turned into an object. The token `-`, or the length of an array,
+    /// appends to the array.
+    ///
+    /// It fails without changing anything when the pointer is malformed, an
+    /// array index is out of range, or the path goes through a string,
+    /// number or boolean.
+    ///
+    /// ```rust
+    /// use wson::{parse, Value};
+    /// # use std::error;
+    /// # fn main() -> Result<(), Box<dyn error::Error>> {
+    ///
+    ///
+    /// let mut value = Value::Null;
+    /// value.insert_pointer("/a/b", Value::Bool(true))?;
+    /// value.insert_pointer("/a/c", Value::Array(vec![]))?;
+    /// value.insert_pointer("/a/c/-", Value::Null)?;
+    /// assert_eq!(value, parse("{\"a\": {\"b\": true, \"c\": [null]}}")?);
+    ///
+    /// let replaced = value.insert_pointer("/a/b", Value::Bool(false))?;
+    /// assert_eq!(replaced, Some(Value::Bool(true)));
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn insert_pointer(
+        &mut self,
+        pointer: &str,
+        value: Value,
+    ) -> Result<Option<Value>, PointerError> {
+        let tokens: Vec<&str> = tokens(pointer)
+            .ok_or(PointerError::InvalidPointer)?
+            .collect();
+        if tokens.iter().any(|token| unescape(token).is_none()) {
+            return Err(PointerError::InvalidPointer);
+        }
+        let (last, parents) = match tokens.split_last() {
+            Some(split) => split,
+            None => return Ok(Some(mem::replace(self, value))),
+        };
+
+        let mut target = self;
+        let mut end = 0;
+        for token in parents {
+            end += 1 + token.len();
+            target = child_or_insert(target, token, &pointer[..end])?;
+        }
+        if target.is_null() {
+            *target = Value::Object(HashMap::new());
+        }
+        match target {
+            Value::Object(members) => Ok(members.insert(unescape(last).unwrap_or_default(), value)),
+            Value::Array(elements) => match array_index(last) {
+                Some(index) if index < elements.len() => {
+                    Ok(Some(mem::replace(&mut elements[index], value)))
+                }
+                Some(index) if index == elements.len() => {
+                    elements.push(value);
+                    Ok(None)
+                }
+                None if *last == "-" => {
+                    elements.push(value);
+                    Ok(None)
+                }
+                _ => Err(PointerError::InvalidIndex {
+                    pointer: pointer.to_string(),
+                }),
+            },
+            _ => Err(PointerError::NotAContainer {
+                pointer: pointer.to_string(),
+            }),
+        }
+    }
+
+    /// Remove the member or element at a JSON Pointer and return it
+    ///
+    /// Elements after a removed array element shift down by one. Returns
+    /// `None` and leaves value as it is when nothing is there. The whole
+    /// value, `""`, can't be removed.
+    ///
+    /// ```rust
+    /// use wson::{parse, Value};
+    /// # use std::error;
+    /// # fn main() -> Result<(), Box<dyn error::Error>> {
+    ///
+    ///
+    /// let mut value = parse("{\"user\": {\"name\": \"a\", \"token\": \"secret\"}, \"ids\": [1, 2, 3]}")?;
+    /// assert_eq!(value.remove_pointer("/user/token"), Some(Value::String("secret".to_string())));
+    /// value.remove_pointer("/ids/0");
+    /// assert_eq!(value, parse("{\"user\": {\"name\": \"a\"}, \"ids\": [2, 3]}")?);
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn remove_pointer(&mut self, pointer: &str) -> Option<Value> {
+        let (parent, last) = pointer.rsplit_once('/')?;
+        match self.pointer_mut(parent)? {
+            Value::Object(members) => members.remove(&unescape(last)?),
+            Value::Array(elements) => {
+                let index = array_index(last).filter(|index| *index < elements.len())?;
+                Some(elements.remove(index))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Child of `value` at `token`, which ends `pointer`, created as `null` when
+/// missing
+fn child_or_insert<'v>(
+    value: &'v mut Value,
+    token: &str,
+    pointer: &str,
+) -> Result<&'v mut Value, PointerError> {
+    if value.is_null() {
+        *value = Value::Object(HashMap::new());
+    }
+    match value {
+        Value::Object(members) => Ok(members
+            .entry(unescape(token).unwrap_or_default())
+            .or_insert(Value::Null)),
+        Value::Array(elements) => {
+            let index = match array_index(token) {
+                Some(index) if index < elements.len() => index,
+                _ if token == "-" => {
+                    elements.push(Value::Null);
+                    elements.len() - 1
+                }
+                _ => {
+                    return Err(PointerError::InvalidIndex {
+                        pointer: pointer.to_string(),
+                    })
+                }
+            };
+            Ok(&mut elements[index])
+        }
+        _ => Err(PointerError::NotAContainer {
+            pointer: pointer.to_string(),
+        }),
+    }
+}
+
+/// Reference tokens of `pointer`, still escaped
+pub(crate) fn tokens(pointer: &str) -> Option<impl Iterator<Item = &str>> {
+    if pointer.is_empty() || pointer.starts_with('/') {
+        Some(pointer.split('/').skip(1))
+    } else {
+        None
+    }
+}
+
+/// Whether `pointer` starts with '/' or is empty, and every '~' in it is
+/// followed by '0' or '1'
+pub(crate) fn is_valid(pointer: &str) -> bool {
+    tokens(pointer).is_some_and(|mut tokens| tokens.all(|token| unescape(token).is_some()))
+}
+
+/// Replace `~` with `~0` and `/` with `~1`
+pub(crate) fn escape(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+/// Replace `~1` with `/` and `~0` with `~`, or `None` for any other `~`
+pub(crate) fn unescape(token: &str) -> Option<String> {
+    let mut unescaped = String::with_capacity(token.len());
+    let mut chars = token.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '~' => match chars.next() {
+                Some('0') => unescaped.push('~'),
+                Some('1') => unescaped.push('/'),
+                _ => return None,
+            },
+            c => unescaped.push(c),
+        }
+    }
+    Some(unescaped)
+}
+
+/// Array index in `token`, which is `0` or digits without a leading zero
+pub(crate) fn array_index(token: &str) -> Option<usize> {
+    if token.is_empty()
+        || !token.bytes().all(|b| b.is_ascii_digit())
+        || (token.len() > 1 && token.starts_with('0'))
+    {
+        return None;
+    }
+    token.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::number::Number;
+    use crate::parse;
+
+    // https://www.rfc-editor.org/rfc/rfc6901#section-5
+    const RFC_EXAMPLE: &str = "{
+      \"foo\": [\"bar\", \"baz\"],
+      \"\": 0,
+      \"a/b\": 1,
+      \"c%d\": 2,
+      \"e^f\": 3,
+      \"g|h\": 4,
+      \"i\\\\j\": 5,
+      \"k\\\"l\": 6,
+      \" \": 7,
+      \"m~n\": 8
+    }";
+
+    fn number(n: u64) -> Value {
+        Value::Number(Number::PositiveInteger(n))
+    }
+
+    #[test]
+    fn rfc_examples() {
+        let value = parse(RFC_EXAMPLE).unwrap();
+        assert_eq!(value.pointer(""), Some(&value));
+        assert_eq!(
+            value.pointer("/foo"),
+            Some(&Value::Array(vec![
+                Value::String("bar".to_string()),
+                Value::String("baz".to_string())
+            ]))
+        );
+        assert_eq!(
+            value.pointer("/foo/0"),
+            Some(&Value::String("bar".to_string()))
+        );
+        assert_eq!(value.pointer("/"), Some(&number(0)));
+        assert_eq!(value.pointer("/a~1b"), Some(&number(1)));
+        assert_eq!(value.pointer("/c%d"), Some(&number(2)));
+        assert_eq!(value.pointer("/e^f"), Some(&number(3)));
+        assert_eq!(value.pointer("/g|h"), Some(&number(4)));
+        assert_eq!(value.pointer("/ "), Some(&number(7)));
+        assert_eq!(value.pointer("/m~0n"), Some(&number(8)));
+    }
+
+    #[test]
+    fn escaped_keys() {
+        let value = parse(r#"{"i\\j": 5, "k\"l": 6, "~1": 9}"#).unwrap();
+        assert_eq!(value.pointer("/i\\j"), Some(&number(5)));
+        assert_eq!(value.pointer("/k\"l"), Some(&number(6)));
+        assert_eq!(value.pointer("/~01"), Some(&number(9)));
+        assert_eq!(value.pointer("/~1"), None);
+    }
+
+    #[test]
+    fn missing_and_malformed() {
+        let value = parse(RFC_EXAMPLE).unwrap();
+        assert_eq!(value.pointer("foo"), None);
+        assert_eq!(value.pointer("/missing"), None);
+        assert_eq!(value.pointer("/foo/2"), None);
+        assert_eq!(value.pointer("/foo/-"), None);
+        assert_eq!(value.pointer("/foo/01"), None);
+        assert_eq!(value.pointer("/foo/+1"), None);
+        assert_eq!(value.pointer("/foo/0/x"), None);
+        assert_eq!(value.pointer("/m~2n"), None);
+        assert_eq!(value.pointer("/m~"), None);
+    }
+
+    #[test]
+    fn pointer_mut_through_objects_and_arrays() {
+        let mut value = parse("{\"a\": [{\"b\": 1}, 2], \"c~d\": {}}").unwrap();
+        if let Some(b) = value.pointer_mut("/a/0/b") {
+            *b = Value::Bool(true);
+        }
+        if let Some(element) = value.pointer_mut("/a/1") {
+            *element = Value::Null;
+        }
+        if let Some(Value::Object(members)) = value.pointer_mut("/c~0d") {
+            members.insert("e".to_string(), number(3));
+        }
+        assert_eq!(
+            Some(value),
+            parse("{\"a\": [{\"b\": true}, null], \"c~d\": {\"e\": 3}}").ok()
+        );
+    }
+
+    #[test]
+    fn pointer_mut_matches_pointer() {
+        let mut value = parse(RFC_EXAMPLE).unwrap();
+        let original = value.clone();
+        for pointer in [
+            "",
+            "/",
+            "/foo",
+            "/foo/1",
+            "/foo/2",
+            "/foo/-",
+            "/foo/01",
+            "/a~1b",
+            "/m~0n",
+            "/m~n",
+            "/missing/x",
+            "foo",
+        ] {
+            let expected = original.pointer(pointer).cloned();
+            assert_eq!(
+                value.pointer_mut(pointer).map(|v| v.clone()),
+                expected,
+                "{}",
+                pointer
+            );
+        }
+        assert_eq!(value, original);
+    }
+
+    #[test]
+    fn dangling_pointer_mut_leaves_value() {
+        let mut value = parse("{\"a\": {\"b\": [1]}}").unwrap();
+        let original = value.clone();
+        assert_eq!(value.pointer_mut("/a/x/y"), None);
+        assert_eq!(value.pointer_mut("/a/b/1"), None);
+        assert_eq!(value.pointer_mut("/a/b/0/c"), None);
+        assert_eq!(value, original);
+    }
+
+    #[test]
+    fn insert_pointer_builds_objects_from_null() -> Result<(), PointerError> {
+        let mut value = Value::Null;
+        assert_eq!(value.insert_pointer("/a/b~1c/d~0", number(1))?, None);
+        assert_eq!(value.pointer("/a/b~1c/d~0"), Some(&number(1)));
+        assert_eq!(
+            value,
+            Value::Object(HashMap::from([(
+                "a".to_string(),
+                Value::Object(HashMap::from([(
+                    "b/c".to_string(),
+                    Value::Object(HashMap::from([("d~".to_string(), number(1))]))
+                )]))
+            )]))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn insert_pointer_into_arrays() -> Result<(), PointerError> {
+        let mut value = parse("{\"a\": [1]}").unwrap();
+        assert_eq!(value.insert_pointer("/a/-", number(2))?, None);
+        assert_eq!(value.insert_pointer("/a/2", number(3))?, None);
+        assert_eq!(value.insert_pointer("/a/0", number(0))?, Some(number(1)));
+        assert_eq!(value.insert_pointer("/a/-/b", Value::Bool(true))?, None);
+        assert_eq!(Some(value), parse("{\"a\": [0, 2, 3, {\"b\": true}]}").ok());
+        Ok(())
+    }
+
+    #[test]
+    fn insert_pointer_replaces() -> Result<(), PointerError> {
+        let mut value = parse("{\"a\": {\"b\": 1}}").unwrap();
+        assert_eq!(value.insert_pointer("/a/b", Value::Null)?, Some(number(1)));
+        assert_eq!(value.insert_pointer("/a/b/c", Value::Bool(true))?, None);
+        assert_eq!(
+            value.insert_pointer("", Value::Bool(false))?,
+            parse("{\"a\": {\"b\": {\"c\": true}}}").ok()
+        );
+        assert_eq!(value, Value::Bool(false));
+        Ok(())
+    }
+
+    #[test]
+    fn insert_pointer_errors() {
+        let mut value = parse("{\"name\": \"x\", \"list\": [1]}").unwrap();
+        let original = value.clone();
+        assert_eq!(
+            value.insert_pointer("/name/first", Value::Null),
+            Err(PointerError::NotAContainer {
+                pointer: "/name/first".to_string()
+            })
+        );
+        assert_eq!(
+            value.insert_pointer("/list/0/a/b", Value::Null),
+            Err(PointerError::NotAContainer {
+                pointer: "/list/0/a".to_string()
+            })
+        );
+        assert_eq!(
+            value.insert_pointer("/list/2", Value::Null),
+            Err(PointerError::InvalidIndex {
+                pointer: "/list/2".to_string()
+            })
+        );
+        assert_eq!(
+            value.insert_pointer("/list/01/a", Value::Null),
+            Err(PointerError::InvalidIndex {
+                pointer: "/list/01".to_string()
+            })
+        );
+        assert_eq!(
+            value.insert_pointer("/new/a~2", Value::Null),
+            Err(PointerError::InvalidPointer)
+        );
+        assert_eq!(
+            value.insert_pointer("new", Value::Null),
+            Err(PointerError::InvalidPointer)
+        );
+        assert_eq!(value, original);
+    }
+
+    #[test]
+    fn remove_pointer_array_elements() {
+        let mut value = parse("{\"a\": [1, 2, 3]}").unwrap();
+        assert_eq!(value.remove_pointer("/a/2"), Some(number(3)));
+        assert_eq!(value.remove_pointer("/a/2"), None);
+        assert_eq!(value.remove_pointer("/a/0"), Some(number(1)));
+        assert_eq!(value.pointer("/a/0"), Some(&number(2)));
+        assert_eq!(value.remove_pointer("/a/-"), None);
+        assert_eq!(Some(value), parse("{\"a\": [2]}").ok());
+    }
+
+    #[test]
+    fn remove_pointer_nested_keys() {
+        let mut value = parse(
+            "{\"user\": {\"auth\": {\"token\": \"x\", \"kind\": \"bearer\"}}, \"a/b\": 1, \"m~n\": 2}",
+        )
+        .unwrap();
+        assert_eq!(
+            value.remove_pointer("/user/auth/token"),
+            Some(Value::String("x".to_string()))
+        );
+        assert_eq!(value.remove_pointer("/a~1b"), Some(number(1)));
+        assert_eq!(value.remove_pointer("/m~0n"), Some(number(2)));
+        assert_eq!(
+            Some(value),
+            parse("{\"user\": {\"auth\": {\"kind\": \"bearer\"}}}").ok()
+        );
+    }
+
+    #[test]
+    fn remove_missing_pointer_leaves_value() {
+        let mut value = parse(RFC_EXAMPLE).unwrap();
+        let original = value.clone();
+        for pointer in [
+            "", "foo", "/missing", "/foo/2", "/foo/01", "/foo/0/x", "/m~2n",
+        ] {
+            assert_eq!(value.remove_pointer(pointer), None, "{}", pointer);
+        }
+        assert_eq!(value, original);
+    }
+
+    #[test]
+    fn array_indices() {
+        assert_eq!(array_index("0"), Some(0));
+        assert_eq!(array_index("10"), Some(10));
+        assert_eq!(array_index("00"), None);
+        assert_eq!(array_index(""), None);
+        assert_eq!(array_index("-"), None);
+        assert_eq!(array_index("99999999999999999999999"), None);
+    }
+}
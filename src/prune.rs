@@ -0,0 +1,180 @@
+//! Removing nulls and empty values from a tree
+
+use crate::Value;
+
+/// Options to control what `Value::prune` removes
+///
+/// By default only object members which are `null` are removed.
+///
+/// ```rust
+/// use wson::json;
+/// use wson::prune::PruneOptions;
+/// # fn main() {
+///
+///
+/// let mut value = json!({"a": null, "b": {"c": null}, "d": [null, ""], "e": ""});
+/// value.prune(&PruneOptions::new());
+/// assert_eq!(value, json!({"b": {}, "d": [null, ""], "e": ""}));
+///
+/// let mut value = json!({"a": null, "b": {"c": null}, "d": [null, ""], "e": ""});
+/// let options = PruneOptions::new()
+///     .empty_containers(true)
+///     .empty_strings(true)
+///     .array_elements(true);
+/// value.prune(&options);
+/// assert_eq!(value, json!({}));
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PruneOptions {
+    empty_containers: bool,
+    empty_strings: bool,
+    array_elements: bool,
+}
+
+impl PruneOptions {
+    /// Options to remove only `null` members
+    pub fn new() -> Self {
+        PruneOptions {
+            empty_containers: false,
+            empty_strings: false,
+            array_elements: false,
+        }
+    }
+
+    /// Also remove objects and arrays which are empty, including ones
+    /// emptied by pruning
+    pub fn empty_containers(mut self, empty_containers: bool) -> Self {
+        self.empty_containers = empty_containers;
+        self
+    }
+
+    /// Also remove empty strings
+    pub fn empty_strings(mut self, empty_strings: bool) -> Self {
+        self.empty_strings = empty_strings;
+        self
+    }
+
+    /// Remove array elements by the same rules as object members, shifting
+    /// later elements down
+    pub fn array_elements(mut self, array_elements: bool) -> Self {
+        self.array_elements = array_elements;
+        self
+    }
+
+    /// Whether `value`, with its children already pruned, is removed
+    fn removes(&self, value: &Value) -> bool {
+        match value {
+            Value::Null => true,
+            Value::String(s) => self.empty_strings && s.is_empty(),
+            Value::Object(members) => self.empty_containers && members.is_empty(),
+            Value::Array(elements) => self.empty_containers && elements.is_empty(),
+            _ => false,
+        }
+    }
+}
+
+impl Default for PruneOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Value {
+    /// Remove `null` members, and what else `options` says, everywhere in
+    /// value
+    ///
+    /// Children are pruned before their parent is checked, so a container
+    /// emptied by pruning is removed too when empty containers are. Value
+    /// itself is never removed, only its contents.
+    pub fn prune(&mut self, options: &PruneOptions) {
+        match self {
+            Value::Object(members) => members.retain(|_, member| {
+                member.prune(options);
+                !options.removes(member)
+            }),
+            Value::Array(elements) => elements.retain_mut(|element| {
+                element.prune(options);
+                !(options.array_elements && options.removes(element))
+            }),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json;
+
+    fn document() -> Value {
+        json!({
+            "id": 1,
+            "name": "",
+            "profile": {"nickname": null, "avatar": {"url": null}},
+            "tags": [null, "a", "", {"b": null}, []],
+            "empty": {},
+        })
+    }
+
+    #[test]
+    fn prune_null_members() {
+        let mut value = document();
+        value.prune(&PruneOptions::new());
+        assert_eq!(
+            value,
+            json!({
+                "id": 1,
+                "name": "",
+                "profile": {"avatar": {}},
+                "tags": [null, "a", "", {}, []],
+                "empty": {},
+            })
+        );
+    }
+
+    #[test]
+    fn prune_cascades_empty_containers() {
+        let mut value = document();
+        value.prune(&PruneOptions::new().empty_containers(true));
+        assert_eq!(
+            value,
+            json!({"id": 1, "name": "", "tags": [null, "a", "", {}, []]})
+        );
+    }
+
+    #[test]
+    fn prune_array_elements() {
+        let mut value = document();
+        value.prune(
+            &PruneOptions::new()
+                .empty_containers(true)
+                .array_elements(true),
+        );
+        assert_eq!(value, json!({"id": 1, "name": "", "tags": ["a", ""]}));
+
+        let mut value = document();
+        let options = PruneOptions::new()
+            .empty_containers(true)
+            .empty_strings(true)
+            .array_elements(true);
+        value.prune(&options);
+        assert_eq!(value, json!({"id": 1, "tags": ["a"]}));
+    }
+
+    #[test]
+    fn prune_keeps_root() {
+        let options = PruneOptions::new()
+            .empty_containers(true)
+            .empty_strings(true)
+            .array_elements(true);
+        for mut value in [json!(null), json!(""), json!({}), json!([[null]])] {
+            let expected = match &value {
+                Value::Array(_) => json!([]),
+                value => value.clone(),
+            };
+            value.prune(&options);
+            assert_eq!(value, expected);
+        }
+    }
+}
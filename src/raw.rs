@@ -0,0 +1,86 @@
+use crate::error::ParseError;
+use crate::format::minify;
+use std::fmt;
+
+/// Json text checked once to be valid, written as it is by `JsonWriter::raw`
+///
+/// ```rust
+/// use wson::raw::RawValue;
+/// use wson::JsonWriter;
+/// # use std::error;
+/// # fn main() -> Result<(), Box<dyn error::Error>> {
+///
+///
+/// let cached = RawValue::from_string("{\"id\": 1}".to_string())?;
+///
+/// let mut writer = JsonWriter::new(Vec::new());
+/// writer.begin_array()?;
+/// writer.raw(&cached)?;
+/// writer.raw(&cached)?;
+/// writer.end()?;
+/// assert_eq!(writer.finish()?, b"[{\"id\": 1},{\"id\": 1}]");
+///
+/// // invalid json is rejected up front
+/// assert!(RawValue::from_string("{\"id\": }".to_string()).is_err());
+///
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct RawValue(String);
+
+impl RawValue {
+    /// Check `json` is a single valid json value
+    pub fn from_string(json: String) -> Result<Self, ParseError> {
+        minify(&json)?;
+        Ok(RawValue(json))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+impl fmt::Display for RawValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ParseErrorKind;
+
+    #[test]
+    fn valid_fragments() -> Result<(), ParseError> {
+        for json in [
+            "null",
+            " [1, 2] ",
+            "{\"a\": {\"b\": []}}",
+            "\"str\"",
+            "-1.5e3",
+        ] {
+            assert_eq!(RawValue::from_string(json.to_string())?.as_str(), json);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_fragments() {
+        for json in ["", "[1,", "{\"a\" 1}", "nul", "1 2", "[] x"] {
+            assert!(RawValue::from_string(json.to_string()).is_err(), "{}", json);
+        }
+    }
+
+    #[test]
+    fn error_position() {
+        let error = RawValue::from_string("[1, }".to_string()).unwrap_err();
+        assert_eq!(error.kind(), &ParseErrorKind::Expected("value"));
+        assert_eq!(error.offset(), 4);
+    }
+}
@@ -0,0 +1,147 @@
+use crate::Value;
+
+/// Replacement `Value::redact_keys` puts in place of redacted values
+pub const REDACTED: &str = "[REDACTED]";
+
+impl Value {
+    /// Replace the value at each JSON Pointer in `pointers` with `replacement`
+    ///
+    /// Pointers where nothing is, or which are malformed, are skipped, since
+    /// what's in a payload varies.
+    ///
+    /// ```rust
+    /// use wson::{json, Value, REDACTED};
+    /// # fn main() {
+    ///
+    ///
+    /// let mut event = json!({"user": {"email": "a@example.com", "id": 1}, "cards": [{"number": "4242"}]});
+    /// event.redact(&["/user/email", "/cards/0/number", "/missing"], Value::from(REDACTED));
+    /// assert_eq!(
+    ///     event,
+    ///     json!({"user": {"email": "[REDACTED]", "id": 1}, "cards": [{"number": "[REDACTED]"}]})
+    /// );
+    /// # }
+    /// ```
+    pub fn redact(&mut self, pointers: &[&str], replacement: Value) {
+        for pointer in pointers {
+            if let Some(value) = self.pointer_mut(pointer) {
+                *value = replacement.clone();
+            }
+        }
+    }
+
+    /// Replace the value of every member named in `keys`, anywhere in value,
+    /// with `"[REDACTED]"`
+    ///
+    /// ```rust
+    /// use wson::json;
+    /// # fn main() {
+    ///
+    ///
+    /// let mut users = json!([{"name": "a", "password": "x"}, {"name": "b", "auth": {"password": "y"}}]);
+    /// users.redact_keys(&["password"]);
+    /// assert_eq!(
+    ///     users,
+    ///     json!([{"name": "a", "password": "[REDACTED]"}, {"name": "b", "auth": {"password": "[REDACTED]"}}])
+    /// );
+    /// # }
+    /// ```
+    pub fn redact_keys(&mut self, keys: &[&str]) {
+        match self {
+            Value::Object(members) => {
+                for (key, member) in members {
+                    if keys.contains(&key.as_str()) {
+                        *member = Value::from(REDACTED);
+                    } else {
+                        member.redact_keys(keys);
+                    }
+                }
+            }
+            Value::Array(elements) => {
+                for element in elements {
+                    element.redact_keys(keys);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json;
+
+    fn document() -> Value {
+        json!({
+            "users": [
+                {"name": "a", "ssn": "1", "contact": {"email": "a@x", "phone": "2"}},
+                {"name": "b", "ssn": "3"},
+            ],
+            "request": {"password": {"plain": "x"}, "path": "/login"},
+        })
+    }
+
+    #[test]
+    fn redact_pointers_in_arrays_of_objects() {
+        let mut value = document();
+        value.redact(
+            &[
+                "/users/0/ssn",
+                "/users/1/ssn",
+                "/users/0/contact",
+                "/users/2/ssn",
+            ],
+            Value::Null,
+        );
+        assert_eq!(
+            value,
+            json!({
+                "users": [
+                    {"name": "a", "ssn": null, "contact": null},
+                    {"name": "b", "ssn": null},
+                ],
+                "request": {"password": {"plain": "x"}, "path": "/login"},
+            })
+        );
+    }
+
+    #[test]
+    fn missing_and_malformed_pointers_are_ignored() {
+        let mut value = document();
+        value.redact(
+            &[
+                "/nothing",
+                "users",
+                "/users/x",
+                "/request/path/0",
+                "/users/~2",
+            ],
+            json!(0),
+        );
+        assert_eq!(value, document());
+    }
+
+    #[test]
+    fn redact_keys_anywhere() {
+        let mut value = document();
+        value.redact_keys(&["ssn", "password", "phone"]);
+        assert_eq!(
+            value,
+            json!({
+                "users": [
+                    {"name": "a", "ssn": REDACTED, "contact": {"email": "a@x", "phone": REDACTED}},
+                    {"name": "b", "ssn": REDACTED},
+                ],
+                "request": {"password": REDACTED, "path": "/login"},
+            })
+        );
+    }
+
+    #[test]
+    fn redact_keys_leaves_values_named_like_keys() {
+        let mut value = json!(["ssn", {"list": ["password"]}]);
+        value.redact_keys(&["ssn", "password"]);
+        assert_eq!(value, json!(["ssn", {"list": ["password"]}]));
+    }
+}
@@ -0,0 +1,266 @@
+use crate::pointer::{array_index, tokens, unescape};
+use crate::Value;
+use std::collections::{BTreeMap, HashMap};
+
+impl Value {
+    /// Copy of an object with only the members named in `keys`
+    ///
+    /// Keys which are missing are left out. Values other than objects are
+    /// returned as they are, since they have no members to select.
+    ///
+    /// ```rust
+    /// use wson::json;
+    /// # fn main() {
+    ///
+    ///
+    /// let user = json!({"id": 1, "name": "a", "password": "x"});
+    /// assert_eq!(user.pick(&["id", "name", "email"]), json!({"id": 1, "name": "a"}));
+    /// assert_eq!(user.omit(&["password"]), json!({"id": 1, "name": "a"}));
+    /// # }
+    /// ```
+    pub fn pick(&self, keys: &[&str]) -> Value {
+        match self {
+            Value::Object(members) => keys
+                .iter()
+                .filter_map(|key| members.get_key_value(*key))
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+            value => value.clone(),
+        }
+    }
+
+    /// Copy of an object without the members named in `keys`
+    ///
+    /// Values other than objects are returned as they are, like `pick`.
+    pub fn omit(&self, keys: &[&str]) -> Value {
+        match self {
+            Value::Object(members) => members
+                .iter()
+                .filter(|(key, _)| !keys.contains(&key.as_str()))
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+            value => value.clone(),
+        }
+    }
+
+    /// Copy of value with only what is at `pointers` and on the way there
+    ///
+    /// Array elements keep their positions, with `null` in place of the
+    /// elements before them which weren't selected. Pointers where nothing
+    /// is, or which are malformed, are skipped. When one pointer is inside
+    /// another, the whole value at the outer one is kept. When nothing is
+    /// selected, an object or array gives an empty one and anything else
+    /// gives `null`.
+    ///
+    /// ```rust
+    /// use wson::json;
+    /// # fn main() {
+    ///
+    ///
+    /// let hook = json!({"id": 1, "items": [{"sku": "a", "qty": 1}, {"sku": "b", "qty": 2}], "raw": "..."});
+    /// assert_eq!(
+    ///     hook.project(&["/id", "/items/1/sku"]),
+    ///     json!({"id": 1, "items": [null, {"sku": "b"}]})
+    /// );
+    /// assert_eq!(
+    ///     hook.project_compact(&["/id", "/items/1/sku"]),
+    ///     json!({"id": 1, "items": [{"sku": "b"}]})
+    /// );
+    /// # }
+    /// ```
+    pub fn project(&self, pointers: &[&str]) -> Value {
+        projection(self, pointers).build(self, false)
+    }
+
+    /// Copy of value with only what is at `pointers`, like `project`, but
+    /// with the selected elements of each array packed together in order
+    pub fn project_compact(&self, pointers: &[&str]) -> Value {
+        projection(self, pointers).build(self, true)
+    }
+}
+
+/// What `project` keeps of a value
+enum Selection<'a> {
+    Nothing,
+    All,
+    Members(HashMap<&'a str, Selection<'a>>),
+    Elements(BTreeMap<usize, Selection<'a>>),
+}
+
+fn projection<'a>(value: &'a Value, pointers: &[&str]) -> Selection<'a> {
+    let mut selection = Selection::Nothing;
+    for pointer in pointers {
+        if value.pointer(pointer).is_none() {
+            continue;
+        }
+        let path =
+            tokens(pointer).and_then(|tokens| tokens.map(unescape).collect::<Option<Vec<_>>>());
+        if let Some(path) = path {
+            selection.select(value, &path);
+        }
+    }
+    selection
+}
+
+impl<'a> Selection<'a> {
+    /// Select `path` inside `value`, which must be there
+    fn select(&mut self, value: &'a Value, path: &[String]) {
+        let (token, rest) = match path.split_first() {
+            Some(split) => split,
+            None => {
+                *self = Selection::All;
+                return;
+            }
+        };
+        match (value, &mut *self) {
+            // the whole value is already selected
+            (_, Selection::All) => {}
+            (Value::Object(members), selection) => {
+                if let Some((key, member)) = members.get_key_value(token) {
+                    if let Selection::Nothing = selection {
+                        *selection = Selection::Members(HashMap::new());
+                    }
+                    if let Selection::Members(selected) = selection {
+                        selected
+                            .entry(key.as_str())
+                            .or_insert(Selection::Nothing)
+                            .select(member, rest);
+                    }
+                }
+            }
+            (Value::Array(elements), selection) => {
+                if let Some(index) = array_index(token).filter(|index| *index < elements.len()) {
+                    if let Selection::Nothing = selection {
+                        *selection = Selection::Elements(BTreeMap::new());
+                    }
+                    if let Selection::Elements(selected) = selection {
+                        selected
+                            .entry(index)
+                            .or_insert(Selection::Nothing)
+                            .select(&elements[index], rest);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn build(self, value: &Value, compact: bool) -> Value {
+        match (self, value) {
+            (Selection::All, value) => value.clone(),
+            (Selection::Members(selected), Value::Object(members)) => selected
+                .into_iter()
+                .map(|(key, selection)| (key.to_string(), selection.build(&members[key], compact)))
+                .collect(),
+            (Selection::Elements(selected), Value::Array(elements)) => {
+                let mut projected = Vec::new();
+                for (index, selection) in selected {
+                    if !compact {
+                        projected.resize(index, Value::Null);
+                    }
+                    projected.push(selection.build(&elements[index], compact));
+                }
+                Value::Array(projected)
+            }
+            (_, Value::Object(_)) => Value::Object(HashMap::new()),
+            (_, Value::Array(_)) => Value::Array(Vec::new()),
+            _ => Value::Null,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{json, Value};
+
+    #[test]
+    fn pick_keys() {
+        let value = json!({"a": 1, "b": {"c": 2}, "d": [3]});
+        assert_eq!(value.pick(&["a", "b"]), json!({"a": 1, "b": {"c": 2}}));
+        assert_eq!(
+            value.pick(&["a", "a", "b", "a"]),
+            json!({"a": 1, "b": {"c": 2}})
+        );
+        assert_eq!(value.pick(&["x", "d"]), json!({"d": [3]}));
+        assert_eq!(value.pick(&[]), json!({}));
+        // not nested
+        assert_eq!(value.pick(&["c"]), json!({}));
+    }
+
+    #[test]
+    fn omit_keys() {
+        let value = json!({"a": 1, "b": {"a": 2}, "d": [3]});
+        assert_eq!(value.omit(&["a"]), json!({"b": {"a": 2}, "d": [3]}));
+        assert_eq!(value.omit(&["a", "a", "x", "d"]), json!({"b": {"a": 2}}));
+        assert_eq!(value.omit(&[]), value);
+    }
+
+    #[test]
+    fn pick_and_omit_overlap() {
+        let value = json!({"a": 1, "b": 2, "c": 3});
+        let keys = ["a", "b"];
+        let mut both = value.pick(&keys);
+        both.merge(value.omit(&keys));
+        assert_eq!(both, value);
+    }
+
+    #[test]
+    fn non_objects_are_cloned() {
+        for value in [
+            json!([{"a": 1}]),
+            json!("a"),
+            json!(1),
+            json!(null),
+            json!(true),
+        ] {
+            assert_eq!(value.pick(&["a"]), value);
+            assert_eq!(value.omit(&["a"]), value);
+        }
+    }
+
+    #[test]
+    fn project_array_elements() {
+        let value = json!({"list": [{"a": 1, "b": 2}, {"a": 3}, {"a": 4, "b": 5}], "x": 0});
+        let pointers = ["/list/2/b", "/list/0/a"];
+        assert_eq!(
+            value.project(&pointers),
+            json!({"list": [{"a": 1}, null, {"b": 5}]})
+        );
+        assert_eq!(
+            value.project_compact(&pointers),
+            json!({"list": [{"a": 1}, {"b": 5}]})
+        );
+        assert_eq!(json!([1, 2, 3]).project(&["/1"]), json!([null, 2]));
+        assert_eq!(
+            json!([1, 2, 3]).project_compact(&["/2", "/0"]),
+            json!([1, 3])
+        );
+    }
+
+    #[test]
+    fn broader_pointer_wins() {
+        let value = json!({"a": {"b": {"c": 1, "d": 2}, "e": [3, 4]}, "f": 5});
+        let expected = json!({"a": {"b": {"c": 1, "d": 2}}});
+        assert_eq!(value.project(&["/a/b/c", "/a/b"]), expected);
+        assert_eq!(value.project(&["/a/b", "/a/b/c"]), expected);
+        assert_eq!(value.project(&["/a/e/1", "/a", "/a/b/d"])["a"], value["a"]);
+        assert_eq!(value.project(&["/f", ""]), value);
+        assert_eq!(
+            value.project(&["/a/b/c", "/a/b/c"]),
+            json!({"a": {"b": {"c": 1}}})
+        );
+    }
+
+    #[test]
+    fn project_missing_pointers() {
+        let value = json!({"a": [1], "b": "x", "a/b": 2});
+        assert_eq!(
+            value.project(&["/a/1", "/b/0", "/c", "a", "/a~2"]),
+            json!({})
+        );
+        assert_eq!(value.project(&["/a/1", "/a~1b"]), json!({"a/b": 2}));
+        assert_eq!(json!([1]).project(&[]), json!([]));
+        assert_eq!(json!("x").project(&["/0"]), Value::Null);
+        assert_eq!(json!("x").project(&[""]), json!("x"));
+    }
+}
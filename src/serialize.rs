@@ -0,0 +1,1396 @@
+use crate::error::SerializeError;
+use crate::null::Null;
+use crate::number::Number;
+use crate::string::JsonString;
+use crate::Value;
+use std::fmt::{self, Write};
+use std::io;
+
+/// Options to control the layout of serialized json
+///
+/// The default is compact output. Setting `indent` puts every member and
+/// element on its own line.
+///
+/// ```rust
+/// use wson::number::Number;
+/// use wson::serialize::SerializeOptions;
+/// use wson::Value;
+/// # use std::error;
+/// # fn main() -> Result<(), Box<dyn error::Error>> {
+///
+///
+/// let value = Value::Array(vec![Value::Number(Number::PositiveInteger(1)), Value::Null]);
+///
+/// // compact
+/// assert_eq!(value.serialize_with(&SerializeOptions::new())?, "[1,null]");
+///
+/// // a space after ','
+/// let options = SerializeOptions::new().space_after_comma(true);
+/// assert_eq!(value.serialize_with(&options)?, "[1, null]");
+///
+/// // indented with a tab, separated with CRLF and ended with a newline
+/// let options = SerializeOptions::new()
+///     .indent("\t")
+///     .newline("\r\n")
+///     .trailing_newline(true);
+/// assert_eq!(value.serialize_with(&options)?, "[\r\n\t1,\r\n\tnull\r\n]\r\n");
+///
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SerializeOptions {
+    indent: Option<String>,
+    newline: String,
+    space_after_colon: bool,
+    space_after_comma: bool,
+    trailing_newline: bool,
+    sort_keys: bool,
+    escape_non_ascii: bool,
+    escape_js: bool,
+    escape_html: bool,
+    float_precision: Option<usize>,
+    big_int_as_string: bool,
+    non_finite_floats: NonFiniteFloats,
+}
+
+/// How NaN and Infinity, which json has no syntax for, are serialized
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NonFiniteFloats {
+    /// Fail with `SerializeError::NonFiniteFloat`
+    Error,
+    /// Write `null` like `JSON.stringify`
+    Null,
+    /// Write `NaN`, `Infinity` and `-Infinity` as JSON5 does. The output is
+    /// not valid json.
+    Literal,
+}
+
+impl SerializeOptions {
+    /// Options for compact json
+    pub fn new() -> Self {
+        SerializeOptions {
+            indent: None,
+            newline: "\n".to_string(),
+            space_after_colon: false,
+            space_after_comma: false,
+            trailing_newline: false,
+            sort_keys: false,
+            escape_non_ascii: false,
+            escape_js: false,
+            escape_html: false,
+            float_precision: None,
+            big_int_as_string: false,
+            non_finite_floats: NonFiniteFloats::Error,
+        }
+    }
+
+    /// Options for json indented with `indent` spaces, used by `to_string_pretty`
+    pub fn pretty(indent: usize) -> Self {
+        Self::new()
+            .indent(&" ".repeat(indent))
+            .space_after_colon(true)
+    }
+
+    /// Put members and elements on their own lines indented with `indent` per depth
+    pub fn indent(mut self, indent: &str) -> Self {
+        self.indent = Some(indent.to_string());
+        self
+    }
+
+    /// Line separator used when indenting or writing a trailing newline
+    pub fn newline(mut self, newline: &str) -> Self {
+        self.newline = newline.to_string();
+        self
+    }
+
+    /// Write a space after ':' in objects
+    pub fn space_after_colon(mut self, space: bool) -> Self {
+        self.space_after_colon = space;
+        self
+    }
+
+    /// Write a space after ',' in objects and arrays.
+    /// Ignored when indenting, as ',' is followed by a newline.
+    pub fn space_after_comma(mut self, space: bool) -> Self {
+        self.space_after_comma = space;
+        self
+    }
+
+    /// End the output with a newline
+    pub fn trailing_newline(mut self, trailing_newline: bool) -> Self {
+        self.trailing_newline = trailing_newline;
+        self
+    }
+
+    /// Write object members ordered by the bytes of their keys, so the same
+    /// value is always serialized into the same json
+    pub fn sort_keys(mut self, sort_keys: bool) -> Self {
+        self.sort_keys = sort_keys;
+        self
+    }
+
+    /// Write characters above U+007F as `\uXXXX` escapes so the output is
+    /// ASCII only. Characters outside the BMP become surrogate pairs.
+    pub fn escape_non_ascii(mut self, escape_non_ascii: bool) -> Self {
+        self.escape_non_ascii = escape_non_ascii;
+        self
+    }
+
+    /// Write '/' as `\/` so `</script>` can't appear in the output, and
+    /// U+2028 and U+2029 as `\u2028` and `\u2029` since they end string
+    /// literals in older JavaScript
+    pub fn escape_js(mut self, escape_js: bool) -> Self {
+        self.escape_js = escape_js;
+        self
+    }
+
+    /// Write '<', '>' and '&' as `\u003c`, `\u003e` and `\u0026` so the
+    /// output can be embedded in HTML
+    pub fn escape_html(mut self, escape_html: bool) -> Self {
+        self.escape_html = escape_html;
+        self
+    }
+
+    /// Write floats with exactly `precision` decimal places instead of the
+    /// shortest digits that parse back into the same `f64`.
+    /// With `Some(0)` integral floats are written like integers, so they are
+    /// parsed as `Number::PositiveInteger` or `Number::NegativeInteger`.
+    pub fn float_precision(mut self, precision: Option<usize>) -> Self {
+        self.float_precision = precision;
+        self
+    }
+
+    /// Write integers outside of ±(2^53 - 1), which JavaScript can't hold
+    /// exactly, as strings like `"18446744073709551615"`
+    pub fn big_int_as_string(mut self, big_int_as_string: bool) -> Self {
+        self.big_int_as_string = big_int_as_string;
+        self
+    }
+
+    /// How NaN and Infinity are serialized, `NonFiniteFloats::Error` by default
+    pub fn non_finite_floats(mut self, non_finite_floats: NonFiniteFloats) -> Self {
+        self.non_finite_floats = non_finite_floats;
+        self
+    }
+}
+
+/// Largest integer JavaScript numbers hold exactly (`Number.MAX_SAFE_INTEGER`)
+const MAX_SAFE_INTEGER: u128 = (1 << 53) - 1;
+
+impl Default for SerializeOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Value {
+    /// Serialize value into json laid out by `options`
+    pub fn serialize_with(&self, options: &SerializeOptions) -> Result<String, SerializeError> {
+        let mut output = String::new();
+        Serializer::new(&mut output, options).serialize(self)?;
+        Ok(output)
+    }
+
+    /// Serialize value into json laid out by `options` and write it to `writer`
+    pub fn serialize_with_writer<W: io::Write>(
+        &self,
+        writer: W,
+        options: &SerializeOptions,
+    ) -> Result<(), SerializeError> {
+        let mut writer = IoWriter::new(writer);
+        let result = Serializer::new(&mut writer, options).serialize(self);
+        writer.finish(result)
+    }
+}
+
+/// Serialize value into compact json
+///
+/// NaN and Infinity can't be serialized and fail with
+/// `SerializeError::NonFiniteFloat`. Use `NonFiniteFloats` to change it.
+///
+/// ```rust
+/// use wson::number::Number;
+/// use wson::{parse, to_string, Value};
+/// use std::collections::HashMap;
+/// # use std::error;
+/// # fn main() -> Result<(), Box<dyn error::Error>> {
+///
+///
+/// // "3" will be serialized
+/// let json = to_string(&Value::Number(Number::PositiveInteger(3)))?;
+/// assert_eq!(json, "3");
+///
+/// // "\"say \\\"hi\\\"\"" will be serialized
+/// let json = to_string(&Value::String("say \"hi\"".to_string()))?;
+/// assert_eq!(json, "\"say \\\"hi\\\"\"");
+///
+/// // "[1,null,true]" will be serialized
+/// let json = to_string(&Value::Array(vec![
+///   Value::Number(Number::PositiveInteger(1)),
+///   Value::Null,
+///   Value::Bool(true),
+/// ]))?;
+/// assert_eq!(json, "[1,null,true]");
+///
+/// // the serialized json can be parsed again
+/// let value = Value::Object(HashMap::from([
+///   ("title".to_string(), Value::String("TITLE1".to_string())),
+///   ("revision".to_string(), Value::Number(Number::PositiveInteger(12)))
+/// ]));
+/// let json = to_string(&value)?;
+/// assert_eq!(parse(&json).ok(), Some(value));
+///
+/// # Ok(())
+/// # }
+/// ```
+pub fn to_string(value: &Value) -> Result<String, SerializeError> {
+    value.serialize_with(&SerializeOptions::new())
+}
+
+/// Serialize value into json indented with `indent` spaces
+///
+/// ```rust
+/// use wson::number::Number;
+/// use wson::{to_string_pretty, Value};
+/// use std::collections::HashMap;
+/// # use std::error;
+/// # fn main() -> Result<(), Box<dyn error::Error>> {
+///
+///
+/// // members and elements are written one per line
+/// let value = Value::Object(HashMap::from([(
+///   "menuitem".to_string(),
+///   Value::Array(vec![Value::Number(Number::PositiveInteger(1)), Value::Null]),
+/// )]));
+/// assert_eq!(
+///   to_string_pretty(&value, 2)?,
+///   "{\n  \"menuitem\": [\n    1,\n    null\n  ]\n}"
+/// );
+///
+/// // empty object and array are written on one line
+/// assert_eq!(to_string_pretty(&Value::Object(HashMap::new()), 2)?, "{}");
+/// assert_eq!(to_string_pretty(&Value::Array(vec![]), 2)?, "[]");
+///
+/// # Ok(())
+/// # }
+/// ```
+pub fn to_string_pretty(value: &Value, indent: usize) -> Result<String, SerializeError> {
+    value.serialize_with(&SerializeOptions::pretty(indent))
+}
+
+/// Serialize value into compact json and write it to `writer`
+///
+/// The output is streamed without building an intermediate `String`, so
+/// wrap unbuffered writers like `File` in a `BufWriter`.
+///
+/// ```rust
+/// use wson::number::Number;
+/// use wson::{to_writer, Value};
+/// # use std::error;
+/// # fn main() -> Result<(), Box<dyn error::Error>> {
+///
+///
+/// let mut output = Vec::new();
+/// to_writer(&mut output, &Value::Array(vec![Value::Number(Number::PositiveInteger(1))]))?;
+/// assert_eq!(output, b"[1]");
+///
+/// # Ok(())
+/// # }
+/// ```
+pub fn to_writer<W: io::Write>(writer: W, value: &Value) -> Result<(), SerializeError> {
+    value.serialize_with_writer(writer, &SerializeOptions::new())
+}
+
+/// Serialize value into json indented with `indent` spaces and write it to `writer`
+///
+/// ```rust
+/// use wson::number::Number;
+/// use wson::{to_writer_pretty, Value};
+/// # use std::error;
+/// # fn main() -> Result<(), Box<dyn error::Error>> {
+///
+///
+/// let mut output = Vec::new();
+/// to_writer_pretty(&mut output, &Value::Array(vec![Value::Number(Number::PositiveInteger(1))]), 2)?;
+/// assert_eq!(output, b"[\n  1\n]");
+///
+/// # Ok(())
+/// # }
+/// ```
+pub fn to_writer_pretty<W: io::Write>(
+    writer: W,
+    value: &Value,
+    indent: usize,
+) -> Result<(), SerializeError> {
+    value.serialize_with_writer(writer, &SerializeOptions::pretty(indent))
+}
+
+/// Adapter to write json into `io::Write` with the same code as `fmt::Write`.
+/// `fmt::Error` has no detail, so the underlying `io::Error` is kept here.
+pub(crate) struct IoWriter<W> {
+    inner: W,
+    error: Option<io::Error>,
+}
+
+impl<W: io::Write> IoWriter<W> {
+    pub(crate) fn new(inner: W) -> Self {
+        IoWriter { inner, error: None }
+    }
+
+    /// Replace `SerializeError::Fmt` in result with the `io::Error` behind it
+    pub(crate) fn check<T>(
+        &mut self,
+        result: Result<T, SerializeError>,
+    ) -> Result<T, SerializeError> {
+        match (result, self.error.take()) {
+            (Err(SerializeError::Fmt(_)), Some(error)) => Err(SerializeError::Io(error)),
+            (result, _) => result,
+        }
+    }
+
+    fn finish(mut self, result: Result<(), SerializeError>) -> Result<(), SerializeError> {
+        self.check(result)
+    }
+
+    pub(crate) fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: io::Write> Write for IoWriter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.inner.write_all(s.as_bytes()).map_err(|error| {
+            self.error = Some(error);
+            fmt::Error
+        })
+    }
+}
+
+/// Format value as compact json, or as json indented with 2 spaces with `{:#}`
+///
+/// Display can't report errors, so NaN and Infinity are formatted as `null`.
+///
+/// ```rust
+/// use wson::number::Number;
+/// use wson::Value;
+/// # fn main() {
+///
+///
+/// let value = Value::Array(vec![Value::Number(Number::PositiveInteger(1)), Value::Null]);
+/// assert_eq!(format!("{}", value), "[1,null]");
+/// assert_eq!(format!("{:#}", value), "[\n  1,\n  null\n]");
+/// # }
+/// ```
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let options = if f.alternate() {
+            SerializeOptions::pretty(2)
+        } else {
+            SerializeOptions::new()
+        };
+        let options = options.non_finite_floats(NonFiniteFloats::Null);
+        Serializer::new(f, &options)
+            .write_value(self)
+            .map_err(|_| fmt::Error)
+    }
+}
+
+/// NaN and Infinity are formatted as `null` like `Value`
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let options = SerializeOptions::new().non_finite_floats(NonFiniteFloats::Null);
+        write_number(f, self, &options).map_err(|_| fmt::Error)
+    }
+}
+
+impl fmt::Display for JsonString<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_string(f, self, &SerializeOptions::new())
+    }
+}
+
+impl fmt::Display for Null {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("null")
+    }
+}
+
+pub(crate) struct Serializer<'a, W> {
+    writer: W,
+    options: &'a SerializeOptions,
+    depth: usize,
+}
+
+impl<'a, W: Write> Serializer<'a, W> {
+    pub(crate) fn new(writer: W, options: &'a SerializeOptions) -> Self {
+        Serializer {
+            writer,
+            options,
+            depth: 0,
+        }
+    }
+
+    /// Serializer for values nested in `depth` objects or arrays
+    pub(crate) fn with_depth(mut self, depth: usize) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    fn serialize(mut self, value: &Value) -> Result<(), SerializeError> {
+        self.write_value(value)?;
+        self.write_trailing_newline()
+    }
+
+    pub(crate) fn write_trailing_newline(&mut self) -> Result<(), SerializeError> {
+        if self.options.trailing_newline {
+            self.writer.write_str(&self.options.newline)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn write_value(&mut self, value: &Value) -> Result<(), SerializeError> {
+        match value {
+            Value::Object(members) if members.is_empty() => Ok(self.writer.write_str("{}")?),
+            Value::Object(members) => {
+                self.writer.write_char('{')?;
+                self.depth += 1;
+                let mut members: Vec<_> = members.iter().collect();
+                if self.options.sort_keys {
+                    members.sort_unstable_by_key(|(key, _)| *key);
+                }
+                for (i, (key, value)) in members.into_iter().enumerate() {
+                    self.write_separator(i)?;
+                    self.write_key(key)?;
+                    self.write_value(value).map_err(|error| error.within(key))?;
+                }
+                self.depth -= 1;
+                self.write_newline()?;
+                Ok(self.writer.write_char('}')?)
+            }
+            Value::Array(elements) if elements.is_empty() => Ok(self.writer.write_str("[]")?),
+            Value::Array(elements) => {
+                self.writer.write_char('[')?;
+                self.depth += 1;
+                for (i, element) in elements.iter().enumerate() {
+                    self.write_separator(i)?;
+                    self.write_value(element)
+                        .map_err(|error| error.within(&i.to_string()))?;
+                }
+                self.depth -= 1;
+                self.write_newline()?;
+                Ok(self.writer.write_char(']')?)
+            }
+            Value::Number(number) => self.write_number(number),
+            Value::String(str) => self.write_string(str),
+            Value::Null => self.write_raw("null"),
+            Value::Bool(true) => self.write_raw("true"),
+            Value::Bool(false) => self.write_raw("false"),
+        }
+    }
+
+    pub(crate) fn write_number(&mut self, number: &Number) -> Result<(), SerializeError> {
+        write_number(&mut self.writer, number, self.options)
+    }
+
+    pub(crate) fn write_string(&mut self, str: &str) -> Result<(), SerializeError> {
+        Ok(write_string(&mut self.writer, str, self.options)?)
+    }
+
+    /// Write json text as it is
+    pub(crate) fn write_raw(&mut self, json: &str) -> Result<(), SerializeError> {
+        Ok(self.writer.write_str(json)?)
+    }
+
+    /// Write an object key and the following ':'
+    pub(crate) fn write_key(&mut self, key: &str) -> Result<(), SerializeError> {
+        write_string(&mut self.writer, key, self.options)?;
+        self.writer.write_char(':')?;
+        if self.options.space_after_colon {
+            self.writer.write_char(' ')?;
+        }
+        Ok(())
+    }
+
+    /// Write what comes before the `i`th member or element
+    pub(crate) fn write_separator(&mut self, i: usize) -> fmt::Result {
+        if i > 0 {
+            self.writer.write_char(',')?;
+            if self.options.space_after_comma && self.options.indent.is_none() {
+                self.writer.write_char(' ')?;
+            }
+        }
+        self.write_newline()
+    }
+
+    pub(crate) fn write_newline(&mut self) -> fmt::Result {
+        if let Some(indent) = &self.options.indent {
+            self.writer.write_str(&self.options.newline)?;
+            for _ in 0..self.depth {
+                self.writer.write_str(indent)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn write_number<W: Write>(
+    writer: &mut W,
+    number: &Number,
+    options: &SerializeOptions,
+) -> Result<(), SerializeError> {
+    match number {
+        Number::PositiveInteger(n) => write_integer(writer, false, u128::from(*n), options)?,
+        Number::NegativeInteger(n) => {
+            write_integer(writer, *n < 0, n.unsigned_abs().into(), options)?
+        }
+        Number::PositiveInteger128(n) => write_integer(writer, false, *n, options)?,
+        Number::NegativeInteger128(n) => write_integer(writer, *n < 0, n.unsigned_abs(), options)?,
+        Number::Float(n) if n.is_finite() => match options.float_precision {
+            Some(precision) => write!(writer, "{:.*}", precision, n)?,
+            // `Debug` writes the shortest digits which are parsed into the
+            // same f64, and keeps a fraction or an exponent on integral
+            // floats so the output is parsed as `Number::Float` again.
+            None => write!(writer, "{:?}", n)?,
+        },
+        Number::Float(n) => match options.non_finite_floats {
+            NonFiniteFloats::Error => {
+                return Err(SerializeError::NonFiniteFloat {
+                    pointer: String::new(),
+                })
+            }
+            NonFiniteFloats::Null => writer.write_str("null")?,
+            NonFiniteFloats::Literal if n.is_nan() => writer.write_str("NaN")?,
+            NonFiniteFloats::Literal if *n > 0.0 => writer.write_str("Infinity")?,
+            NonFiniteFloats::Literal => writer.write_str("-Infinity")?,
+        },
+        Number::Raw(raw) => writer.write_str(raw.as_str())?,
+        #[cfg(feature = "arbitrary_precision")]
+        Number::Arbitrary(literal) => writer.write_str(literal)?,
+    }
+    Ok(())
+}
+
+/// Write an integer of `magnitude`, formatting the digits on the stack
+fn write_integer<W: Write>(
+    writer: &mut W,
+    negative: bool,
+    magnitude: u128,
+    options: &SerializeOptions,
+) -> fmt::Result {
+    // 39 digits of u128::MAX and a sign
+    let mut buffer = [0u8; 40];
+    let mut start = buffer.len();
+    let mut n = magnitude;
+    loop {
+        start -= 1;
+        buffer[start] = b'0' + (n % 10) as u8;
+        n /= 10;
+        if n == 0 {
+            break;
+        }
+    }
+    if negative {
+        start -= 1;
+        buffer[start] = b'-';
+    }
+    let digits = std::str::from_utf8(&buffer[start..]).expect("digits are ASCII");
+
+    if options.big_int_as_string && magnitude > MAX_SAFE_INTEGER {
+        writer.write_char('"')?;
+        writer.write_str(digits)?;
+        writer.write_char('"')
+    } else {
+        writer.write_str(digits)
+    }
+}
+
+/// Write str as a json string, escaping '"', '\' and control characters,
+/// and the characters selected by `escape_*` options
+pub(crate) fn write_string<W: Write>(
+    writer: &mut W,
+    str: &str,
+    options: &SerializeOptions,
+) -> fmt::Result {
+    writer.write_char('"')?;
+    let mut start = 0;
+    for (i, c) in str.char_indices() {
+        let escaped = match c {
+            '"' => "\\\"",
+            '\\' => "\\\\",
+            '\u{08}' => "\\b",
+            '\u{0c}' => "\\f",
+            '\n' => "\\n",
+            '\r' => "\\r",
+            '\t' => "\\t",
+            '/' if options.escape_js => "\\/",
+            c if c < '\u{20}' => "",
+            '\u{2028}' | '\u{2029}' if options.escape_js => "",
+            '<' | '>' | '&' if options.escape_html => "",
+            c if options.escape_non_ascii && !c.is_ascii() => "",
+            _ => continue,
+        };
+        writer.write_str(&str[start..i])?;
+        if escaped.is_empty() {
+            for unit in c.encode_utf16(&mut [0; 2]) {
+                write!(writer, "\\u{:04x}", unit)?;
+            }
+        } else {
+            writer.write_str(escaped)?;
+        }
+        start = i + c.len_utf8();
+    }
+    writer.write_str(&str[start..])?;
+    writer.write_char('"')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse;
+    use std::collections::HashMap;
+
+    #[test]
+    fn serialize_literals() -> Result<(), SerializeError> {
+        assert_eq!(to_string(&Value::Null)?, "null");
+        assert_eq!(to_string(&Value::Bool(true))?, "true");
+        assert_eq!(to_string(&Value::Bool(false))?, "false");
+        Ok(())
+    }
+
+    #[test]
+    fn serialize_numbers() -> Result<(), SerializeError> {
+        assert_eq!(
+            to_string(&Value::Number(Number::PositiveInteger(u64::MAX)))?,
+            "18446744073709551615"
+        );
+        assert_eq!(
+            to_string(&Value::Number(Number::NegativeInteger(-32)))?,
+            "-32"
+        );
+        assert_eq!(to_string(&Value::Number(Number::Float(3.21)))?, "3.21");
+        assert_eq!(to_string(&Value::Number(Number::Float(2500.0)))?, "2500.0");
+        Ok(())
+    }
+
+    #[test]
+    fn serialize_string_with_quotes() -> Result<(), SerializeError> {
+        let json = to_string(&Value::String("He\"\"llo".to_string()))?;
+        assert_eq!(json, "\"He\\\"\\\"llo\"");
+        Ok(())
+    }
+
+    #[test]
+    fn serialize_string_with_backslashes() -> Result<(), SerializeError> {
+        let json = to_string(&Value::String("C:\\path\\".to_string()))?;
+        assert_eq!(json, "\"C:\\\\path\\\\\"");
+        Ok(())
+    }
+
+    #[test]
+    fn serialize_string_with_control_characters() -> Result<(), SerializeError> {
+        let json = to_string(&Value::String("\u{08}\u{0c}\n\r\t\u{00}\u{1f}".to_string()))?;
+        assert_eq!(json, "\"\\b\\f\\n\\r\\t\\u0000\\u001f\"");
+        Ok(())
+    }
+
+    #[test]
+    fn serialize_utf8_string() -> Result<(), SerializeError> {
+        let json = to_string(&Value::String("こんにちは".to_string()))?;
+        assert_eq!(json, "\"こんにちは\"");
+        Ok(())
+    }
+
+    #[test]
+    fn serialize_empty_containers() -> Result<(), SerializeError> {
+        assert_eq!(to_string(&Value::Object(HashMap::new()))?, "{}");
+        assert_eq!(to_string(&Value::Array(vec![]))?, "[]");
+        Ok(())
+    }
+
+    #[test]
+    fn serialize_object() -> Result<(), SerializeError> {
+        let value = Value::Object(HashMap::from([(
+            "key".to_string(),
+            Value::Array(vec![Value::Null, Value::Bool(false)]),
+        )]));
+        assert_eq!(to_string(&value)?, "{\"key\":[null,false]}");
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip_literals_and_numbers() -> Result<(), SerializeError> {
+        for value in [
+            Value::Null,
+            Value::Bool(true),
+            Value::Bool(false),
+            Value::Number(Number::PositiveInteger(0)),
+            Value::Number(Number::NegativeInteger(-12)),
+            Value::Number(Number::Float(2500.0)),
+            Value::Number(Number::Float(0.032)),
+            Value::Number(Number::Float(1e300)),
+            Value::String("hello".to_string()),
+        ] {
+            let json = to_string(&value)?;
+            assert_eq!(parse(&json).ok(), Some(value));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip_nested() -> Result<(), SerializeError> {
+        let value = Value::Object(HashMap::from([
+            ("empty_object".to_string(), Value::Object(HashMap::new())),
+            ("empty_array".to_string(), Value::Array(vec![])),
+            (
+                "nested".to_string(),
+                Value::Array(vec![
+                    Value::Object(HashMap::from([(
+                        "value".to_string(),
+                        Value::String("New".to_string()),
+                    )])),
+                    Value::Array(vec![Value::Array(vec![])]),
+                    Value::Number(Number::Float(-3.21)),
+                ]),
+            ),
+        ]));
+        let json = to_string(&value)?;
+        assert_eq!(parse(&json).ok(), Some(value));
+        Ok(())
+    }
+
+    #[test]
+    fn serialize_pretty_empty_containers() -> Result<(), SerializeError> {
+        assert_eq!(to_string_pretty(&Value::Object(HashMap::new()), 4)?, "{}");
+        assert_eq!(to_string_pretty(&Value::Array(vec![]), 4)?, "[]");
+        Ok(())
+    }
+
+    #[test]
+    fn serialize_pretty_scalar() -> Result<(), SerializeError> {
+        assert_eq!(to_string_pretty(&Value::Null, 4)?, "null");
+        assert_eq!(
+            to_string_pretty(&Value::String("a b".to_string()), 4)?,
+            "\"a b\""
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn serialize_pretty_nested() -> Result<(), SerializeError> {
+        let value = Value::Array(vec![
+            Value::Object(HashMap::from([(
+                "key".to_string(),
+                Value::Array(vec![Value::Array(vec![]), Value::Bool(true)]),
+            )])),
+            Value::Object(HashMap::new()),
+        ]);
+        assert_eq!(
+            to_string_pretty(&value, 4)?,
+            "[
+    {
+        \"key\": [
+            [],
+            true
+        ]
+    },
+    {}
+]"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn serialize_pretty_zero_indent() -> Result<(), SerializeError> {
+        let value = Value::Array(vec![Value::Null, Value::Array(vec![Value::Bool(false)])]);
+        assert_eq!(to_string_pretty(&value, 0)?, "[\nnull,\n[\nfalse\n]\n]");
+        Ok(())
+    }
+
+    #[test]
+    fn pretty_has_no_trailing_whitespace() -> Result<(), SerializeError> {
+        let value = Value::Object(HashMap::from([
+            (
+                "a".to_string(),
+                Value::Array(vec![Value::Null, Value::Bool(true)]),
+            ),
+            ("b".to_string(), Value::Object(HashMap::new())),
+        ]));
+        for line in to_string_pretty(&value, 2)?.lines() {
+            assert_eq!(line, line.trim_end());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip_escaped_strings() -> Result<(), SerializeError> {
+        for text in [
+            "He\"\"llo",
+            "C:\\path\\",
+            "\\\"",
+            "tab\tnew\nline\r\n",
+            "\u{8}\u{c}",
+            "</script>",
+            "\\n is not a newline",
+        ] {
+            let value = Value::String(text.to_string());
+            assert_eq!(parse(&to_string(&value)?).ok(), Some(value.clone()));
+            let js = value.serialize_with(&SerializeOptions::new().escape_js(true))?;
+            assert_eq!(parse(&js).ok(), Some(value));
+        }
+        let options = SerializeOptions::new().escape_non_ascii(true);
+        for text in ["\u{0}\u{1f}\u{7f}", "こんにちは", "😀 \u{10ffff}"] {
+            let value = Value::String(text.to_string());
+            assert_eq!(parse(&value.serialize_with(&options)?).ok(), Some(value));
+        }
+        let key = "a\"b\\c\td";
+        let value = Value::Object(HashMap::from([(key.to_string(), Value::Null)]));
+        assert_eq!(parse(&to_string(&value)?).ok(), Some(value));
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip_pretty_deeply_nested() -> Result<(), SerializeError> {
+        let mut value = Value::Number(Number::Float(0.5));
+        for i in 0..32 {
+            value = if i % 2 == 0 {
+                Value::Array(vec![value, Value::String(i.to_string())])
+            } else {
+                Value::Object(HashMap::from([
+                    (i.to_string(), value),
+                    ("empty".to_string(), Value::Array(vec![])),
+                ]))
+            };
+        }
+        let json = to_string_pretty(&value, 2)?;
+        assert_eq!(parse(&json).ok(), Some(value));
+        Ok(())
+    }
+
+    #[test]
+    fn display_value() -> Result<(), SerializeError> {
+        let value = Value::Object(HashMap::from([(
+            "a".to_string(),
+            Value::Array(vec![Value::Number(Number::PositiveInteger(1))]),
+        )]));
+        assert_eq!(format!("{}", value), "{\"a\":[1]}");
+        assert_eq!(value.to_string(), to_string(&value)?);
+        Ok(())
+    }
+
+    #[test]
+    fn display_value_alternate() -> Result<(), SerializeError> {
+        let value = Value::Object(HashMap::from([(
+            "a".to_string(),
+            Value::Array(vec![Value::Number(Number::PositiveInteger(1))]),
+        )]));
+        assert_eq!(format!("{:#}", value), "{\n  \"a\": [\n    1\n  ]\n}");
+        assert_eq!(format!("{:#}", value), to_string_pretty(&value, 2)?);
+        Ok(())
+    }
+
+    #[test]
+    fn display_round_trip() -> Result<(), SerializeError> {
+        let value = Value::Array(vec![
+            Value::String("text".to_string()),
+            Value::Number(Number::Float(2500.0)),
+            Value::Object(HashMap::new()),
+        ]);
+        let json = format!("{}", value);
+        assert_eq!(parse(&json).ok(), Some(value.clone()));
+        let json = format!("{:#}", value);
+        assert_eq!(parse(&json).ok(), Some(value));
+        Ok(())
+    }
+
+    #[test]
+    fn display_number() -> Result<(), SerializeError> {
+        assert_eq!(Number::PositiveInteger(3).to_string(), "3");
+        assert_eq!(Number::NegativeInteger(-3).to_string(), "-3");
+        assert_eq!(Number::Float(3.0).to_string(), "3.0");
+        Ok(())
+    }
+
+    #[test]
+    fn display_json_string() -> Result<(), SerializeError> {
+        assert_eq!(JsonString::from("a\"b").to_string(), "\"a\\\"b\"");
+        Ok(())
+    }
+
+    #[test]
+    fn display_null() -> Result<(), SerializeError> {
+        assert_eq!(Null.to_string(), "null");
+        Ok(())
+    }
+
+    fn example() -> Value {
+        Value::Object(HashMap::from([
+            (
+                "menuitem".to_string(),
+                Value::Array(vec![
+                    Value::String("New".to_string()),
+                    Value::Number(Number::Float(-0.5)),
+                    Value::Object(HashMap::new()),
+                ]),
+            ),
+            ("id".to_string(), Value::Null),
+        ]))
+    }
+
+    #[test]
+    fn to_writer_matches_to_string() -> Result<(), SerializeError> {
+        let value = example();
+        let mut output = Vec::new();
+        to_writer(&mut output, &value)?;
+        assert_eq!(String::from_utf8(output).ok(), Some(to_string(&value)?));
+        Ok(())
+    }
+
+    #[test]
+    fn to_writer_pretty_matches_to_string_pretty() -> Result<(), SerializeError> {
+        let value = example();
+        let mut output = Vec::new();
+        to_writer_pretty(&mut output, &value, 4)?;
+        assert_eq!(
+            String::from_utf8(output).ok(),
+            Some(to_string_pretty(&value, 4)?)
+        );
+        Ok(())
+    }
+
+    struct FailingWriter;
+
+    impl io::Write for FailingWriter {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            Err(io::Error::new(io::ErrorKind::BrokenPipe, "closed"))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn to_writer_returns_io_error() {
+        match to_writer(FailingWriter, &example()) {
+            Err(SerializeError::Io(error)) => assert_eq!(error.kind(), io::ErrorKind::BrokenPipe),
+            result => panic!("{:?}", result),
+        }
+    }
+
+    #[test]
+    fn default_options_match_compact_output() -> Result<(), SerializeError> {
+        let value = example();
+        assert_eq!(
+            value.serialize_with(&SerializeOptions::default())?,
+            to_string(&value)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn serialize_with_tab_indent() -> Result<(), SerializeError> {
+        let value = Value::Object(HashMap::from([(
+            "a".to_string(),
+            Value::Array(vec![Value::Null]),
+        )]));
+        let options = SerializeOptions::new().indent("\t").space_after_colon(true);
+        assert_eq!(
+            value.serialize_with(&options)?,
+            "{\n\t\"a\": [\n\t\tnull\n\t]\n}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn serialize_with_crlf() -> Result<(), SerializeError> {
+        let value = Value::Array(vec![
+            Value::Bool(true),
+            Value::Array(vec![Value::Bool(false)]),
+        ]);
+        let options = SerializeOptions::pretty(2).newline("\r\n");
+        assert_eq!(
+            value.serialize_with(&options)?,
+            "[\r\n  true,\r\n  [\r\n    false\r\n  ]\r\n]"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn serialize_with_spaces() -> Result<(), SerializeError> {
+        let value = Value::Object(HashMap::from([(
+            "a".to_string(),
+            Value::Array(vec![Value::Null, Value::Bool(true)]),
+        )]));
+        let options = SerializeOptions::new()
+            .space_after_colon(true)
+            .space_after_comma(true);
+        assert_eq!(value.serialize_with(&options)?, "{\"a\": [null, true]}");
+        Ok(())
+    }
+
+    #[test]
+    fn serialize_with_trailing_newline() -> Result<(), SerializeError> {
+        let options = SerializeOptions::new().trailing_newline(true);
+        assert_eq!(Value::Null.serialize_with(&options)?, "null\n");
+        let options = options.newline("\r\n");
+        assert_eq!(Value::Null.serialize_with(&options)?, "null\r\n");
+        Ok(())
+    }
+
+    #[test]
+    fn serialize_with_writer_matches_serialize_with() -> Result<(), SerializeError> {
+        let value = example();
+        let options = SerializeOptions::new().indent("\t").newline("\r\n");
+        let mut output = Vec::new();
+        value.serialize_with_writer(&mut output, &options)?;
+        assert_eq!(
+            String::from_utf8(output).ok(),
+            Some(value.serialize_with(&options)?)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip_with_options() -> Result<(), SerializeError> {
+        let value = example();
+        let options = SerializeOptions::new()
+            .indent("\t")
+            .space_after_comma(true)
+            .trailing_newline(true);
+        let json = value.serialize_with(&options)?;
+        assert_eq!(parse(&json).ok(), Some(value));
+        Ok(())
+    }
+
+    #[test]
+    fn serialize_with_sorted_keys() -> Result<(), SerializeError> {
+        let value = Value::Object(HashMap::from([
+            ("b".to_string(), Value::Null),
+            ("a".to_string(), Value::Null),
+            ("B".to_string(), Value::Null),
+            (
+                "c".to_string(),
+                Value::Array(vec![Value::Object(HashMap::from([
+                    ("z".to_string(), Value::Bool(true)),
+                    ("y".to_string(), Value::Bool(false)),
+                ]))]),
+            ),
+        ]));
+        let options = SerializeOptions::new().sort_keys(true);
+        assert_eq!(
+            value.serialize_with(&options)?,
+            "{\"B\":null,\"a\":null,\"b\":null,\"c\":[{\"y\":false,\"z\":true}]}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn sorted_keys_ignore_insertion_order() -> Result<(), SerializeError> {
+        let keys: Vec<String> = (0..64).map(|i| format!("key{}", i)).collect();
+        let mut forward = HashMap::new();
+        for (i, key) in keys.iter().enumerate() {
+            forward.insert(
+                key.clone(),
+                Value::Number(Number::PositiveInteger(i as u64)),
+            );
+        }
+        let mut backward = HashMap::with_capacity(1024);
+        for (i, key) in keys.iter().enumerate().rev() {
+            backward.insert(
+                key.clone(),
+                Value::Number(Number::PositiveInteger(i as u64)),
+            );
+        }
+        let options = SerializeOptions::pretty(2).sort_keys(true);
+        assert_eq!(
+            Value::Object(forward).serialize_with(&options)?,
+            Value::Object(backward).serialize_with(&options)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn serialize_non_ascii_raw_by_default() -> Result<(), SerializeError> {
+        let value = Value::Array(vec![
+            Value::String("こんにちは".to_string()),
+            Value::String("😀".to_string()),
+        ]);
+        assert_eq!(to_string(&value)?, "[\"こんにちは\",\"😀\"]");
+        Ok(())
+    }
+
+    #[test]
+    fn serialize_escaping_non_ascii() -> Result<(), SerializeError> {
+        let options = SerializeOptions::new().escape_non_ascii(true);
+        assert_eq!(
+            Value::String("こんにちは".to_string()).serialize_with(&options)?,
+            "\"\\u3053\\u3093\\u306b\\u3061\\u306f\""
+        );
+        assert_eq!(
+            Value::String("😀".to_string()).serialize_with(&options)?,
+            "\"\\ud83d\\ude00\""
+        );
+        assert_eq!(
+            Value::String("a\u{7f}é\n".to_string()).serialize_with(&options)?,
+            "\"a\u{7f}\\u00e9\\n\""
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn escaped_non_ascii_keys_are_ascii() -> Result<(), SerializeError> {
+        let value = Value::Object(HashMap::from([(
+            "キー".to_string(),
+            Value::String("値😀".to_string()),
+        )]));
+        let json = value.serialize_with(&SerializeOptions::new().escape_non_ascii(true))?;
+        assert!(json.is_ascii());
+        assert!(parse(&json).is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn serialize_js_unsafe_characters_raw_by_default() -> Result<(), SerializeError> {
+        let value = Value::String("</script>\u{2028}\u{2029}&".to_string());
+        assert_eq!(to_string(&value)?, "\"</script>\u{2028}\u{2029}&\"");
+        Ok(())
+    }
+
+    #[test]
+    fn serialize_escaping_js() -> Result<(), SerializeError> {
+        let value = Value::String("</script>\u{2028}\u{2029}&".to_string());
+        let json = value.serialize_with(&SerializeOptions::new().escape_js(true))?;
+        assert_eq!(json, "\"<\\/script>\\u2028\\u2029&\"");
+        assert!(!json.contains("</script>"));
+        assert!(parse(&json).is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn serialize_escaping_html() -> Result<(), SerializeError> {
+        let value = Value::Object(HashMap::from([(
+            "<a>".to_string(),
+            Value::String("</script> & more".to_string()),
+        )]));
+        let options = SerializeOptions::new().escape_js(true).escape_html(true);
+        let json = value.serialize_with(&options)?;
+        assert_eq!(
+            json,
+            "{\"\\u003ca\\u003e\":\"\\u003c\\/script\\u003e \\u0026 more\"}"
+        );
+        assert!(!json.contains("</script>"));
+        assert!(parse(&json).is_ok());
+        Ok(())
+    }
+
+    const TRICKY_FLOATS: [f64; 14] = [
+        0.1,
+        0.30000000000000004,
+        1e-308,
+        5e-324,
+        f64::MIN_POSITIVE,
+        1.7976931348623157e308,
+        -1.7976931348623157e308,
+        2500.0,
+        9007199254740993.0,
+        1e21,
+        1e-7,
+        123456789.12345679,
+        -0.0,
+        0.0,
+    ];
+
+    #[test]
+    fn floats_round_trip_bit_for_bit() -> Result<(), SerializeError> {
+        for n in TRICKY_FLOATS {
+            let json = to_string(&Value::Number(Number::Float(n)))?;
+            match parse(&json).ok() {
+                Some(Value::Number(Number::Float(parsed))) => {
+                    assert_eq!(parsed.to_bits(), n.to_bits(), "{}", json)
+                }
+                parsed => panic!("{} was parsed into {:?}", json, parsed),
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn serialize_shortest_floats() -> Result<(), SerializeError> {
+        assert_eq!(Number::Float(0.1).to_string(), "0.1");
+        assert_eq!(Number::Float(1e-308).to_string(), "1e-308");
+        assert_eq!(
+            Number::Float(1.7976931348623157e308).to_string(),
+            "1.7976931348623157e308"
+        );
+        assert_eq!(Number::Float(2500.0).to_string(), "2500.0");
+        Ok(())
+    }
+
+    #[test]
+    fn serialize_with_float_precision() -> Result<(), SerializeError> {
+        let value = Value::Array(vec![
+            Value::Number(Number::Float(1.23456)),
+            Value::Number(Number::Float(2500.0)),
+            Value::Number(Number::PositiveInteger(7)),
+        ]);
+        let options = SerializeOptions::new().float_precision(Some(2));
+        assert_eq!(value.serialize_with(&options)?, "[1.23,2500.00,7]");
+        let options = SerializeOptions::new().float_precision(Some(0));
+        assert_eq!(value.serialize_with(&options)?, "[1,2500,7]");
+        Ok(())
+    }
+
+    #[test]
+    fn serialize_integer_boundaries() -> Result<(), SerializeError> {
+        for (number, json) in [
+            (Number::PositiveInteger(0), "0"),
+            (Number::PositiveInteger(10), "10"),
+            (Number::PositiveInteger(u64::MAX), "18446744073709551615"),
+            (Number::NegativeInteger(0), "0"),
+            (Number::NegativeInteger(-1), "-1"),
+            (Number::NegativeInteger(i64::MIN), "-9223372036854775808"),
+            (Number::NegativeInteger(i64::MAX), "9223372036854775807"),
+            (Number::PositiveInteger128(1 << 64), "18446744073709551616"),
+            (
+                Number::PositiveInteger128(u128::MAX),
+                "340282366920938463463374607431768211455",
+            ),
+            (
+                Number::NegativeInteger128(i128::from(i64::MIN) - 1),
+                "-9223372036854775809",
+            ),
+            (
+                Number::NegativeInteger128(i128::MIN),
+                "-170141183460469231731687303715884105728",
+            ),
+        ] {
+            assert_eq!(number.to_string(), json);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn integers_round_trip_at_boundaries() -> Result<(), SerializeError> {
+        for number in [
+            Number::PositiveInteger(u64::MAX),
+            Number::PositiveInteger128(u128::from(u64::MAX) + 1),
+            Number::PositiveInteger128(u128::MAX),
+            Number::NegativeInteger(i64::MIN),
+            Number::NegativeInteger128(i128::from(i64::MIN) - 1),
+            Number::NegativeInteger128(i128::MIN),
+        ] {
+            let json = to_string(&Value::Number(number.clone()))?;
+            match parse(&json).ok() {
+                Some(Value::Number(parsed)) => {
+                    assert_eq!(format!("{:?}", parsed), format!("{:?}", number))
+                }
+                parsed => panic!("{} was parsed into {:?}", json, parsed),
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn serialize_big_int_as_string() -> Result<(), SerializeError> {
+        let options = SerializeOptions::new().big_int_as_string(true);
+        for (number, json) in [
+            (
+                Number::PositiveInteger(9007199254740991),
+                "9007199254740991",
+            ),
+            (
+                Number::PositiveInteger(9007199254740992),
+                "\"9007199254740992\"",
+            ),
+            (
+                Number::PositiveInteger(u64::MAX),
+                "\"18446744073709551615\"",
+            ),
+            (
+                Number::NegativeInteger(-9007199254740991),
+                "-9007199254740991",
+            ),
+            (
+                Number::NegativeInteger(-9007199254740992),
+                "\"-9007199254740992\"",
+            ),
+            (
+                Number::NegativeInteger(i64::MIN),
+                "\"-9223372036854775808\"",
+            ),
+            (
+                Number::PositiveInteger128(u128::MAX),
+                "\"340282366920938463463374607431768211455\"",
+            ),
+            (Number::Float(1e300), "1e300"),
+        ] {
+            assert_eq!(Value::Number(number).serialize_with(&options)?, json);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn big_int_as_numbers_by_default() -> Result<(), SerializeError> {
+        assert_eq!(
+            to_string(&Value::Number(Number::PositiveInteger(u64::MAX)))?,
+            "18446744073709551615"
+        );
+        Ok(())
+    }
+
+    fn nested_nan() -> Value {
+        Value::Object(HashMap::from([
+            ("ok".to_string(), Value::Number(Number::Float(1.5))),
+            (
+                "a/b".to_string(),
+                Value::Array(vec![Value::Null, Value::Number(Number::Float(f64::NAN))]),
+            ),
+        ]))
+    }
+
+    #[test]
+    fn non_finite_floats_error_by_default() {
+        match to_string(&nested_nan()) {
+            Err(SerializeError::NonFiniteFloat { pointer }) => assert_eq!(pointer, "/a~1b/1"),
+            result => panic!("{:?}", result),
+        }
+        match to_string(&Value::Number(Number::Float(f64::INFINITY))) {
+            Err(SerializeError::NonFiniteFloat { pointer }) => assert_eq!(pointer, ""),
+            result => panic!("{:?}", result),
+        }
+    }
+
+    #[test]
+    fn non_finite_floats_as_null() -> Result<(), SerializeError> {
+        let options = SerializeOptions::new()
+            .sort_keys(true)
+            .non_finite_floats(NonFiniteFloats::Null);
+        assert_eq!(
+            nested_nan().serialize_with(&options)?,
+            "{\"a/b\":[null,null],\"ok\":1.5}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn non_finite_floats_as_literal() -> Result<(), SerializeError> {
+        let options = SerializeOptions::new().non_finite_floats(NonFiniteFloats::Literal);
+        let value = Value::Array(vec![
+            Value::Number(Number::Float(f64::NAN)),
+            Value::Number(Number::Float(f64::INFINITY)),
+            Value::Number(Number::Float(f64::NEG_INFINITY)),
+        ]);
+        assert_eq!(value.serialize_with(&options)?, "[NaN,Infinity,-Infinity]");
+        Ok(())
+    }
+
+    #[test]
+    fn non_finite_floats_displayed_as_null() {
+        assert_eq!(Number::Float(f64::NAN).to_string(), "null");
+        assert_eq!(
+            Value::Array(vec![Value::Number(Number::Float(f64::INFINITY))]).to_string(),
+            "[null]"
+        );
+    }
+}
@@ -0,0 +1,170 @@
+//! Size and shape of values
+
+use crate::Value;
+use std::mem;
+
+/// Counts and sizes of a value, from `Value::stats`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Stats {
+    /// Deepest nesting of objects and arrays. A scalar is `0`, `[]` is `1`
+    /// and `[{}]` is `2`.
+    pub max_depth: usize,
+    pub objects: usize,
+    pub arrays: usize,
+    pub strings: usize,
+    pub numbers: usize,
+    pub bools: usize,
+    pub nulls: usize,
+    /// Members of all objects
+    pub members: usize,
+    /// Elements of all arrays
+    pub elements: usize,
+    /// Bytes of all string values, without object keys
+    pub string_bytes: usize,
+    /// Estimated bytes allocated on the heap, including object keys
+    pub heap_size: usize,
+}
+
+impl Value {
+    /// Count the nodes of the value and measure its size
+    ///
+    /// The value is traversed with a stack of its own instead of recursion,
+    /// so any depth can be measured.
+    ///
+    /// ```rust
+    /// use wson::json;
+    /// # fn main() {
+    ///
+    ///
+    /// let stats = json!({"tags": ["a", "bc"], "count": 2, "next": null}).stats();
+    /// assert_eq!(stats.max_depth, 2);
+    /// assert_eq!((stats.objects, stats.arrays), (1, 1));
+    /// assert_eq!((stats.strings, stats.numbers, stats.nulls), (2, 1, 1));
+    /// assert_eq!((stats.members, stats.elements), (3, 2));
+    /// assert_eq!(stats.string_bytes, 3);
+    /// # }
+    /// ```
+    pub fn stats(&self) -> Stats {
+        let mut stats = Stats::default();
+        let mut stack = vec![(self, 0)];
+        while let Some((value, depth)) = stack.pop() {
+            match value {
+                Value::Object(members) => {
+                    stats.objects += 1;
+                    stats.members += members.len();
+                    stats.max_depth = stats.max_depth.max(depth + 1);
+                    // a hash table has a control byte per bucket
+                    stats.heap_size += members.capacity() * (mem::size_of::<(String, Value)>() + 1);
+                    for (key, member) in members {
+                        stats.heap_size += key.capacity();
+                        stack.push((member, depth + 1));
+                    }
+                }
+                Value::Array(elements) => {
+                    stats.arrays += 1;
+                    stats.elements += elements.len();
+                    stats.max_depth = stats.max_depth.max(depth + 1);
+                    stats.heap_size += elements.capacity() * mem::size_of::<Value>();
+                    stack.extend(elements.iter().map(|element| (element, depth + 1)));
+                }
+                Value::String(s) => {
+                    stats.strings += 1;
+                    stats.string_bytes += s.len();
+                    stats.heap_size += s.capacity();
+                }
+                Value::Number(_) => stats.numbers += 1,
+                Value::Bool(_) => stats.bools += 1,
+                Value::Null => stats.nulls += 1,
+            }
+        }
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{json, parse};
+    use std::error;
+
+    #[test]
+    fn scalars() {
+        assert_eq!(
+            json!(1).stats(),
+            Stats {
+                numbers: 1,
+                ..Stats::default()
+            }
+        );
+        assert_eq!(
+            json!("héllo").stats(),
+            Stats {
+                strings: 1,
+                string_bytes: 6,
+                heap_size: 6,
+                ..Stats::default()
+            }
+        );
+        assert_eq!(Value::Null.stats().nulls, 1);
+        assert_eq!(json!(true).stats().bools, 1);
+    }
+
+    #[test]
+    fn small_document() -> Result<(), Box<dyn error::Error>> {
+        let value = parse(
+            r#"{"a": [1, 2.5, {"b": [[], {}]}], "c": "xyz", "d": true, "e": null, "f": ""}"#,
+        )?;
+        let stats = value.stats();
+        assert_eq!(
+            Stats {
+                heap_size: 0,
+                ..stats
+            },
+            Stats {
+                max_depth: 5,
+                objects: 3,
+                arrays: 3,
+                strings: 2,
+                numbers: 2,
+                bools: 1,
+                nulls: 1,
+                members: 6,
+                elements: 5,
+                string_bytes: 3,
+                heap_size: 0,
+            }
+        );
+        assert!(stats.heap_size >= 3 + 6 + 3 * mem::size_of::<Value>());
+        Ok(())
+    }
+
+    #[test]
+    fn deep_and_wide() {
+        let depth = 100_000;
+        let mut value = Value::Array((0..10).map(Value::from).collect());
+        for _ in 1..depth {
+            value = Value::Array(vec![value]);
+        }
+        let stats = value.stats();
+        assert_eq!(stats.max_depth, depth);
+        assert_eq!(stats.arrays, depth);
+        assert_eq!(stats.elements, depth - 1 + 10);
+        assert_eq!(stats.numbers, 10);
+
+        let wide: Value = (0..1000)
+            .map(|i| (i.to_string(), json!({"id": i, "tags": ["x"]})))
+            .collect();
+        let stats = wide.stats();
+        assert_eq!(stats.max_depth, 3);
+        assert_eq!(stats.objects, 1001);
+        assert_eq!(stats.arrays, 1000);
+        assert_eq!(stats.members, 3000);
+        assert_eq!(stats.strings, 1000);
+        assert_eq!(stats.numbers, 1000);
+
+        // dropping a deep value recurses, so unwrap it level by level
+        while let Value::Array(mut elements) = value {
+            value = elements.pop().unwrap_or_default();
+        }
+    }
+}
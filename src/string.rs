@@ -1,56 +1,239 @@
-use crate::number::digit;
+use std::borrow::{Borrow, Cow};
+use std::ops::Deref;
+
+use crate::{LoneSurrogates, ParseOptions};
 use nom::{
     branch::alt,
-    bytes::complete::tag,
+    bytes::complete::{tag, take_till},
     character::complete::{one_of, satisfy},
-    combinator::{map, recognize, value},
-    multi::many0,
-    sequence::{delimited, tuple},
+    combinator::recognize,
+    error::{Error, ErrorKind},
+    sequence::{preceded, tuple},
     IResult,
 };
 
-#[derive(Debug, PartialEq)]
-pub struct JsonString(pub String);
+/// Text of a json string
+///
+/// It borrows from the input when the string has no escapes, so only
+/// strings with escapes are copied. It derefs to `str`, and `Display`
+/// writes it as json, with quotes and escapes.
+///
+/// ```rust
+/// use std::collections::HashMap;
+/// use wson::string::JsonString;
+/// # fn main() {
+///
+///
+/// let key = JsonString::from("name");
+/// assert_eq!(&key[..2], "na");
+/// assert_eq!(key.to_string(), "\"name\"");
+///
+/// let map = HashMap::from([(key, 1)]);
+/// assert_eq!(map.get("name"), Some(&1));
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct JsonString<'a>(Cow<'a, str>);
+
+impl JsonString<'_> {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The text, copied if it is borrowed
+    pub fn into_string(self) -> String {
+        self.0.into_owned()
+    }
+
+    /// The same text, owning it so that it doesn't borrow the input
+    pub fn into_owned(self) -> JsonString<'static> {
+        JsonString(Cow::Owned(self.0.into_owned()))
+    }
+}
+
+impl Deref for JsonString<'_> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for JsonString<'_> {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
 
-/// Recognize string
+impl Borrow<str> for JsonString<'_> {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for JsonString<'_> {
+    fn from(text: String) -> Self {
+        JsonString(Cow::Owned(text))
+    }
+}
+
+impl<'a> From<&'a str> for JsonString<'a> {
+    fn from(text: &'a str) -> Self {
+        JsonString(Cow::Borrowed(text))
+    }
+}
+
+impl From<JsonString<'_>> for String {
+    fn from(string: JsonString<'_>) -> Self {
+        string.into_string()
+    }
+}
+
+/// Recognize string, with its escapes decoded
+///
+/// A `\u` escape of a surrogate which isn't part of a pair fails with
+/// `ErrorKind::Escaped` at the escape.
 /// ```rust
-/// use wson::string::{string, JsonString};
+/// use wson::string::string;
 /// # use std::error::Error;
 /// # fn main() -> Result<(), Box<dyn Error>> {
-/// let value = string("\"\"")?;
-/// assert_eq!(value, ("", JsonString("".to_string())));
+/// let (rest, value) = string("\"\"")?;
+/// assert_eq!((rest, value.as_str()), ("", ""));
 ///
-/// let value = string("\"hello\"")?;
-/// assert_eq!(value, ("", JsonString("hello".to_string())));
+/// let (rest, value) = string("\"hello\"")?;
+/// assert_eq!((rest, value.as_str()), ("", "hello"));
 ///
-/// let value = string("\"こんにちは\"")?;
-/// assert_eq!(value, ("", JsonString("こんにちは".to_string())));
+/// let (rest, value) = string("\"こんにちは\"")?;
+/// assert_eq!((rest, value.as_str()), ("", "こんにちは"));
 ///
-/// let value = string("\"abc123\"")?;
-/// assert_eq!(value, ("", JsonString("abc123".to_string())));
+/// let (rest, value) = string("\"abc123\"")?;
+/// assert_eq!((rest, value.as_str()), ("", "abc123"));
 ///
-/// let value = string("\"He\\\"\\\"llo\"")?;
-/// assert_eq!(value, ("", JsonString("He\\\"\\\"llo".to_string())));
+/// let (rest, value) = string("\"He\\\"\\\"llo\"")?;
+/// assert_eq!((rest, value.as_str()), ("", "He\"\"llo"));
+///
+/// let (rest, value) = string(r#""tab\tnew\nline\\""#)?;
+/// assert_eq!((rest, value.as_str()), ("", "tab\tnew\nline\\"));
+///
+/// let (rest, value) = string(r#""\u3053\ud83d\ude00""#)?;
+/// assert_eq!((rest, value.as_str()), ("", "こ😀"));
 ///
 /// # Ok(())
 /// # }
 /// ```
-pub fn string(input: &str) -> IResult<&str, JsonString> {
-    map(delimited(tag("\""), characters, tag("\"")), |str: &str| {
-        JsonString(str.to_string())
-    })(input)
+pub fn string(input: &str) -> IResult<&str, JsonString<'_>> {
+    string_with(input, &ParseOptions::new())
 }
 
-fn characters(input: &str) -> IResult<&str, &str> {
-    recognize(many0(character))(input)
+/// Recognize string, with lone surrogates and control characters handled
+/// as `options` say
+pub(crate) fn string_with<'a>(
+    input: &'a str,
+    options: &ParseOptions,
+) -> IResult<&'a str, JsonString<'a>> {
+    let (rest, characters) = quoted(input, options.allow_control_chars)?;
+    match unescape(characters, options.lone_surrogates) {
+        Ok(text) => Ok((rest, JsonString(text))),
+        Err(escape) => {
+            // the error is at the same place in the whole input
+            let at = &input[1 + characters.len() - escape.len()..];
+            Err(nom::Err::Failure(Error::new(at, ErrorKind::Escaped)))
+        }
+    }
 }
 
-fn character(input: &str) -> IResult<&str, &str> {
-    alt((
-        recognize(tuple((tag("\\"), escape))),
-        recognize(satisfy(|c| c != '"')),
-        value("", one_of("")),
-    ))(input)
+/// Recognize string and return it as written, without decoding it
+pub(crate) fn string_literal(input: &str, allow_control_chars: bool) -> IResult<&str, &str> {
+    recognize(|input| quoted(input, allow_control_chars))(input)
+}
+
+/// Text of `characters` with their escapes decoded, or the rest of them
+/// from a lone surrogate which `policy` says is an error
+///
+/// A `String` is built only when there is an escape.
+fn unescape(characters: &str, policy: LoneSurrogates) -> Result<Cow<'_, str>, &str> {
+    if !characters.contains('\\') {
+        return Ok(Cow::Borrowed(characters));
+    }
+    let mut text = String::with_capacity(characters.len());
+    let mut rest = characters;
+    while let Some(start) = rest.find('\\') {
+        text.push_str(&rest[..start]);
+        let escape = &rest[start..];
+        // the escape is valid as `characters` recognized it
+        let (decoded, after) = match escape.as_bytes()[1] {
+            b'b' => (Some('\u{8}'), &escape[2..]),
+            b'f' => (Some('\u{c}'), &escape[2..]),
+            b'n' => (Some('\n'), &escape[2..]),
+            b'r' => (Some('\r'), &escape[2..]),
+            b't' => (Some('\t'), &escape[2..]),
+            b'u' => unicode(escape, policy)?,
+            // '"', '\\' and '/' stand for themselves
+            c => (Some(char::from(c)), &escape[2..]),
+        };
+        text.extend(decoded);
+        rest = after;
+    }
+    text.push_str(rest);
+    Ok(Cow::Owned(text))
+}
+
+/// Char of the `\u` escape at the start of `escape`, with the low
+/// surrogate after it if it is a high one, and the rest after them
+///
+/// A lone surrogate is decoded as `policy` says, where `None` is skipped,
+/// and only its own escape is consumed.
+fn unicode(escape: &str, policy: LoneSurrogates) -> Result<(Option<char>, &str), &str> {
+    let unit = code_unit(escape);
+    let after = &escape[6..];
+    let is_low =
+        |escape: &str| escape.starts_with("\\u") && (0xdc00..=0xdfff).contains(&code_unit(escape));
+    match unit {
+        0xd800..=0xdbff if is_low(after) => {
+            let pair = 0x10000 + ((unit - 0xd800) << 10) + (code_unit(after) - 0xdc00);
+            Ok((char::from_u32(pair), &after[6..]))
+        }
+        0xd800..=0xdfff => match policy {
+            LoneSurrogates::Error => Err(escape),
+            LoneSurrogates::ReplacementChar => Ok((Some(char::REPLACEMENT_CHARACTER), after)),
+            LoneSurrogates::Skip => Ok((None, after)),
+        },
+        unit => Ok((char::from_u32(unit), after)),
+    }
+}
+
+/// Code unit of the `\u` escape at the start of `escape`
+fn code_unit(escape: &str) -> u32 {
+    u32::from_str_radix(&escape[2..6], 16).unwrap_or(u32::MAX)
+}
+
+/// Recognize the characters of a string between its quotes
+///
+/// An unescaped control character, which json doesn't allow in a string,
+/// fails with `ErrorKind::Char` at it unless `allow_control_chars`.
+fn quoted(input: &str, allow_control_chars: bool) -> IResult<&str, &str> {
+    let (rest, characters) =
+        preceded(tag("\""), |input| characters(input, allow_control_chars))(input)?;
+    match rest.chars().next() {
+        Some('"') => Ok((&rest[1..], characters)),
+        Some(c) if c < ' ' => Err(nom::Err::Failure(Error::new(rest, ErrorKind::Char))),
+        _ => Err(nom::Err::Error(Error::new(rest, ErrorKind::Tag))),
+    }
+}
+
+/// Recognize runs of characters other than `"`, `\` and control
+/// characters, and the valid escapes between them
+fn characters(input: &str, allow_control_chars: bool) -> IResult<&str, &str> {
+    let special = |c: char| c == '"' || c == '\\' || (c < ' ' && !allow_control_chars);
+    let mut rest = input;
+    loop {
+        let (after, _) = take_till(special)(rest)?;
+        rest = after;
+        match after.strip_prefix('\\').map(escape) {
+            Some(Ok((after, _))) => rest = after,
+            _ => return Ok((rest, &input[..input.len() - rest.len()])),
+        }
+    }
 }
 
 // escape = '"' DoubleQuote
@@ -73,11 +256,12 @@ fn escape(input: &str) -> IResult<&str, &str> {
 //     | 'A' . 'F'
 //     | 'a' . 'f'
 fn hex(input: &str) -> IResult<&str, &str> {
-    alt((recognize(digit), recognize(one_of("abcdefABCDEF"))))(input)
+    recognize(satisfy(|c| c.is_ascii_hexdigit()))(input)
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
     use std::error::Error;
 
     use super::*;
@@ -87,31 +271,154 @@ mod tests {
     #[test]
     fn parse_empty_string() -> TestResult {
         let value = string("\"\"")?;
-        assert_eq!(value, ("", JsonString("".to_string())));
+        assert_eq!(value, ("", JsonString::from("")));
         Ok(())
     }
 
     #[test]
     fn parse_hello_string() -> TestResult {
         let value = string("\"hello\"")?;
-        assert_eq!(value, ("", JsonString("hello".to_string())));
+        assert_eq!(value, ("", JsonString::from("hello")));
         Ok(())
     }
 
     #[test]
     fn parse_utf8_string() -> TestResult {
         let value = string("\"こんにちは\"")?;
-        assert_eq!(value, ("", JsonString("こんにちは".to_string())));
+        assert_eq!(value, ("", JsonString::from("こんにちは")));
         Ok(())
     }
 
     #[test]
     fn parse_alphanum_string() -> TestResult {
         let value = string("\"abc123\"")?;
-        assert_eq!(value, ("", JsonString("abc123".to_string())));
+        assert_eq!(value, ("", JsonString::from("abc123")));
         Ok(())
     }
 
+    #[test]
+    fn decode_escapes() -> TestResult {
+        for (input, expected) in [
+            (r#""\"""#, "\""),
+            (r#""\\""#, "\\"),
+            (r#""\/""#, "/"),
+            (r#""\b\f\n\r\t""#, "\u{8}\u{c}\n\r\t"),
+            (r#""a\nb""#, "a\nb"),
+            (r#""\\n""#, "\\n"),
+            (r#""こ\tん""#, "こ\tん"),
+        ] {
+            assert_eq!(string(input)?, ("", JsonString::from(expected)));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn decode_unicode_escapes() -> TestResult {
+        for (input, expected) in [
+            (r#""\u3053""#, "こ"),
+            (r#""\u0041\u00e9\uFFFD""#, "Aé\u{fffd}"),
+            (r#""\ud83d\ude00""#, "😀"),
+            (r#""\uD83D\uDE00""#, "😀"),
+            (r#""\udbff\udfff""#, "\u{10ffff}"),
+            (r#""a\u3053b😀\ud83d\ude00\n""#, "aこb😀😀\n"),
+            (r#""\u0000""#, "\u{0}"),
+            (r#""\\u0041""#, "\\u0041"),
+        ] {
+            assert_eq!(string(input)?, ("", JsonString::from(expected)));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn lone_surrogates() {
+        for (input, at) in [
+            (r#""\ud800""#, 1),
+            (r#""a\udc00b""#, 2),
+            (r#""\ud83dx""#, 1),
+            (r#""\ud83d\u0041""#, 1),
+            (r#""\ud83d\ud83d\ude00""#, 1),
+            (r#""\ude00\ud83d""#, 1),
+            (r#""\ud83d\\ude00""#, 1),
+        ] {
+            let error = nom::error::Error::new(&input[at..], ErrorKind::Escaped);
+            assert_eq!(string(input), Err(nom::Err::Failure(error)), "{}", input);
+        }
+    }
+
+    #[test]
+    fn lone_surrogate_policies() -> TestResult {
+        let replace = ParseOptions::new().lone_surrogates(LoneSurrogates::ReplacementChar);
+        let skip = ParseOptions::new().lone_surrogates(LoneSurrogates::Skip);
+        for (input, replaced, skipped) in [
+            // high surrogate without a low one
+            (r#""a\ud800b""#, "a\u{fffd}b", "ab"),
+            (r#""\ud800""#, "\u{fffd}", ""),
+            // low surrogate without a high one
+            (r#""a\udc00b""#, "a\u{fffd}b", "ab"),
+            (r#""\ude00\ud83d""#, "\u{fffd}\u{fffd}", ""),
+            // high surrogate followed by an escape which isn't a low one
+            (r#""\ud83d\u0041""#, "\u{fffd}A", "A"),
+            (r#""\ud83d\n""#, "\u{fffd}\n", "\n"),
+            (r#""\ud83d\ud83d\ude00""#, "\u{fffd}😀", "😀"),
+        ] {
+            assert!(string(input).is_err(), "{}", input);
+            let (_, text) = string_with(input, &replace)?;
+            assert_eq!(text, JsonString::from(replaced), "{}", input);
+            let (_, text) = string_with(input, &skip)?;
+            assert_eq!(text, JsonString::from(skipped), "{}", input);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn borrow_without_escapes() -> TestResult {
+        let input = "\"hello こんにちは\"";
+        let (_, value) = string(input)?;
+        assert!(matches!(value.0, Cow::Borrowed(text) if text.as_ptr() == input[1..].as_ptr()));
+        assert!(matches!(string("\"\"")?.1 .0, Cow::Borrowed("")));
+        assert!(matches!(string(r#""a\nb""#)?.1 .0, Cow::Owned(_)));
+        assert!(matches!(string(r#""\u0041""#)?.1 .0, Cow::Owned(_)));
+        Ok(())
+    }
+
+    #[test]
+    fn into_owned() -> TestResult {
+        let input = String::from("\"text\"");
+        let owned = string(&input)
+            .map(|(_, value)| value.into_owned())
+            .map_err(|error| error.to_string())?;
+        drop(input);
+        assert!(matches!(owned.0, Cow::Owned(_)));
+        assert_eq!(owned, JsonString::from("text"));
+        Ok(())
+    }
+
+    #[test]
+    fn use_as_str() -> TestResult {
+        let (_, value) = string(r#""key\n""#)?;
+        assert_eq!(&value[..], "key\n");
+        assert_eq!(value.len(), 4);
+        assert!(value.starts_with("key"));
+        assert_eq!(value.as_ref() as &str, "key\n");
+        assert_eq!(value.clone().into_string(), "key\n");
+        assert_eq!(String::from(value.clone()), "key\n");
+        assert_eq!(JsonString::from("key\n".to_string()), value);
+        assert_eq!(JsonString::from("key\n"), value);
+
+        let map = HashMap::from([(value, 1), (JsonString::from("other"), 2)]);
+        assert_eq!(map.get("key\n"), Some(&1));
+        assert_eq!(map.get("other"), Some(&2));
+        assert_eq!(map.get("key"), None);
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_escapes() {
+        for input in [r#""\q""#, r#""\""#, r#""\x41""#, r#""\u12""#, r#""a\""#] {
+            assert!(string(input).is_err(), "{}", input);
+        }
+    }
+
     #[test]
     fn hex_five() -> TestResult {
         let value = hex("5")?;
@@ -148,8 +455,33 @@ mod tests {
     }
 
     #[test]
-    fn character_unicode() -> TestResult {
-        let value = character("\\u1234")?;
+    fn characters_stop() -> TestResult {
+        for (input, rest) in [
+            ("ab\"c", "\"c"),
+            ("a\\\"b\"", "\""),
+            ("a\\n\\t\\u0041", ""),
+            ("a\\qb", "\\qb"),
+            ("a\\u12x", "\\u12x"),
+            ("a\\", "\\"),
+            ("a\nb", "\nb"),
+            ("こ\u{1f}", "\u{1f}"),
+            ("\u{7f}\u{80}", ""),
+        ] {
+            let value = characters(input, false)?;
+            assert_eq!(
+                value,
+                (rest, &input[..input.len() - rest.len()]),
+                "{:?}",
+                input
+            );
+        }
+        assert_eq!(characters("a\nb\"", true)?, ("\"", "a\nb"));
+        Ok(())
+    }
+
+    #[test]
+    fn characters_unicode() -> TestResult {
+        let value = characters("\\u1234", false)?;
         assert_eq!(value, ("", "\\u1234"));
         Ok(())
     }
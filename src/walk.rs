@@ -0,0 +1,297 @@
+use crate::pointer;
+use crate::Value;
+
+/// What `Value::walk_mut` does after visiting a node
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WalkAction {
+    /// Go on into the children of the node
+    Continue,
+    /// Keep the node but don't visit its children
+    SkipChildren,
+    /// Remove the node from its object or array
+    Remove,
+}
+
+impl Value {
+    /// Visit every node in depth-first pre-order, letting `f` change or remove it
+    ///
+    /// `f` gets the JSON Pointer of each node as it was before the walk, so
+    /// elements after a removed one keep their original index. Removals are
+    /// applied once the walk of their container is done. Removing the whole
+    /// value turns it into `null`. Members of an object are visited in
+    /// unspecified order.
+    ///
+    /// ```rust
+    /// use wson::{json, Value, WalkAction};
+    /// # fn main() {
+    ///
+    ///
+    /// let mut value = json!({"user": {"name": "A", "password": "x"}, "tags": ["B", "C"]});
+    /// value.walk_mut(|_, node| {
+    ///     if let Value::Object(members) = node {
+    ///         members.remove("password");
+    ///     }
+    ///     if let Value::String(s) = node {
+    ///         *s = s.to_lowercase();
+    ///     }
+    ///     WalkAction::Continue
+    /// });
+    /// assert_eq!(value, json!({"user": {"name": "a"}, "tags": ["b", "c"]}));
+    /// # }
+    /// ```
+    pub fn walk_mut<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&str, &mut Value) -> WalkAction,
+    {
+        if walk(&mut String::new(), self, &mut f) {
+            *self = Value::Null;
+        }
+    }
+
+    /// Keep only the members and elements, anywhere in value, for which `f`
+    /// returns `true`
+    ///
+    /// The tree is walked depth-first in post-order: the children of a
+    /// member or element are filtered first, and then `f` sees it with the
+    /// children that are left. `f` gets the JSON Pointer of each node as it
+    /// was before anything was removed. Value itself is not passed to `f`.
+    /// Members of an object are visited in unspecified order.
+    ///
+    /// ```rust
+    /// use wson::json;
+    /// # fn main() {
+    ///
+    ///
+    /// let mut response = json!({"data": [{"id": 1, "_trace_id": "x"}], "_debug": {"ms": 3}});
+    /// response.retain_recursive(|pointer, _| {
+    ///     !pointer.rsplit('/').next().unwrap_or_default().starts_with('_')
+    /// });
+    /// assert_eq!(response, json!({"data": [{"id": 1}]}));
+    /// # }
+    /// ```
+    pub fn retain_recursive<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&str, &Value) -> bool,
+    {
+        retain(&mut String::new(), self, &mut f);
+    }
+}
+
+/// Visit `value` at `pointer` and its children, and whether to remove it
+fn walk<F>(pointer: &mut String, value: &mut Value, f: &mut F) -> bool
+where
+    F: FnMut(&str, &mut Value) -> WalkAction,
+{
+    match f(pointer, value) {
+        WalkAction::Continue => {}
+        WalkAction::SkipChildren => return false,
+        WalkAction::Remove => return true,
+    }
+    let len = pointer.len();
+    match value {
+        Value::Object(members) => {
+            let mut removed = Vec::new();
+            for (key, member) in members.iter_mut() {
+                pointer.push('/');
+                pointer.push_str(&pointer::escape(key));
+                if walk(pointer, member, f) {
+                    removed.push(key.clone());
+                }
+                pointer.truncate(len);
+            }
+            for key in removed {
+                members.remove(&key);
+            }
+        }
+        Value::Array(elements) => {
+            let mut index = 0;
+            elements.retain_mut(|element| {
+                pointer.push('/');
+                pointer.push_str(&index.to_string());
+                let remove = walk(pointer, element, f);
+                pointer.truncate(len);
+                index += 1;
+                !remove
+            });
+        }
+        _ => {}
+    }
+    false
+}
+
+/// Filter the children of `value` at `pointer`, and theirs first
+fn retain<F>(pointer: &mut String, value: &mut Value, f: &mut F)
+where
+    F: FnMut(&str, &Value) -> bool,
+{
+    let len = pointer.len();
+    match value {
+        Value::Object(members) => members.retain(|key, member| {
+            pointer.push('/');
+            pointer.push_str(&pointer::escape(key));
+            retain(pointer, member, f);
+            let keep = f(pointer, member);
+            pointer.truncate(len);
+            keep
+        }),
+        Value::Array(elements) => {
+            let mut index = 0;
+            elements.retain_mut(|element| {
+                pointer.push('/');
+                pointer.push_str(&index.to_string());
+                retain(pointer, element, f);
+                let keep = f(pointer, element);
+                pointer.truncate(len);
+                index += 1;
+                keep
+            });
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json;
+
+    #[test]
+    fn remove_array_elements_during_walk() {
+        let mut value = json!({"list": [1, "drop", 2, "drop", "drop", [3, "drop"]]});
+        let mut visited = Vec::new();
+        value.walk_mut(|pointer, node| {
+            visited.push(pointer.to_string());
+            if node == "drop" {
+                WalkAction::Remove
+            } else {
+                WalkAction::Continue
+            }
+        });
+        assert_eq!(value, json!({"list": [1, 2, [3]]}));
+        assert_eq!(
+            visited,
+            [
+                "",
+                "/list",
+                "/list/0",
+                "/list/1",
+                "/list/2",
+                "/list/3",
+                "/list/4",
+                "/list/5",
+                "/list/5/0",
+                "/list/5/1"
+            ]
+        );
+    }
+
+    #[test]
+    fn remove_object_members() {
+        let mut value = json!({
+            "password": "a",
+            "users": [{"name": "b", "password": "c"}, {"password": {"hash": "d"}}],
+        });
+        value.walk_mut(|pointer, _| {
+            if pointer.ends_with("/password") {
+                WalkAction::Remove
+            } else {
+                WalkAction::Continue
+            }
+        });
+        assert_eq!(value, json!({"users": [{"name": "b"}, {}]}));
+    }
+
+    #[test]
+    fn skip_children() {
+        let mut value = json!({"secret": {"a": "x", "b": ["y"]}, "public": {"c": "z"}});
+        let mut visited = Vec::new();
+        value.walk_mut(|pointer, node| {
+            visited.push(pointer.to_string());
+            if pointer == "/secret" {
+                return WalkAction::SkipChildren;
+            }
+            if let Value::String(s) = node {
+                s.make_ascii_uppercase();
+            }
+            WalkAction::Continue
+        });
+        assert_eq!(
+            value,
+            json!({"secret": {"a": "x", "b": ["y"]}, "public": {"c": "Z"}})
+        );
+        assert!(!visited
+            .iter()
+            .any(|pointer| pointer.starts_with("/secret/")));
+        visited.sort();
+        assert_eq!(visited, ["", "/public", "/public/c", "/secret"]);
+    }
+
+    #[test]
+    fn escaped_pointers() {
+        let mut value = json!({"a/b": {"~": 1}});
+        let mut visited = Vec::new();
+        value.walk_mut(|pointer, _| {
+            visited.push(pointer.to_string());
+            WalkAction::Continue
+        });
+        assert_eq!(visited, ["", "/a~1b", "/a~1b/~0"]);
+    }
+
+    #[test]
+    fn remove_root() {
+        let mut value = json!([1, 2]);
+        value.walk_mut(|_, _| WalkAction::Remove);
+        assert_eq!(value, Value::Null);
+    }
+
+    #[test]
+    fn retain_by_key_pattern() {
+        let mut value = json!({
+            "_debug": {"query": "x"},
+            "users": [
+                {"name": "a", "_trace_id": 1, "_internal": {"x": 1}},
+                {"name": "b", "meta": {"_trace_id": 2, "kept": true}},
+            ],
+        });
+        value.retain_recursive(|pointer, _| !pointer.contains("/_"));
+        assert_eq!(
+            value,
+            json!({"users": [{"name": "a"}, {"name": "b", "meta": {"kept": true}}]})
+        );
+    }
+
+    #[test]
+    fn retain_by_value_type() {
+        let mut value = json!([1, "a", [2, "b", {"c": 3, "d": "e"}], {"f": "g"}]);
+        value.retain_recursive(|_, node| !node.is_string());
+        assert_eq!(value, json!([1, [2, {"c": 3}], {}]));
+    }
+
+    #[test]
+    fn retain_sees_children_first() {
+        let mut value = json!({"a": [null, {"b": null}], "c": {"d": 1}});
+        let mut visited = Vec::new();
+        value.retain_recursive(|pointer, node| {
+            visited.push(pointer.to_string());
+            !node.is_null() && !node.is_empty()
+        });
+        assert_eq!(value, json!({"c": {"d": 1}}));
+        let position = |pointer: &str| visited.iter().position(|p| p == pointer);
+        assert!(position("/a/1/b") < position("/a/1"));
+        assert!(position("/a/1") < position("/a"));
+        assert!(position("/c/d") < position("/c"));
+        assert_eq!(visited.len(), 6);
+    }
+
+    #[test]
+    fn retain_keeps_original_indices() {
+        let mut value = json!([0, 1, 2, 3]);
+        let mut visited = Vec::new();
+        value.retain_recursive(|pointer, node| {
+            visited.push(pointer.to_string());
+            node != &json!(1)
+        });
+        assert_eq!(value, json!([0, 2, 3]));
+        assert_eq!(visited, ["/0", "/1", "/2", "/3"]);
+    }
+}
@@ -0,0 +1,571 @@
+use crate::error::SerializeError;
+use crate::number::Number;
+use crate::raw::RawValue;
+use crate::serialize::{IoWriter, SerializeOptions, Serializer};
+use crate::Value;
+use std::io;
+
+/// Write json piece by piece into an `io::Write` without building a `Value`
+///
+/// Calls which would make invalid json, like writing two keys in a row or
+/// ending an array that was never begun, fail with
+/// `SerializeError::InvalidStructure`.
+///
+/// ```rust
+/// use wson::number::Number;
+/// use wson::{JsonWriter, Value};
+/// # use std::error;
+/// # fn main() -> Result<(), Box<dyn error::Error>> {
+///
+///
+/// let mut writer = JsonWriter::new(Vec::new());
+/// writer.begin_object()?;
+/// writer.key("rows")?;
+/// writer.begin_array()?;
+/// for i in 0..3 {
+///     writer.number(&Number::PositiveInteger(i))?;
+/// }
+/// writer.end()?;
+/// writer.key("done")?;
+/// writer.value(&Value::Bool(true))?;
+/// writer.end()?;
+/// let output = writer.finish()?;
+/// assert_eq!(output, b"{\"rows\":[0,1,2],\"done\":true}");
+///
+/// # Ok(())
+/// # }
+/// ```
+pub struct JsonWriter<W: io::Write> {
+    writer: IoWriter<W>,
+    options: SerializeOptions,
+    stack: Vec<Frame>,
+    complete: bool,
+}
+
+/// An object or array being written
+enum Frame {
+    Object {
+        count: usize,
+        /// Key waiting for its value, kept for `SerializeError::NonFiniteFloat`
+        key: Option<String>,
+    },
+    Array {
+        count: usize,
+    },
+}
+
+impl<W: io::Write> JsonWriter<W> {
+    /// Writer of compact json
+    pub fn new(writer: W) -> Self {
+        Self::with_options(writer, SerializeOptions::new())
+    }
+
+    /// Writer laid out by `options`
+    pub fn with_options(writer: W, options: SerializeOptions) -> Self {
+        JsonWriter {
+            writer: IoWriter::new(writer),
+            options,
+            stack: vec![],
+            complete: false,
+        }
+    }
+
+    pub fn begin_object(&mut self) -> Result<(), SerializeError> {
+        self.begin_value()?;
+        self.write(|serializer| serializer.write_raw("{"))?;
+        self.stack.push(Frame::Object {
+            count: 0,
+            key: None,
+        });
+        Ok(())
+    }
+
+    pub fn begin_array(&mut self) -> Result<(), SerializeError> {
+        self.begin_value()?;
+        self.write(|serializer| serializer.write_raw("["))?;
+        self.stack.push(Frame::Array { count: 0 });
+        Ok(())
+    }
+
+    /// Write the key of the next member of the current object
+    pub fn key(&mut self, key: &str) -> Result<(), SerializeError> {
+        let count = match self.stack.last_mut() {
+            Some(Frame::Object {
+                key: current @ None,
+                count,
+            }) => {
+                *current = Some(key.to_string());
+                *count += 1;
+                *count - 1
+            }
+            Some(Frame::Object { .. }) => {
+                return Err(SerializeError::InvalidStructure(
+                    "a key was written after a key",
+                ))
+            }
+            _ => {
+                return Err(SerializeError::InvalidStructure(
+                    "a key was written outside of an object",
+                ))
+            }
+        };
+        self.write(|serializer| {
+            serializer.write_separator(count)?;
+            serializer.write_key(key)
+        })
+    }
+
+    pub fn string(&mut self, str: &str) -> Result<(), SerializeError> {
+        self.begin_value()?;
+        self.write(|serializer| serializer.write_string(str))?;
+        self.end_value();
+        Ok(())
+    }
+
+    pub fn number(&mut self, number: &Number) -> Result<(), SerializeError> {
+        self.begin_value()?;
+        self.write(|serializer| serializer.write_number(number))?;
+        self.end_value();
+        Ok(())
+    }
+
+    pub fn bool(&mut self, bool: bool) -> Result<(), SerializeError> {
+        self.begin_value()?;
+        self.write(|serializer| serializer.write_raw(if bool { "true" } else { "false" }))?;
+        self.end_value();
+        Ok(())
+    }
+
+    pub fn null(&mut self) -> Result<(), SerializeError> {
+        self.begin_value()?;
+        self.write(|serializer| serializer.write_raw("null"))?;
+        self.end_value();
+        Ok(())
+    }
+
+    /// Write a whole value, serialized the same as `Value::serialize_with`
+    pub fn value(&mut self, value: &Value) -> Result<(), SerializeError> {
+        self.begin_value()?;
+        self.write(|serializer| serializer.write_value(value))?;
+        self.end_value();
+        Ok(())
+    }
+
+    /// Write json text checked by `RawValue` as it is
+    pub fn raw(&mut self, raw: &RawValue) -> Result<(), SerializeError> {
+        self.begin_value()?;
+        self.write(|serializer| serializer.write_raw(raw.as_str()))?;
+        self.end_value();
+        Ok(())
+    }
+
+    /// End the current object or array
+    pub fn end(&mut self) -> Result<(), SerializeError> {
+        let (count, close) = match self.stack.last() {
+            Some(Frame::Object { key: Some(_), .. }) => {
+                return Err(SerializeError::InvalidStructure(
+                    "an object was ended after a key",
+                ))
+            }
+            Some(Frame::Object { count, .. }) => (*count, "}"),
+            Some(Frame::Array { count }) => (*count, "]"),
+            None => {
+                return Err(SerializeError::InvalidStructure(
+                    "no object or array to end",
+                ))
+            }
+        };
+        self.stack.pop();
+        self.write(|serializer| {
+            if count > 0 {
+                serializer.write_newline()?;
+            }
+            serializer.write_raw(close)
+        })?;
+        self.end_value();
+        Ok(())
+    }
+
+    /// Check a complete json value was written and return the inner writer
+    pub fn finish(mut self) -> Result<W, SerializeError> {
+        if !self.complete {
+            return Err(SerializeError::InvalidStructure(
+                "the json value is not complete",
+            ));
+        }
+        self.write(|serializer| serializer.write_trailing_newline())?;
+        Ok(self.writer.into_inner())
+    }
+
+    /// Check a value can be written here and write what comes before it
+    fn begin_value(&mut self) -> Result<(), SerializeError> {
+        match self.stack.last_mut() {
+            None if self.complete => Err(SerializeError::InvalidStructure(
+                "the json value is already complete",
+            )),
+            None => Ok(()),
+            Some(Frame::Object { key: None, .. }) => Err(SerializeError::InvalidStructure(
+                "a value was written in an object without a key",
+            )),
+            Some(Frame::Object { .. }) => Ok(()),
+            Some(Frame::Array { count }) => {
+                *count += 1;
+                let i = *count - 1;
+                self.write(|serializer| Ok(serializer.write_separator(i)?))
+            }
+        }
+    }
+
+    /// Mark the value in the current object or array, or the whole json, done
+    fn end_value(&mut self) {
+        match self.stack.last_mut() {
+            None => self.complete = true,
+            Some(Frame::Object { key, .. }) => *key = None,
+            Some(Frame::Array { .. }) => {}
+        }
+    }
+
+    fn write<F>(&mut self, f: F) -> Result<(), SerializeError>
+    where
+        F: FnOnce(&mut Serializer<&mut IoWriter<W>>) -> Result<(), SerializeError>,
+    {
+        let mut serializer =
+            Serializer::new(&mut self.writer, &self.options).with_depth(self.stack.len());
+        let result = f(&mut serializer);
+        let result = self.writer.check(result);
+        result.map_err(|error| {
+            self.stack
+                .iter()
+                .rev()
+                .fold(error, |error, frame| match frame {
+                    Frame::Object { key: Some(key), .. } => error.within(key),
+                    Frame::Object { key: None, .. } => error,
+                    Frame::Array { count } => error.within(&(count - 1).to_string()),
+                })
+        })
+    }
+}
+
+/// Serialize values pulled one by one from `iter` into a compact json array
+///
+/// ```rust
+/// use wson::number::Number;
+/// use wson::{to_string_from_iter, Value};
+/// # use std::error;
+/// # fn main() -> Result<(), Box<dyn error::Error>> {
+///
+///
+/// let json = to_string_from_iter((1..4).map(|i| Value::Number(Number::PositiveInteger(i))))?;
+/// assert_eq!(json, "[1,2,3]");
+///
+/// # Ok(())
+/// # }
+/// ```
+pub fn to_string_from_iter<I>(iter: I) -> Result<String, SerializeError>
+where
+    I: IntoIterator<Item = Value>,
+{
+    let output = to_writer_from_iter(Vec::new(), iter)?;
+    Ok(String::from_utf8(output).expect("json is UTF-8"))
+}
+
+/// Serialize values pulled one by one from `iter` into a compact json array
+/// written to `writer`, and return the writer
+pub fn to_writer_from_iter<W, I>(writer: W, iter: I) -> Result<W, SerializeError>
+where
+    W: io::Write,
+    I: IntoIterator<Item = Value>,
+{
+    let mut writer = JsonWriter::new(writer);
+    writer.begin_array()?;
+    for value in iter {
+        writer.value(&value)?;
+    }
+    writer.end()?;
+    writer.finish()
+}
+
+/// Serialize members pulled one by one from `iter` into a compact json object
+///
+/// ```rust
+/// use wson::{to_string_from_members, Value};
+/// # use std::error;
+/// # fn main() -> Result<(), Box<dyn error::Error>> {
+///
+///
+/// let json = to_string_from_members([("a".to_string(), Value::Null)])?;
+/// assert_eq!(json, "{\"a\":null}");
+///
+/// # Ok(())
+/// # }
+/// ```
+pub fn to_string_from_members<I>(iter: I) -> Result<String, SerializeError>
+where
+    I: IntoIterator<Item = (String, Value)>,
+{
+    let output = to_writer_from_members(Vec::new(), iter)?;
+    Ok(String::from_utf8(output).expect("json is UTF-8"))
+}
+
+/// Serialize members pulled one by one from `iter` into a compact json object
+/// written to `writer`, and return the writer
+///
+/// Keys are written as they come, so duplicated keys stay duplicated.
+pub fn to_writer_from_members<W, I>(writer: W, iter: I) -> Result<W, SerializeError>
+where
+    W: io::Write,
+    I: IntoIterator<Item = (String, Value)>,
+{
+    let mut writer = JsonWriter::new(writer);
+    writer.begin_object()?;
+    for (key, value) in iter {
+        writer.key(&key)?;
+        writer.value(&value)?;
+    }
+    writer.end()?;
+    writer.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::serialize::NonFiniteFloats;
+    use crate::to_string;
+    use std::collections::HashMap;
+
+    type TestResult = Result<(), SerializeError>;
+
+    fn output(writer: JsonWriter<Vec<u8>>) -> Result<String, SerializeError> {
+        Ok(String::from_utf8(writer.finish()?).unwrap())
+    }
+
+    fn invalid(result: Result<(), SerializeError>) -> &'static str {
+        match result {
+            Err(SerializeError::InvalidStructure(message)) => message,
+            result => panic!("{:?}", result),
+        }
+    }
+
+    #[test]
+    fn write_scalars() -> TestResult {
+        let mut writer = JsonWriter::new(Vec::new());
+        writer.string("a\"b")?;
+        assert_eq!(output(writer)?, "\"a\\\"b\"");
+
+        let mut writer = JsonWriter::new(Vec::new());
+        writer.null()?;
+        assert_eq!(output(writer)?, "null");
+        Ok(())
+    }
+
+    #[test]
+    fn write_empty_containers() -> TestResult {
+        let mut writer = JsonWriter::new(Vec::new());
+        writer.begin_array()?;
+        writer.begin_object()?;
+        writer.end()?;
+        writer.begin_array()?;
+        writer.end()?;
+        writer.end()?;
+        assert_eq!(output(writer)?, "[{},[]]");
+        Ok(())
+    }
+
+    #[test]
+    fn golden_output_matches_to_string() -> TestResult {
+        let value = Value::Array(vec![
+            Value::Object(HashMap::from([(
+                "value".to_string(),
+                Value::String("New".to_string()),
+            )])),
+            Value::Number(Number::Float(2.5)),
+            Value::Bool(true),
+            Value::Null,
+        ]);
+
+        let mut writer = JsonWriter::new(Vec::new());
+        writer.begin_array()?;
+        writer.begin_object()?;
+        writer.key("value")?;
+        writer.string("New")?;
+        writer.end()?;
+        writer.number(&Number::Float(2.5))?;
+        writer.bool(true)?;
+        writer.null()?;
+        writer.end()?;
+        assert_eq!(output(writer)?, to_string(&value)?);
+        Ok(())
+    }
+
+    #[test]
+    fn pretty_output_matches_serialize_with() -> TestResult {
+        let value = Value::Object(HashMap::from([(
+            "rows".to_string(),
+            Value::Array(vec![
+                Value::Array(vec![Value::Null]),
+                Value::Object(HashMap::new()),
+            ]),
+        )]));
+        let options = SerializeOptions::pretty(2).trailing_newline(true);
+
+        let mut writer = JsonWriter::with_options(Vec::new(), options.clone());
+        writer.begin_object()?;
+        writer.key("rows")?;
+        writer.begin_array()?;
+        writer.value(&Value::Array(vec![Value::Null]))?;
+        writer.begin_object()?;
+        writer.end()?;
+        writer.end()?;
+        writer.end()?;
+        assert_eq!(output(writer)?, value.serialize_with(&options)?);
+        Ok(())
+    }
+
+    #[test]
+    fn mix_values_and_streamed_parts() -> TestResult {
+        let mut writer = JsonWriter::new(Vec::new());
+        writer.begin_object()?;
+        writer.key("head")?;
+        writer.value(&Value::Array(vec![Value::Bool(false)]))?;
+        writer.key("rows")?;
+        writer.begin_array()?;
+        for i in 0..3 {
+            writer.number(&Number::PositiveInteger(i))?;
+        }
+        writer.end()?;
+        writer.end()?;
+        assert_eq!(output(writer)?, "{\"head\":[false],\"rows\":[0,1,2]}");
+        Ok(())
+    }
+
+    #[test]
+    fn two_keys_in_a_row() -> TestResult {
+        let mut writer = JsonWriter::new(Vec::new());
+        writer.begin_object()?;
+        writer.key("a")?;
+        assert_eq!(invalid(writer.key("b")), "a key was written after a key");
+        Ok(())
+    }
+
+    #[test]
+    fn misuse() -> TestResult {
+        let mut writer = JsonWriter::new(Vec::new());
+        assert_eq!(invalid(writer.end()), "no object or array to end");
+        assert_eq!(
+            invalid(writer.key("a")),
+            "a key was written outside of an object"
+        );
+
+        writer.begin_object()?;
+        assert_eq!(
+            invalid(writer.null()),
+            "a value was written in an object without a key"
+        );
+        writer.key("a")?;
+        assert_eq!(invalid(writer.end()), "an object was ended after a key");
+        writer.null()?;
+        writer.end()?;
+        assert_eq!(invalid(writer.null()), "the json value is already complete");
+        Ok(())
+    }
+
+    #[test]
+    fn finish_incomplete_json() -> TestResult {
+        let writer = JsonWriter::new(Vec::new());
+        assert!(matches!(
+            writer.finish(),
+            Err(SerializeError::InvalidStructure(_))
+        ));
+
+        let mut writer = JsonWriter::new(Vec::new());
+        writer.begin_array()?;
+        assert!(matches!(
+            writer.finish(),
+            Err(SerializeError::InvalidStructure(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn non_finite_float_pointer() -> TestResult {
+        let mut writer = JsonWriter::new(Vec::new());
+        writer.begin_object()?;
+        writer.key("a")?;
+        writer.begin_array()?;
+        writer.null()?;
+        match writer.number(&Number::Float(f64::NAN)) {
+            Err(SerializeError::NonFiniteFloat { pointer }) => assert_eq!(pointer, "/a/1"),
+            result => panic!("{:?}", result),
+        }
+
+        let options = SerializeOptions::new().non_finite_floats(NonFiniteFloats::Null);
+        let mut writer = JsonWriter::with_options(Vec::new(), options);
+        writer.number(&Number::Float(f64::NAN))?;
+        assert_eq!(output(writer)?, "null");
+        Ok(())
+    }
+
+    #[test]
+    fn empty_iter() -> TestResult {
+        assert_eq!(to_string_from_iter(std::iter::empty())?, "[]");
+        assert_eq!(to_string_from_members(std::iter::empty())?, "{}");
+        Ok(())
+    }
+
+    #[test]
+    fn iter_matches_to_string() -> TestResult {
+        let values = vec![
+            Value::String("a".to_string()),
+            Value::Array(vec![Value::Null]),
+            Value::Bool(false),
+        ];
+        assert_eq!(
+            to_string_from_iter(values.clone())?,
+            to_string(&Value::Array(values))?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn members_in_order() -> TestResult {
+        let members =
+            (0..3).map(|i| (format!("k{}", i), Value::Number(Number::PositiveInteger(i))));
+        assert_eq!(
+            to_string_from_members(members)?,
+            "{\"k0\":0,\"k1\":1,\"k2\":2}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn million_elements_from_range() -> TestResult {
+        let json =
+            to_string_from_iter((0..1_000_000).map(|i| Value::Number(Number::PositiveInteger(i))))?;
+        assert!(json.starts_with("[0,1,2,"));
+        assert!(json.ends_with(",999998,999999]"));
+        Ok(())
+    }
+
+    #[test]
+    fn iter_to_writer() -> TestResult {
+        let output = to_writer_from_iter(Vec::new(), vec![Value::Null, Value::Bool(true)])?;
+        assert_eq!(output, b"[null,true]");
+        Ok(())
+    }
+
+    #[test]
+    fn write_raw_values() -> TestResult {
+        let raw = RawValue::from_string("[1, {\"a\": null}]".to_string()).unwrap();
+        let mut writer = JsonWriter::new(Vec::new());
+        writer.begin_object()?;
+        writer.key("cached")?;
+        writer.raw(&raw)?;
+        writer.key("fresh")?;
+        writer.bool(false)?;
+        writer.end()?;
+        let json = output(writer)?;
+        assert_eq!(json, "{\"cached\":[1, {\"a\": null}],\"fresh\":false}");
+        assert!(crate::minify(&json).is_ok());
+        Ok(())
+    }
+}